@@ -0,0 +1,19 @@
+local N = 1000
+local STEPS = 1000000
+
+local first = { id = 0 }
+local last = first
+for i = 1, N - 1 do
+    local node = { id = i }
+    last.next = node
+    last = node
+end
+last.next = first
+
+local sum = 0
+local node = first
+for _ = 1, STEPS do
+    sum = sum + node.id
+    node = node.next
+end
+print(sum)
@@ -0,0 +1,17 @@
+N = 1000
+STEPS = 1000000
+
+first = {"id": 0}
+last = first
+for i in range(1, N):
+    node = {"id": i}
+    last["next"] = node
+    last = node
+last["next"] = first
+
+sum = 0
+node = first
+for _ in range(STEPS):
+    sum += node["id"]
+    node = node["next"]
+print(sum)
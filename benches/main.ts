@@ -17,8 +17,26 @@ const info = await Promise.all([
   load_benchmark_info("fibonacci"),
   load_benchmark_info("mandelbrot"),
   load_benchmark_info("nbody"),
+  load_benchmark_info("shared_refs"),
 ]);
 
+// Set `LICO_BASELINE` to the path of another `lico` build (e.g. the one before a VM change) to
+// compare it with the current `lico`.
+const lico_baseline = Deno.env.get("LICO_BASELINE");
+if (lico_baseline !== undefined) {
+  for (const i of info) {
+    const lico = i.benches.find((bench) => bench.name === "Lico");
+    if (lico !== undefined) {
+      i.benches.push({
+        ...lico,
+        name: "Lico (baseline)",
+        is_base: false,
+        command: `${lico_baseline} run`,
+      });
+    }
+  }
+}
+
 await Promise.all(info.map(async (info) => {
   info.benches.sort(() => 0.5 - Math.random()); // shuffle
 
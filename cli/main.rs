@@ -10,13 +10,26 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     /// Run
-    Run { file: std::path::PathBuf },
+    Run {
+        file: std::path::PathBuf,
+        /// Maximum depth of the nested function calls
+        #[arg(long, default_value_t = run::DEFAULT_MAX_CALL_DEPTH)]
+        max_call_depth: usize,
+        /// Arguments passed to the script as `os.args`
+        #[arg(last = true)]
+        args: Vec<String>,
+    },
 }
 
 fn main() {
     let cli = Cli::parse();
 
-    match &cli.command {
-        Commands::Run { file } => run::start(file),
-    }
+    let code = match &cli.command {
+        Commands::Run {
+            file,
+            max_call_depth,
+            args,
+        } => run::start(file, *max_call_depth, args),
+    };
+    std::process::exit(code);
 }
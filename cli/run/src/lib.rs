@@ -1,83 +1,85 @@
-use lico_core::*;
+use lico_core::{
+    foundation::syntax::{
+        ast::{AstNode, Program},
+        SyntaxNode, TextRange,
+    },
+    *,
+};
 use std::path::PathBuf;
 
-pub fn start(file: &PathBuf) {
-    let buf = std::fs::read_to_string(file).unwrap();
-    let buf_str = buf.as_str();
+pub use lico_core::vm::DEFAULT_MAX_CALL_DEPTH;
 
-    let (tokens, err) = lexer::parse(buf_str);
-    if !err.is_empty() {
-        for e in err {
-            println!("{e:?}");
+/// Runs the script and returns the exit code of the process.
+pub fn start(file: &PathBuf, max_call_depth: usize, args: &[String]) -> i32 {
+    let source = match std::fs::read_to_string(file) {
+        Ok(source) => source,
+        Err(err) => {
+            eprintln!("Cannot read {}: {}", file.display(), err);
+            return 1;
         }
-        return;
-    }
+    };
 
-    let (tree, err) = parser::parse(&tokens);
-    if !err.is_empty() {
-        for e in err {
-            println!("{e:?}");
+    let (green, errors) = parser::parse(&source, lexer::tokenize(&source));
+    if !errors.is_empty() {
+        for error in errors {
+            report(
+                &source,
+                "Syntax error",
+                error.message(),
+                Some(error.range()),
+            );
         }
-        return;
+        return 1;
     }
 
-    let code = match compiler::compile(&tree) {
-        Ok(x) => x,
-        Err(e) => {
-            println!("Compilation error: {:?}", e);
-            let (start, end) = match get_line_column_range(buf_str, e.span.to_range()) {
-                Some(x) => x,
-                None => {
-                    println!("Invalid span");
-                    return;
-                }
-            };
-            println!("Positon: {}:{} ~ {}:{}", start.0, start.1, end.0, end.1);
-            return;
-        }
-    };
-
-    let mut runtime = vm::runtime::Runtime::new();
-    vm::execute(&code, &mut runtime).unwrap();
-}
-
-fn get_line_column_range(
-    source: &str,
-    span: std::ops::Range<u32>,
-) -> Option<((u32, u32), (u32, u32))> {
-    let source_len = {
-        let len = source.chars().count();
-        if len > u32::MAX as usize {
-            return None;
+    let program = Program::cast(SyntaxNode::new_root(green)).unwrap();
+    let (module, errors, warnings) = compiler::lower_ast(program);
+    for warning in warnings {
+        report(&source, "Warning", warning.message(), Some(warning.range()));
+    }
+    if !errors.is_empty() {
+        for error in errors {
+            report(
+                &source,
+                "Syntax error",
+                error.message(),
+                Some(error.range()),
+            );
         }
-        len as u32
-    };
-
-    // Return None if the start and end positions of the range are outside the document range
-    if span.start >= source_len || span.end > source_len || span.start > span.end {
-        return None;
+        return 1;
     }
 
-    let line_start_offsets = {
-        let mut offsets = vec![0];
-        for (i, c) in source.char_indices() {
-            if c == '\n' {
-                offsets.push(i + 1);
+    let module = compiler::compile(&compiler::optimize(module));
+    vm::set_args(args.iter().cloned());
+    match vm::execute_with(&module, &vm::Options { max_call_depth }) {
+        Ok(_) => 0,
+        Err(vm::Interrupt::Exit(code)) => code,
+        Err(vm::Interrupt::Exception) => {
+            let mut log = vm::EXCEPTION_LOG.lock().unwrap();
+            for exception in log.read_all() {
+                report(&source, "Exception", exception.message(), exception.range());
             }
+            1
         }
-        offsets
-    };
+    }
+}
 
-    let calc_line_column = |offset: u32| {
-        let line = line_start_offsets
-            .iter()
-            .position(|&line_start_offset| line_start_offset as u32 > offset)
-            .unwrap_or(line_start_offsets.len() - 1);
-        let column = offset - line_start_offsets[line - 1] as u32;
-        (line as u32, column)
-    };
+fn report(source: &str, kind: &str, message: &str, range: Option<TextRange>) {
+    match range {
+        Some(range) => {
+            let (line, column) = line_column(source, range.start().into());
+            eprintln!("{} at {}:{}: {}", kind, line, column, message);
+        }
+        None => eprintln!("{}: {}", kind, message),
+    }
+}
 
-    let start = calc_line_column(span.start);
-    let end = calc_line_column(span.end);
-    Some((start, end))
+/// Converts the byte offset to the 1-based line and column (in characters).
+fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    let offset = offset.min(source.len());
+    let before = &source[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let column = before[line_start..].chars().count() + 1;
+    (line, column)
 }
@@ -1,6 +1,6 @@
 use foundation::{
-    il, ir,
-    object::{Object, RustFunction},
+    il::{self, Reg},
+    ir,
 };
 
 mod context;
@@ -12,6 +12,8 @@ use fragment::*;
 mod icodesource;
 use icodesource::*;
 
+mod peephole;
+
 mod compile_utils;
 
 mod compilable_impl_effect;
@@ -23,44 +25,52 @@ trait Compilable<'node, 'src: 'node> {
     fn compile(&'node self, fragment: &mut Fragment, ctx: &mut Context<'src>);
 }
 
+trait CompilableTo<'node, 'src: 'node> {
+    /// Compiles the value so that its result is stored to `dst`.
+    fn compile_to(&'node self, dst: Reg, fragment: &mut Fragment, ctx: &mut Context<'src>);
+}
+
 // TODO: The name of the default capture names are taken as an argument to `compile`. (Not defined here).
-const DEFAULT_FUNCTIONS: [(&str, RustFunction); 2] = [
-    (
-        "print",
-        RustFunction::new(1, |mut args| {
-            print!("{}", args.next().unwrap());
-            Ok(Object::Nil)
-        }),
-    ),
-    (
-        "println",
-        RustFunction::new(1, |mut args| {
-            println!("{}", args.next().unwrap());
-            Ok(Object::Nil)
-        }),
-    ),
+// The values are provided by the runtime (`vm::stdlib`), so keep this list in sync with it.
+const DEFAULT_GLOBALS: [&str; 19] = [
+    "print",
+    "println",
+    "type",
+    "int",
+    "float",
+    "str",
+    "bool",
+    "tostring",
+    "callable",
+    "weak",
+    "weak_table",
+    "io",
+    "fs",
+    "json",
+    "math",
+    "os",
+    "random",
+    "time",
+    "gc",
 ];
 
 pub fn compile(module: &ir::Module) -> il::Module {
-    let capture_db = database::FunctionCapture::build_with(
-        module,
-        DEFAULT_FUNCTIONS.iter().map(|(name, _)| *name),
-    );
+    let capture_db = database::FunctionCapture::build_with(module, DEFAULT_GLOBALS.iter().copied());
     let mut ctx = Context::new(module.strage(), &capture_db);
-    let mut default_rfns = Vec::new();
-    for (name, f) in DEFAULT_FUNCTIONS.iter() {
+    let mut default_globals = Vec::new();
+    for name in DEFAULT_GLOBALS.iter() {
         if capture_db.get_capture(module).contains(name) {
             ctx.add_local(name);
-            default_rfns.push((*name, *f));
+            default_globals.push(*name);
         }
     }
     let mut fragment = Fragment::new();
     fragment.append_compile(module.effects(), &mut ctx);
-    fragment.append_many([ICodeSource::LoadNilObject, ICodeSource::Leave]);
+    compile_utils::append_leave_nil(&mut fragment, &mut ctx);
     let (codes, infos) = ctx.finish_with(fragment);
     il::Module::new(
         il::Executable::new(codes),
-        default_rfns.into_boxed_slice(),
+        default_globals.into_boxed_slice(),
         infos,
     )
 }
@@ -1,5 +1,5 @@
 use super::*;
-use foundation::object::UString;
+use foundation::object::{MethodName, UString};
 
 impl<'node, 'src: 'node> Compilable<'node, 'src> for ir::EffectsKey {
     fn compile(&'node self, fragment: &mut Fragment, ctx: &mut Context<'src>) {
@@ -25,23 +25,32 @@ fn compile<'node, 'src: 'node>(
 
     match effect {
         Effect::MakeLocal { name, value } => {
-            fragment.append_compile(value, ctx).append(StoreNewLocal);
+            let local = ctx.alloc_reg();
+            fragment.append_compile_to(value, local, ctx);
             let name_str = ctx.strage.get(name).unwrap().1.text();
-            ctx.add_local(name_str);
+            ctx.bind_local(name_str, local);
         }
 
         Effect::MakeFunc { name, func } => {
             let name_str = ctx.strage.get(name).unwrap().1.text();
-            compile_utils::compile_function(func, fragment, ctx);
-            fragment.append(StoreNewLocal);
-            ctx.add_local(name_str);
+            let local = ctx.alloc_reg();
+            compile_utils::compile_function(func, local, fragment, ctx);
+            ctx.bind_local(name_str, local);
         }
 
         Effect::SetLocal { local, value } => {
-            fragment.append_compile(value, ctx);
             let name_str = ctx.strage.get(local).unwrap().1.text();
-            let local_id = ctx.resolve_local(name_str);
-            fragment.append_many([StoreLocal(local_id)]);
+            let local = ctx.resolve_local(name_str);
+            let (_, value) = ctx.strage.get(value).unwrap();
+            if compile_utils::can_compile_to_local(value) {
+                fragment.append_compile_to(&value, local, ctx);
+            } else {
+                let temp = ctx.alloc_reg();
+                fragment
+                    .append_compile_to(&value, temp, ctx)
+                    .append(Move(local, temp.take()));
+                ctx.free_reg(temp);
+            }
         }
 
         Effect::SetIndex {
@@ -49,12 +58,14 @@ fn compile<'node, 'src: 'node>(
             index,
             value,
         } => {
+            let mark = ctx.next_reg();
+            let (_, value) = ctx.strage.get(value).unwrap();
+            let (_, target) = ctx.strage.get(target).unwrap();
             let (index_syntax, index) = ctx.strage.get(index).unwrap();
-            fragment
-                .append_compile(value, ctx)
-                .append_compile(target, ctx)
-                .append_compile(&index, ctx)
-                .append(SetItem(index_syntax.text_range()));
+            let [value, container, key] =
+                compile_utils::compile_operands([value, target, index], fragment, ctx);
+            fragment.append(SetItem(container, key, value, index_syntax.text_range()));
+            ctx.free_reg(mark);
         }
 
         Effect::SetField {
@@ -62,36 +73,48 @@ fn compile<'node, 'src: 'node>(
             field,
             value,
         } => {
+            let mark = ctx.next_reg();
             let (field_syntax, field_string) = ctx.strage.get(field).unwrap();
-            fragment
-                .append_compile(value, ctx)
-                .append_compile(target, ctx)
-                .append_many([
-                    LoadStringObject(UString::from(field_string.clone())),
-                    SetItem(field_syntax.text_range()),
-                ]);
+            let (_, value) = ctx.strage.get(value).unwrap();
+            let (_, target) = ctx.strage.get(target).unwrap();
+            let [value, container] =
+                compile_utils::compile_operands([value, target], fragment, ctx);
+            let key = ctx.alloc_reg();
+            fragment.append_many([
+                LoadStringObject(key, UString::intern(field_string)),
+                SetItem(container, key.take(), value, field_syntax.text_range()),
+            ]);
+            ctx.free_reg(mark);
         }
 
+        // `func t.a.b()`: the function is set to `t.a` with the key `b`.
         Effect::SetFieldFunc { table, path, func } => {
             let table_symbol = ctx.strage.get(table).unwrap().1;
             let path_len = path.len();
-            let path_iter = ctx.strage.get(path);
 
-            compile_utils::compile_function(func, fragment, ctx);
-            fragment
-                .append(LoadLocal(ctx.resolve_local(table_symbol.text())))
-                .append_many(path_iter.enumerate().flat_map(|(i, (syntax, field))| {
-                    [
-                        LoadStringObject(UString::from(field.clone())),
-                        if i == path_len - 1 {
-                            GetItem(syntax.text_range())
-                        } else {
-                            SetItem(syntax.text_range())
-                        },
-                    ]
-                }));
+            let value = ctx.alloc_reg();
+            compile_utils::compile_function(func, value, fragment, ctx);
+            let mut container = ctx.resolve_local(table_symbol.text());
+            let temp = ctx.alloc_reg();
+            let key = ctx.alloc_reg();
+            for (i, (syntax, field)) in ctx.strage.get(path).enumerate() {
+                fragment.append(LoadStringObject(key, UString::intern(field)));
+                if i == path_len - 1 {
+                    fragment.append(SetItem(
+                        container,
+                        key.take(),
+                        value.take(),
+                        syntax.text_range(),
+                    ));
+                } else {
+                    fragment.append(GetItem(temp, container, key.take(), syntax.text_range()));
+                    container = temp.take();
+                }
+            }
+            ctx.free_reg(value);
         }
 
+        // `func t.a->m()`: the method `m` is set to `t.a`.
         Effect::SetMethod {
             table,
             path,
@@ -99,28 +122,33 @@ fn compile<'node, 'src: 'node>(
             func,
         } => {
             let table_symbol = ctx.strage.get(table).unwrap().1;
-            let path_iter = ctx.strage.get(path);
             let (name_syntax, name_string) = ctx.strage.get(name).unwrap();
 
-            compile_utils::compile_function(func, fragment, ctx);
-            fragment
-                .append(LoadLocal(ctx.resolve_local(table_symbol.text())))
-                .append_many(path_iter.flat_map(|(syntax, field)| {
-                    [
-                        LoadStringObject(UString::from(field.clone())),
-                        GetItem(syntax.text_range()),
-                    ]
-                }))
-                .append(SetMethod(
-                    UString::from(name_string.clone()),
-                    name_syntax.text_range(),
-                ));
+            let value = ctx.alloc_reg();
+            compile_utils::compile_function(func, value, fragment, ctx);
+            let mut container = ctx.resolve_local(table_symbol.text());
+            let temp = ctx.alloc_reg();
+            let key = ctx.alloc_reg();
+            for (syntax, field) in ctx.strage.get(path) {
+                fragment.append_many([
+                    LoadStringObject(key, UString::intern(field)),
+                    GetItem(temp, container, key.take(), syntax.text_range()),
+                ]);
+                container = temp.take();
+            }
+            fragment.append(SetMethod(
+                container,
+                value.take(),
+                MethodName::new(name_string),
+                name_syntax.text_range(),
+            ));
+            ctx.free_reg(value);
         }
 
-        // 0: eval           [condition]
-        // 1: jump_if_false  4
+        // 0: eval           cond = [condition]
+        // 1: jump_if_false  cond, 4      (cond, 3 if [else] is empty)
         // 2: eval           [then]
-        // 3: jump           5
+        // 3: jump           5            (omitted if [else] is empty)
         // 4: eval           [else]
         // 5: ...
         Effect::Branch {
@@ -128,11 +156,14 @@ fn compile<'node, 'src: 'node>(
             then,
             else_,
         } => {
-            fragment.append_compile(condition, ctx);
+            let mark = ctx.next_reg();
+            let (_, condition) = ctx.strage.get(condition).unwrap();
+            let condition = compile_utils::compile_operand(condition, fragment, ctx);
+            ctx.free_reg(mark);
             let (then_fragment, then_len) = {
                 let m = ctx.start_block();
                 let mut fragment = Fragment::with_compile(then, ctx);
-                fragment.append(DropLocal(ctx.get_block_local_count()));
+                compile_utils::append_drop_block_local(&mut fragment, ctx);
                 m.finish(ctx);
                 let len = fragment.len() as isize;
                 (fragment, len)
@@ -140,81 +171,101 @@ fn compile<'node, 'src: 'node>(
             let (else_fragment, else_len) = {
                 let m = ctx.start_block();
                 let mut fragment = Fragment::with_compile(else_, ctx);
-                fragment.append(DropLocal(ctx.get_block_local_count()));
+                compile_utils::append_drop_block_local(&mut fragment, ctx);
                 m.finish(ctx);
                 let len = fragment.len() as isize;
                 (fragment, len)
             };
-            fragment
-                .append(JumpIfFalse(then_len + 2))
-                .append_fragment(then_fragment)
-                .append(Jump(else_len + 1))
-                .append_fragment(else_fragment);
+            if else_len == 0 {
+                fragment
+                    .append(JumpIfFalse(condition, then_len + 1))
+                    .append_fragment(then_fragment);
+            } else {
+                fragment
+                    .append(JumpIfFalse(condition, then_len + 2))
+                    .append_fragment(then_fragment)
+                    .append(Jump(else_len + 1))
+                    .append_fragment(else_fragment);
+            }
         }
 
-        //            0: make_local    <>iter = [iterable]->__get_iterator()
-        //            1: make_local    [variable] = nil
-        // (continue) 2: eval          <>iter->__move_next()
-        //            3: jump_if_false 7
-        //            4: set_local     [variable] = <>iter->__current()
-        //            5: eval          [effects]
-        //            6: jump          2
-        //    (break) 7: delete        <>iter, [variable]
-        //            8: ...
+        //            0: eval          <>iter = [iterable]
+        //            1: get_iter      <>iter = <>iter->__get_iterator()
+        //            2: load_nil      [variable]
+        // (continue) 3: move_next     next = <>iter->__move_next()
+        //            4: jump_if_false next, 8
+        //            5: current       [variable] = <>iter->__current()
+        //            6: eval          [effects]
+        //            7: jump          3
+        //    (break) 8: close_upvalues <>iter, [variable]    (if [variable] may be captured)
+        //            9: drop_local    <>iter, [variable]
+        //           10: ...
         Effect::LoopFor {
             variable,
             iterable,
             effects,
         } => {
-            fragment.append_compile(iterable, ctx);
+            let iter = ctx.alloc_reg();
+            fragment.append_compile_to(iterable, iter, ctx);
+            ctx.bind_local("<>iter", iter);
+            let variable = ctx.add_local(ctx.strage.get(variable).unwrap().1.text());
             let loop_fragment = {
-                let iter_id = ctx.add_local("<>iter");
-                let variable_id = ctx.add_local(ctx.strage.get(variable).unwrap().1.text());
-
                 let m = ctx.start_loop();
+                // The result of `__move_next` is consumed before the effects, so they share the
+                // register.
+                let next = ctx.alloc_reg();
+                ctx.free_reg(next);
                 let (effects_fragment, effects_len) = {
                     let m = ctx.start_block();
                     let mut fragment = Fragment::with_compile(effects, ctx);
-                    fragment.append(DropLocal(ctx.get_block_local_count()));
+                    compile_utils::append_drop_block_local(&mut fragment, ctx);
                     m.finish(ctx);
                     let len = fragment.len() as isize;
                     (fragment, len)
                 };
+                let mut drop_fragment = Fragment::new();
+                compile_utils::append_drop_local(iter, &mut drop_fragment, ctx); // 8, 9
+                let drop_len = drop_fragment.len() as isize;
                 let mut fragment = Fragment::new();
                 fragment
                     .append_many([
-                        GetIter,                      // 0
-                        StoreNewLocal,                // |
-                        LoadNilObject,                // 1
-                        StoreNewLocal,                // |
-                        LoadLocal(iter_id),           // 2
-                        IterMoveNext,                 // |
-                        JumpIfFalse(effects_len + 2), // 3
-                        LoadLocal(iter_id),           // 4
-                        IterCurrent,                  // |
-                        StoreLocal(variable_id),      // |
+                        GetIter(iter, iter.take()),                // 1
+                        LoadNilObject(variable),                   // 2
+                        IterMoveNext(next, iter),                  // 3
+                        JumpIfFalse(next.take(), effects_len + 3), // 4
+                        IterCurrent(variable, iter),               // 5
                     ])
-                    .append_fragment(effects_fragment) // 5
-                    .append_many([
-                        Jump(-effects_len - 6), // 6
-                        DropLocal(2),           // 7
-                    ]);
-                fragment.patch_backward_jump(4); // to 2
-                fragment.patch_forward_jump(-1); // to 7
+                    .append_fragment(effects_fragment) // 6
+                    .append(Jump(-effects_len - 3)) // 7
+                    .append_fragment(drop_fragment);
+                fragment.patch_backward_jump(2); // to 3
+                fragment.patch_forward_jump(1 - drop_len); // to 8
                 m.finish(ctx);
-                ctx.drop_local(2);
                 fragment
             };
+            ctx.drop_local(2);
             fragment.append_fragment(loop_fragment);
         }
 
+        // 0: eval           cond = [condition]
+        // 1: jump_if_false  cond, 4
+        // 2: eval           [effects]
+        // 3: jump           0
+        // 4: ...
         Effect::LoopWhile { condition, effects } => {
             let while_fragment = {
-                let cond_fragment = Fragment::with_compile(condition, ctx);
+                let mut cond_fragment = Fragment::new();
+                let mark = ctx.next_reg();
+                let (_, condition) = ctx.strage.get(condition).unwrap();
+                let condition = compile_utils::compile_operand(condition, &mut cond_fragment, ctx);
+                ctx.free_reg(mark);
                 let cond_len = cond_fragment.len() as isize;
                 let (effects_fragment, effects_len) = {
                     let m = ctx.start_loop();
-                    let fragment = Fragment::with_compile(effects, ctx);
+                    let b = ctx.start_block();
+                    let mut fragment = Fragment::with_compile(effects, ctx);
+                    compile_utils::append_drop_block_local(&mut fragment, ctx);
+                    b.finish(ctx);
                     let len = fragment.len() as isize;
                     m.finish(ctx);
                     (fragment, len)
@@ -222,7 +273,7 @@ fn compile<'node, 'src: 'node>(
                 let mut fragment = Fragment::new();
                 fragment
                     .append_fragment(cond_fragment)
-                    .append(JumpIfFalse(effects_len + 2))
+                    .append(JumpIfFalse(condition, effects_len + 2))
                     .append_fragment(effects_fragment)
                     .append(Jump(-(effects_len + 1 + cond_len)));
                 fragment.patch_forward_jump(1);
@@ -234,39 +285,51 @@ fn compile<'node, 'src: 'node>(
 
         Effect::Scope { body } => {
             let m = ctx.start_block();
-            fragment
-                .append_compile(body, ctx)
-                .append(DropLocal(ctx.get_block_local_count()));
+            fragment.append_compile(body, ctx);
+            compile_utils::append_drop_block_local(fragment, ctx);
             m.finish(ctx);
         }
 
         Effect::Call { value, args } => {
+            let base = ctx.alloc_reg();
             let (calee_syntax, value) = ctx.strage.get(value).unwrap();
-            fragment.append_compile(&value, ctx);
+            fragment.append_compile_to(&value, base, ctx);
             assert!(
                 args.len() <= u8::MAX as usize,
                 "Number of arguments greater than u8::MAX is not supported."
             );
             let mut args_range = Vec::with_capacity(args.len());
             for (syntax, arg) in ctx.strage.get(args) {
-                fragment.append_compile(&arg, ctx);
+                let reg = ctx.alloc_reg();
+                fragment.append_compile_to(&arg, reg, ctx);
                 args_range.push(syntax.text_range());
             }
             fragment
                 .append(Call(
+                    base,
                     args.len() as u8,
                     calee_syntax.text_range(),
                     args_range.into_boxed_slice(),
                 ))
-                .append(Unload);
+                .append(DropLocal(base));
+            ctx.free_reg(base);
         }
 
-        Effect::MethodCall { table, name, args } => {
+        Effect::MethodCall {
+            table,
+            name,
+            args,
+            optional,
+        } => {
             let mut ranges = Vec::with_capacity(args.len() + 1);
 
+            let base = ctx.alloc_reg();
             let (table_syntax, table) = ctx.strage.get(table).unwrap();
             ranges.push(table_syntax.text_range());
-            fragment.append_compile(&table, ctx);
+            fragment.append_compile_to(&table, base, ctx);
+
+            // See `Value::MethodCall` for the layout of the optional call.
+            let mut call = Fragment::new();
 
             let (name_syntax, name_string) = ctx.strage.get(name).unwrap();
             ranges.push(name_syntax.text_range());
@@ -276,41 +339,81 @@ fn compile<'node, 'src: 'node>(
                 "Number of arguments greater than u8::MAX is not supported."
             );
             for (syntax, arg) in ctx.strage.get(args) {
-                fragment.append_compile(&arg, ctx);
+                let reg = ctx.alloc_reg();
+                call.append_compile_to(&arg, reg, ctx);
                 ranges.push(syntax.text_range());
             }
+            call.append(CallMethod(
+                base,
+                args.len() as u8,
+                MethodName::new(name_string),
+                ranges.into_boxed_slice(),
+            ));
 
-            fragment
-                .append(CallMethod(
-                    args.len() as u8,
-                    UString::from(name_string.clone()),
-                    ranges.into_boxed_slice(),
-                ))
-                .append(Unload);
+            if *optional {
+                fragment.append(JumpIfNil(base, call.len() as isize + 1));
+            }
+            fragment.append_fragment(call).append(DropLocal(base));
+            ctx.free_reg(base);
         }
 
         Effect::Return { value } => {
+            let mark = ctx.next_reg();
             match ctx.strage.get(value) {
-                Some((_, value)) => fragment.append_compile(&value, ctx),
-                None => fragment.append(LoadNilObject),
-            };
-            fragment.append(Leave);
+                // `return f(x)` reuses the frame for the callee. (See `ICode::TailCall`)
+                Some((_, ir::Value::Call { value, args })) => {
+                    let base = ctx.alloc_reg();
+                    let (calee_syntax, value) = ctx.strage.get(value).unwrap();
+                    fragment.append_compile_to(&value, base, ctx);
+                    assert!(
+                        args.len() <= u8::MAX as usize,
+                        "Number of arguments greater than u8::MAX is not supported."
+                    );
+                    let mut args_range = Vec::with_capacity(args.len());
+                    for (syntax, arg) in ctx.strage.get(args) {
+                        let reg = ctx.alloc_reg();
+                        fragment.append_compile_to(&arg, reg, ctx);
+                        args_range.push(syntax.text_range());
+                    }
+                    compile_utils::append_close_upvalues(Reg::new(0), fragment, ctx);
+                    fragment
+                        .append(TailCall(
+                            base,
+                            args.len() as u8,
+                            calee_syntax.text_range(),
+                            args_range.into_boxed_slice(),
+                        ))
+                        .append(Leave(base.take()));
+                }
+                Some((_, value)) => {
+                    let value = compile_utils::compile_operand(value, fragment, ctx);
+                    compile_utils::append_leave(value, fragment, ctx);
+                }
+                None => compile_utils::append_leave_nil(fragment, ctx),
+            }
+            ctx.free_reg(mark);
         }
 
         Effect::BreakLoop => {
-            fragment
-                .append(DropLocal(ctx.get_loop_local_count()))
-                .append_forward_jump();
+            if ctx.get_loop_local_count() > 0 {
+                compile_utils::append_drop_local(ctx.get_loop_base(), fragment, ctx);
+            }
+            fragment.append_forward_jump();
         }
 
         Effect::ContinueLoop => {
-            fragment
-                .append(DropLocal(ctx.get_loop_local_count()))
-                .append_backward_jump();
+            if ctx.get_loop_local_count() > 0 {
+                compile_utils::append_drop_local(ctx.get_loop_base(), fragment, ctx);
+            }
+            fragment.append_backward_jump();
         }
 
         Effect::NoEffectValue { value } => {
-            fragment.append_compile(value, ctx).append(Unload);
+            let reg = ctx.alloc_reg();
+            fragment
+                .append_compile_to(value, reg, ctx)
+                .append(DropLocal(reg));
+            ctx.free_reg(reg);
         }
     }
 }
@@ -1,24 +1,38 @@
-use foundation::object::UString;
+use foundation::object::{MethodName, UString};
 
 use super::*;
 
-impl<'node, 'src: 'node> Compilable<'node, 'src> for ir::ValueKey {
-    fn compile(&'node self, fragment: &mut Fragment, ctx: &mut Context<'src>) {
+impl<'node, 'src: 'node> CompilableTo<'node, 'src> for ir::ValueKey {
+    fn compile_to(&'node self, dst: Reg, fragment: &mut Fragment, ctx: &mut Context<'src>) {
         let Some((_, value)) = ctx.strage.get(self) else {
             panic!("[BUG] Missing value must be resolved at caller side");
         };
-        fragment.append_compile(&value, ctx);
+        fragment.append_compile_to(&value, dst, ctx);
     }
 }
 
-impl<'node, 'src: 'node> Compilable<'node, 'src> for &ir::Value {
-    fn compile(&'node self, fragment: &mut Fragment, ctx: &mut Context<'src>) {
-        compile(self, fragment, ctx);
+impl<'node, 'src: 'node> CompilableTo<'node, 'src> for &ir::Value {
+    fn compile_to(&'node self, dst: Reg, fragment: &mut Fragment, ctx: &mut Context<'src>) {
+        compile(self, dst, fragment, ctx);
     }
 }
 
+/// Compiles the tail of a block to `dst`, or loads nil if there is no tail.
+fn compile_tail<'src>(
+    tail: &ir::ValueKey,
+    dst: Reg,
+    fragment: &mut Fragment,
+    ctx: &mut Context<'src>,
+) {
+    match ctx.strage.get(tail) {
+        Some((_, tail)) => fragment.append_compile_to(&tail, dst, ctx),
+        None => fragment.append(ICodeSource::LoadNilObject(dst)),
+    };
+}
+
 fn compile<'node, 'src: 'node>(
     value: &ir::Value,
+    dst: Reg,
     fragment: &mut Fragment,
     ctx: &mut Context<'src>,
 ) {
@@ -26,11 +40,11 @@ fn compile<'node, 'src: 'node>(
     use ICodeSource::*;
 
     match value {
-        // 0: eval           [condition]
-        // 1: jump_if_false  4
-        // 2: eval           [then]
+        // 0: eval           cond = [condition]
+        // 1: jump_if_false  cond, 4
+        // 2: eval           dst = [then]
         // 3: jump           5
-        // 4: eval           [else]
+        // 4: eval           dst = [else]
         // 5: ...
         Value::Branch {
             condition,
@@ -39,15 +53,15 @@ fn compile<'node, 'src: 'node>(
             else_,
             else_tail,
         } => {
-            fragment.append_compile(condition, ctx);
+            let mark = ctx.next_reg();
+            let (_, condition) = ctx.strage.get(condition).unwrap();
+            let condition = compile_utils::compile_operand(condition, fragment, ctx);
+            ctx.free_reg(mark);
             let (then_fragment, then_len) = {
                 let m = ctx.start_block();
                 let mut fragment = Fragment::with_compile(then, ctx);
-                match ctx.strage.get(then_tail) {
-                    Some((_, tail)) => fragment.append_compile(&tail, ctx),
-                    None => fragment.append(LoadNilObject),
-                };
-                fragment.append(DropLocal(ctx.get_block_local_count()));
+                compile_tail(then_tail, dst, &mut fragment, ctx);
+                compile_utils::append_drop_block_local(&mut fragment, ctx);
                 m.finish(ctx);
                 let len = fragment.len() as isize;
                 (fragment, len)
@@ -55,144 +69,242 @@ fn compile<'node, 'src: 'node>(
             let (else_fragment, else_len) = {
                 let m = ctx.start_block();
                 let mut fragment = Fragment::with_compile(else_, ctx);
-                match ctx.strage.get(else_tail) {
-                    Some((_, tail)) => fragment.append_compile(&tail, ctx),
-                    None => fragment.append(LoadNilObject),
-                };
-                fragment.append(DropLocal(ctx.get_block_local_count()));
+                compile_tail(else_tail, dst, &mut fragment, ctx);
+                compile_utils::append_drop_block_local(&mut fragment, ctx);
                 m.finish(ctx);
                 let len = fragment.len() as isize;
                 (fragment, len)
             };
             fragment
-                .append(JumpIfFalse(then_len + 2))
+                .append(JumpIfFalse(condition, then_len + 2))
                 .append_fragment(then_fragment)
                 .append(Jump(else_len + 1))
                 .append_fragment(else_fragment);
         }
 
+        // 0: eval            @match = [value]
+        // 1: [arm]*
+        //      [test; jump_if_false (next arm)]*
+        //      [load]*                         (bindings)
+        //      eval            dst = [body]
+        //      drop_local      (bindings and body locals)
+        //      jump            (end)
+        // n: load_nil        dst               (no arm matched)
+        // end: drop_local    @match
+        Value::Match { value, arms } => {
+            let m = ctx.start_block();
+            let scrutinee = ctx.alloc_reg();
+            fragment.append_compile_to(value, scrutinee, ctx);
+            ctx.bind_local("@match", scrutinee);
+
+            let mut compiled_arms = Vec::with_capacity(arms.len());
+            for ir::MatchArm {
+                pattern,
+                body,
+                tail,
+            } in arms.iter()
+            {
+                // The result of the tests is consumed before the bindings, so they share the
+                // register.
+                let test = ctx.alloc_reg();
+                let mut tests = Vec::new();
+                compile_utils::compile_pattern_tests(pattern, scrutinee, test, &mut tests, ctx);
+                ctx.free_reg(test);
+                let m = ctx.start_block();
+                let mut success = Fragment::new();
+                compile_utils::compile_pattern_bindings(pattern, scrutinee, &mut success, ctx);
+                success.append_compile(body, ctx);
+                compile_tail(tail, dst, &mut success, ctx);
+                compile_utils::append_drop_block_local(&mut success, ctx);
+                m.finish(ctx);
+                compiled_arms.push((tests, test, success));
+            }
+
+            let tests_len = |tests: &[Fragment]| tests.iter().map(|t| t.len() + 1).sum::<usize>();
+            let mut rest_len = compiled_arms
+                .iter()
+                .map(|(tests, _, success)| tests_len(tests) + success.len() + 1)
+                .sum::<usize>();
+            for (tests, test, success) in compiled_arms {
+                let mut arm_rest_len = tests_len(&tests) + success.len() + 1;
+                rest_len -= arm_rest_len;
+                for t in tests {
+                    arm_rest_len -= t.len() + 1;
+                    fragment
+                        .append_fragment(t)
+                        .append(JumpIfFalse(test.take(), arm_rest_len as isize + 1));
+                }
+                fragment
+                    .append_fragment(success)
+                    .append(Jump(rest_len as isize + 2));
+            }
+            fragment
+                .append(LoadNilObject(dst))
+                .append(DropLocal(scrutinee));
+            m.finish(ctx);
+        }
+
         Value::Prefix { op, value } => {
-            fragment.append_compile(value, ctx).append(match op {
-                ir::PrefixOp::Plus(t) => Unp(t.text_range()),
-                ir::PrefixOp::Minus(t) => Unm(t.text_range()),
-                ir::PrefixOp::Not(t) => Not(t.text_range()),
-                ir::PrefixOp::BitNot(t) => BitNot(t.text_range()),
+            let mark = ctx.next_reg();
+            let (_, value) = ctx.strage.get(value).unwrap();
+            let src = compile_utils::compile_operand(value, fragment, ctx);
+            fragment.append(match op {
+                ir::PrefixOp::Plus(t) => Unp(dst, src, t.text_range()),
+                ir::PrefixOp::Minus(t) => Unm(dst, src, t.text_range()),
+                ir::PrefixOp::Not(t) => Not(dst, src, t.text_range()),
+                ir::PrefixOp::BitNot(t) => BitNot(dst, src, t.text_range()),
                 ir::PrefixOp::Missing => {
                     panic!("Missing prefix operator, this error must be resolved upstream.")
                 }
             });
+            ctx.free_reg(mark);
         }
 
         Value::Binary { op, lhs, rhs } => {
             match op {
-                //   0: eval lhs
-                //   1: jump_if_false 4
-                //   2: eval rhs
-                //   3: jump 5
-                //   4: push false
-                //   5: ...
-                ir::BinaryOp::And(_) => {
-                    let lhs_fragment = Fragment::with_compile(lhs, ctx);
-                    let rhs_fragment = Fragment::with_compile(rhs, ctx);
+                // `and`, `or` and `??` evaluate to one of their operands, not to a boolean.
+                //
+                //   0: eval lhs        dst = [lhs]
+                //   1: jump_if_false   dst, 3    (jump_if_true / jump_if_not_nil for `or` / `??`)
+                //   2: eval rhs        dst = [rhs]
+                //   3: ...
+                ir::BinaryOp::And(_) | ir::BinaryOp::Or(_) | ir::BinaryOp::NilCoalesce(_) => {
+                    let lhs_fragment = Fragment::with_compile_to(lhs, dst, ctx);
+                    let rhs_fragment = Fragment::with_compile_to(rhs, dst, ctx);
+                    let offset = rhs_fragment.len() as isize + 1;
                     fragment
                         .append_fragment(lhs_fragment)
-                        .append(JumpIfFalse(rhs_fragment.len() as isize + 2))
-                        .append_fragment(rhs_fragment)
-                        .append_many([Jump(2), LoadBoolObject(false)]);
-                    return;
-                }
-                //   0: eval lhs
-                //   1: jump_if_true 4
-                //   2: eval rhs
-                //   3: jump 5
-                //   4: push true
-                //   5: ...
-                ir::BinaryOp::Or(_) => {
-                    let lhs_fragment = Fragment::with_compile(lhs, ctx);
-                    let rhs_fragment = Fragment::with_compile(rhs, ctx);
-                    fragment
-                        .append_fragment(lhs_fragment)
-                        .append(JumpIfTrue(rhs_fragment.len() as isize + 2))
-                        .append_fragment(rhs_fragment)
-                        .append_many([Jump(2), LoadBoolObject(true)]);
+                        .append(match op {
+                            ir::BinaryOp::And(_) => JumpIfFalse(dst, offset),
+                            ir::BinaryOp::Or(_) => JumpIfTrue(dst, offset),
+                            _ => JumpIfNotNil(dst, offset),
+                        })
+                        .append_fragment(rhs_fragment);
                     return;
                 }
                 _ => {}
             }
-            fragment
-                .append_compile(lhs, ctx)
-                .append_compile(rhs, ctx)
-                .append(match op {
-                    ir::BinaryOp::Add(t) => Add(t.text_range()),
-                    ir::BinaryOp::Sub(t) => Sub(t.text_range()),
-                    ir::BinaryOp::Mul(t) => Mul(t.text_range()),
-                    ir::BinaryOp::Div(t) => Div(t.text_range()),
-                    ir::BinaryOp::Mod(t) => Mod(t.text_range()),
-                    ir::BinaryOp::Shl(t) => ShiftL(t.text_range()),
-                    ir::BinaryOp::Shr(t) => ShiftR(t.text_range()),
-                    ir::BinaryOp::Concat(t) => Concat(t.text_range()),
-                    ir::BinaryOp::Eq(t) => Eq(t.text_range()),
-                    ir::BinaryOp::Ne(t) => NotEq(t.text_range()),
-                    ir::BinaryOp::Lt(t) => Less(t.text_range()),
-                    ir::BinaryOp::Le(t) => LessEq(t.text_range()),
-                    ir::BinaryOp::Gt(t) => Greater(t.text_range()),
-                    ir::BinaryOp::Ge(t) => GreaterEq(t.text_range()),
-                    ir::BinaryOp::And(_) => unreachable!(),
-                    ir::BinaryOp::Or(_) => unreachable!(),
-                    ir::BinaryOp::BitAnd(t) => BitAnd(t.text_range()),
-                    ir::BinaryOp::BitOr(t) => BitOr(t.text_range()),
-                    ir::BinaryOp::BitXor(t) => BitXor(t.text_range()),
-                    ir::BinaryOp::Assign(_) => panic!(
-                        "Invalid binary operator `Assign`, this error must be resolved upstream."
-                    ),
-                    ir::BinaryOp::Missing => {
-                        panic!("Missing binary operator, this error must be resolved upstream.")
-                    }
-                });
+            let mark = ctx.next_reg();
+            let (_, lhs) = ctx.strage.get(lhs).unwrap();
+            let (_, rhs) = ctx.strage.get(rhs).unwrap();
+            let [l, r] = compile_utils::compile_operands([lhs, rhs], fragment, ctx);
+            fragment.append(match op {
+                ir::BinaryOp::Add(t) => Add(dst, l, r, t.text_range()),
+                ir::BinaryOp::Sub(t) => Sub(dst, l, r, t.text_range()),
+                ir::BinaryOp::Mul(t) => Mul(dst, l, r, t.text_range()),
+                ir::BinaryOp::Div(t) => Div(dst, l, r, t.text_range()),
+                ir::BinaryOp::Mod(t) => Mod(dst, l, r, t.text_range()),
+                ir::BinaryOp::Shl(t) => ShiftL(dst, l, r, t.text_range()),
+                ir::BinaryOp::Shr(t) => ShiftR(dst, l, r, t.text_range()),
+                ir::BinaryOp::Concat(t) => Concat(dst, l, r, t.text_range()),
+                ir::BinaryOp::Eq(t) => Eq(dst, l, r, t.text_range()),
+                ir::BinaryOp::Ne(t) => NotEq(dst, l, r, t.text_range()),
+                ir::BinaryOp::Lt(t) => Less(dst, l, r, t.text_range()),
+                ir::BinaryOp::Le(t) => LessEq(dst, l, r, t.text_range()),
+                ir::BinaryOp::Gt(t) => Greater(dst, l, r, t.text_range()),
+                ir::BinaryOp::Ge(t) => GreaterEq(dst, l, r, t.text_range()),
+                ir::BinaryOp::And(_) => unreachable!(),
+                ir::BinaryOp::Or(_) => unreachable!(),
+                ir::BinaryOp::NilCoalesce(_) => unreachable!(),
+                ir::BinaryOp::BitAnd(t) => BitAnd(dst, l, r, t.text_range()),
+                ir::BinaryOp::BitOr(t) => BitOr(dst, l, r, t.text_range()),
+                ir::BinaryOp::BitXor(t) => BitXor(dst, l, r, t.text_range()),
+                ir::BinaryOp::Assign(_) => panic!(
+                    "Invalid binary operator `Assign`, this error must be resolved upstream."
+                ),
+                ir::BinaryOp::Missing => {
+                    panic!("Missing binary operator, this error must be resolved upstream.")
+                }
+            });
+            ctx.free_reg(mark);
         }
 
+        // The callee and the arguments are placed at `base`, `base + 1`, ..., and the result is
+        // stored to `base`.
         Value::Call { value, args } => {
+            let base = compile_utils::start_call_base(dst, ctx);
             let (calee_syntax, value) = ctx.strage.get(value).unwrap();
-            fragment.append_compile(&value, ctx);
+            fragment.append_compile_to(&value, base, ctx);
             assert!(
                 args.len() <= u8::MAX as usize,
                 "Number of arguments greater than u8::MAX is not supported."
             );
             let mut args_range = Vec::with_capacity(args.len());
             for (syntax, arg) in ctx.strage.get(args) {
-                fragment.append_compile(&arg, ctx);
+                let reg = ctx.alloc_reg();
+                fragment.append_compile_to(&arg, reg, ctx);
                 args_range.push(syntax.text_range());
             }
             fragment.append(Call(
+                base,
                 args.len() as u8,
                 calee_syntax.text_range(),
                 args_range.into_boxed_slice(),
             ));
+            compile_utils::finish_call_base(dst, base, fragment, ctx);
         }
 
         Value::Index { value, index } => {
-            let (index_syntax, index_value) = ctx.strage.get(index).unwrap();
-            fragment
-                .append_compile(value, ctx)
-                .append_compile(&index_value, ctx)
-                .append(GetItem(index_syntax.text_range()));
+            let mark = ctx.next_reg();
+            let (_, value) = ctx.strage.get(value).unwrap();
+            let (index_syntax, index) = ctx.strage.get(index).unwrap();
+            let [container, key] = compile_utils::compile_operands([value, index], fragment, ctx);
+            fragment.append(GetItem(dst, container, key, index_syntax.text_range()));
+            ctx.free_reg(mark);
         }
 
-        Value::Field { value, name } => {
+        Value::Field {
+            value,
+            name,
+            optional,
+        } => {
             let (field_syntax, field_string) = ctx.strage.get(name).unwrap();
-            fragment
-                .append_compile(value, ctx)
-                .append(LoadStringObject(UString::from(field_string.clone())))
-                .append(GetItem(field_syntax.text_range()));
+            let field = UString::intern(field_string);
+            if *optional {
+                //   0: eval           dst = [value]
+                //   1: jump_if_nil    dst, 4
+                //   2: load_string    key = name
+                //   3: get_item       dst = dst[key]
+                //   4: ...
+                fragment.append_compile_to(value, dst, ctx);
+                let key = ctx.alloc_reg();
+                fragment.append_many([
+                    JumpIfNil(dst, 3),
+                    LoadStringObject(key, field),
+                    GetItem(dst, dst.take(), key.take(), field_syntax.text_range()),
+                ]);
+                ctx.free_reg(key);
+            } else {
+                let mark = ctx.next_reg();
+                let (_, value) = ctx.strage.get(value).unwrap();
+                let container = compile_utils::compile_operand(value, fragment, ctx);
+                let key = ctx.alloc_reg();
+                fragment.append_many([
+                    LoadStringObject(key, field),
+                    GetItem(dst, container, key.take(), field_syntax.text_range()),
+                ]);
+                ctx.free_reg(mark);
+            }
         }
 
-        Value::MethodCall { value, name, args } => {
+        // The receiver and the arguments are placed as in `Value::Call`.
+        Value::MethodCall {
+            value,
+            name,
+            args,
+            optional,
+        } => {
             let mut ranges = Vec::with_capacity(args.len() + 1);
 
+            let base = compile_utils::start_call_base(dst, ctx);
             let (value_syntax, value) = ctx.strage.get(value).unwrap();
-            fragment.append_compile(&value, ctx);
+            fragment.append_compile_to(&value, base, ctx);
             ranges.push(value_syntax.text_range());
 
+            // Arguments are evaluated after the receiver check, so `nil?->f(g())` does not call
+            // `g`. The call itself is made on a separate fragment to measure the jump offset.
+            let mut call = Fragment::new();
+
             let (name_syntax, name_string) = ctx.strage.get(name).unwrap();
             ranges.push(name_syntax.text_range());
 
@@ -201,85 +313,161 @@ fn compile<'node, 'src: 'node>(
                 "Number of arguments greater than u8::MAX is not supported."
             );
             for (syntax, arg) in ctx.strage.get(args) {
-                fragment.append_compile(&arg, ctx);
+                let reg = ctx.alloc_reg();
+                call.append_compile_to(&arg, reg, ctx);
                 ranges.push(syntax.text_range());
             }
-
-            fragment.append(CallMethod(
+            call.append(CallMethod(
+                base,
                 args.len() as u8,
-                UString::from(name_string.clone()),
+                MethodName::new(name_string),
                 ranges.into_boxed_slice(),
             ));
+
+            if *optional {
+                fragment.append(JumpIfNil(base, call.len() as isize + 1));
+            }
+            fragment.append_fragment(call);
+            compile_utils::finish_call_base(dst, base, fragment, ctx);
         }
 
         Value::Block { effects, tail } => {
             let m = ctx.start_block();
             fragment.append_compile(effects, ctx);
-            if let Some((_, tail)) = ctx.strage.get(tail) {
-                fragment.append_compile(&tail, ctx);
-            } else {
-                fragment.append(LoadNilObject);
-            }
+            compile_tail(tail, dst, fragment, ctx);
+            compile_utils::append_drop_block_local(fragment, ctx);
             m.finish(ctx);
         }
 
         Value::Local { name } => {
             let name_str = ctx.strage.get(name).unwrap().1.text();
-            let local_id = ctx.resolve_local(name_str);
-            fragment.append(LoadLocal(local_id));
+            let local = ctx.resolve_local(name_str);
+            if local != dst {
+                fragment.append(Move(dst, local));
+            }
         }
 
         Value::Int(x) => {
-            fragment.append(LoadIntObject(*x));
+            fragment.append(LoadIntObject(dst, *x));
         }
 
         Value::Float(x) => {
-            fragment.append(LoadFloatObject(*x));
+            fragment.append(LoadFloatObject(dst, *x));
         }
 
         Value::String(x) => {
-            fragment.append(LoadStringObject(x.clone()));
+            fragment.append(LoadStringObject(dst, x.clone()));
         }
 
         Value::Bool(x) => {
-            fragment.append(LoadBoolObject(*x));
+            fragment.append(LoadBoolObject(dst, *x));
         }
 
         Value::Nil => {
-            fragment.append(LoadNilObject);
+            fragment.append(LoadNilObject(dst));
         }
 
         Value::Function(func) => {
-            compile_utils::compile_function(func, fragment, ctx);
+            compile_utils::compile_function(func, dst, fragment, ctx);
         }
 
+        // The elements are placed at `base`, `base + 1`, ..., and the array is stored to `base`.
         Value::Array { elements } => {
-            for (_, element) in ctx.strage.get(elements) {
-                fragment.append_compile(&element, ctx);
+            let base = compile_utils::start_call_base(dst, ctx);
+            for (i, (_, element)) in ctx.strage.get(elements).enumerate() {
+                let reg = if i == 0 { base } else { ctx.alloc_reg() };
+                fragment.append_compile_to(&element, reg, ctx);
             }
-            fragment.append(MakeArray(elements.len()));
+            fragment.append(MakeArray(base, elements.len()));
+            compile_utils::finish_call_base(dst, base, fragment, ctx);
         }
 
+        // The keys and values are placed alternately from `base`, and the table is stored to
+        // `base`.
         Value::Table { fields } => {
+            let base = compile_utils::start_call_base(dst, ctx);
             let mut key_ranges = Vec::new();
-            for (key, value) in fields {
+            for (i, (key, value)) in fields.iter().enumerate() {
+                let key_reg = if i == 0 { base } else { ctx.alloc_reg() };
                 match key {
                     ir::TableKeyName::Value(v) => {
                         let Some((syntax, value)) = ctx.strage.get(v) else {
                             panic!("Missing table key, this error must be resolved upstream.");
                         };
-                        fragment.append_compile(&value, ctx);
+                        fragment.append_compile_to(&value, key_reg, ctx);
                         key_ranges.push(Some(syntax.text_range()));
                     }
                     ir::TableKeyName::String(s) => {
                         let key_str = ctx.strage.get(s).unwrap().0.text();
-                        fragment.append(LoadStringObject(UString::from(key_str)));
+                        fragment.append(LoadStringObject(key_reg, UString::intern(key_str)));
                         key_ranges.push(None);
                     }
                 }
-                fragment.append_compile(value, ctx);
+                let value_reg = ctx.alloc_reg();
+                fragment.append_compile_to(value, value_reg, ctx);
+            }
+            fragment.append(MakeTable(base, fields.len(), key_ranges.into_boxed_slice()));
+            compile_utils::finish_call_base(dst, base, fragment, ctx);
+        }
+
+        // 0: eval       dst = [part 0]
+        // 1: to_string  dst            (or format with spec)
+        // 2: eval       part = [part 1]
+        // 3: to_string  part           (or format with spec)
+        // 4: concat     dst = dst .. part
+        // 5: ...
+        Value::Interpolation { parts } => {
+            let mut is_first = true;
+            for part in parts {
+                let reg = if is_first { dst } else { ctx.alloc_reg() };
+                let range = match part {
+                    ir::InterpolationPart::String(token, string) => {
+                        if string.is_empty() {
+                            if !is_first {
+                                ctx.free_reg(reg);
+                            }
+                            continue;
+                        }
+                        fragment.append(LoadStringObject(reg, string.clone()));
+                        token.text_range()
+                    }
+                    ir::InterpolationPart::Value(value, format_spec) => {
+                        let (value_syntax, value) = ctx.strage.get(value).unwrap();
+                        let range = value_syntax.text_range();
+                        match format_spec {
+                            Some((token, format_spec)) => {
+                                fragment.append_compile_to(&value, reg, ctx).append(Format(
+                                    reg,
+                                    reg.take(),
+                                    Box::new(*format_spec),
+                                    token.text_range(),
+                                ));
+                            }
+                            None => {
+                                let base = compile_utils::start_call_base(reg, ctx);
+                                fragment
+                                    .append_compile_to(&value, base, ctx)
+                                    .append(CallMethod(
+                                        base,
+                                        0,
+                                        MethodName::new("to_string"),
+                                        Box::new([range, range]),
+                                    ));
+                                compile_utils::finish_call_base(reg, base, fragment, ctx);
+                            }
+                        };
+                        range
+                    }
+                };
+                if !is_first {
+                    fragment.append(Concat(dst, dst.take(), reg.take(), range));
+                    ctx.free_reg(reg);
+                }
+                is_first = false;
+            }
+            if is_first {
+                fragment.append(LoadStringObject(dst, UString::new()));
             }
-            fragment.append(MakeTable(fields.len(), key_ranges.into_boxed_slice()));
         }
     }
 }
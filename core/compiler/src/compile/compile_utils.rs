@@ -1,6 +1,13 @@
 use super::*;
+use foundation::{il::Reg, object::UString, syntax::TextRange};
 
-pub(crate) fn compile_function(func: &ir::FunctionKey, fragment: &mut Fragment, ctx: &mut Context) {
+/// Compiles the function `func`, and stores the function object to `dst`.
+pub(crate) fn compile_function(
+    func: &ir::FunctionKey,
+    dst: Reg,
+    fragment: &mut Fragment,
+    ctx: &mut Context,
+) {
     use ICodeSource::*;
 
     let func_capture = ctx.capture_db.get_capture(func);
@@ -9,10 +16,10 @@ pub(crate) fn compile_function(func: &ir::FunctionKey, fragment: &mut Fragment,
         let (func_params, func_effects) = ctx.strage.get(func);
 
         let mut fragment = Fragment::new();
-        let mut ctx = Context::new_with(ctx);
+        let mut ctx = Context::new_with(ctx, func);
 
         for capture in func_capture.iter() {
-            ctx.add_local(capture);
+            ctx.add_capture(capture);
         }
 
         let mut param_len = 0;
@@ -25,6 +32,8 @@ pub(crate) fn compile_function(func: &ir::FunctionKey, fragment: &mut Fragment,
         for (_, effect) in func_effects {
             fragment.append_compile(&effect, &mut ctx);
         }
+        // Implicit `return nil` for the bodies which do not end with `return`.
+        append_leave_nil(&mut fragment, &mut ctx);
 
         (fragment, param_len)
     };
@@ -32,12 +41,285 @@ pub(crate) fn compile_function(func: &ir::FunctionKey, fragment: &mut Fragment,
     let func_id = ctx.add_function(func_fragment);
     fragment
         .append_many([
-            BeginFuncSection,
+            BeginFuncSection(dst),
             FuncSetProperty(func_param_len as u8, func_id),
         ])
         .append_many(func_capture.iter().map(|name| {
-            let local_id = ctx.resolve_local(name);
-            FuncAddCapture(local_id)
+            let local = ctx.resolve_local(name);
+            FuncAddCapture(local)
         }))
         .append(EndFuncSection);
 }
+
+/// Appends `return nil`.
+pub(crate) fn append_leave_nil(fragment: &mut Fragment, ctx: &mut Context) {
+    let reg = ctx.alloc_reg();
+    fragment.append(ICodeSource::LoadNilObject(reg));
+    append_leave(reg.take(), fragment, ctx);
+    ctx.free_reg(reg);
+}
+
+/// Appends `Leave` of `value`, closing the upvalues of the frame before it.
+pub(crate) fn append_leave(value: Reg, fragment: &mut Fragment, ctx: &Context) {
+    append_close_upvalues(Reg::new(0), fragment, ctx);
+    fragment.append(ICodeSource::Leave(value));
+}
+
+/// Drops the locals declared in the current block, if any.
+pub(crate) fn append_drop_block_local(fragment: &mut Fragment, ctx: &Context) {
+    if ctx.get_block_local_count() > 0 {
+        append_drop_local(ctx.get_block_base(), fragment, ctx);
+    }
+}
+
+/// Drops the locals from `base`, closing their upvalues before it if they may be captured.
+pub(crate) fn append_drop_local(base: Reg, fragment: &mut Fragment, ctx: &Context) {
+    append_close_upvalues(base, fragment, ctx);
+    fragment.append(ICodeSource::DropLocal(base));
+}
+
+/// Appends `CloseUpvalues` if a live local from `base` may be captured by a function.
+/// (See `database::FunctionCapture::get_escape()`)
+pub(crate) fn append_close_upvalues(base: Reg, fragment: &mut Fragment, ctx: &Context) {
+    if ctx.has_escaping_local_from(base) {
+        fragment.append(ICodeSource::CloseUpvalues(base));
+    }
+}
+
+/// Compiles `value` as an operand, and returns the register holding it.
+///
+/// A local is used in place. The other values are compiled to a new temporary, which is marked as
+/// `take`. The caller frees the temporary after the instruction using it.
+pub(crate) fn compile_operand<'src>(
+    value: &ir::Value,
+    fragment: &mut Fragment,
+    ctx: &mut Context<'src>,
+) -> Reg {
+    if let ir::Value::Local { name } = value {
+        let name_str = ctx.strage.get(name).unwrap().1.text();
+        return ctx.resolve_local(name_str);
+    }
+    compile_temporary(value, fragment, ctx)
+}
+
+/// Compiles `values` as operands in order. (See `compile_operand()`)
+///
+/// A local is copied to a temporary if it is followed by a value which may change it, e.g. `x` in
+/// `x + f()`, so that the operands are evaluated from left to right.
+pub(crate) fn compile_operands<'src, const N: usize>(
+    values: [&ir::Value; N],
+    fragment: &mut Fragment,
+    ctx: &mut Context<'src>,
+) -> [Reg; N] {
+    let mut regs = [Reg::new(0); N];
+    for (i, value) in values.iter().enumerate() {
+        regs[i] = if values[i + 1..].iter().all(|value| has_no_effect(value)) {
+            compile_operand(value, fragment, ctx)
+        } else {
+            compile_temporary(value, fragment, ctx)
+        };
+    }
+    regs
+}
+
+/// Compiles `value` to a new temporary, and returns it marked as `take`.
+pub(crate) fn compile_temporary<'src>(
+    value: &ir::Value,
+    fragment: &mut Fragment,
+    ctx: &mut Context<'src>,
+) -> Reg {
+    let reg = ctx.alloc_reg();
+    fragment.append_compile_to(&value, reg, ctx);
+    reg.take()
+}
+
+/// Returns `true` if evaluating `value` has no side effect.
+fn has_no_effect(value: &ir::Value) -> bool {
+    matches!(
+        value,
+        ir::Value::Local { .. }
+            | ir::Value::Int(_)
+            | ir::Value::Float(_)
+            | ir::Value::String(_)
+            | ir::Value::Bool(_)
+            | ir::Value::Nil
+    )
+}
+
+/// Returns `true` if `value` can be compiled directly to the register of a live local.
+///
+/// Such a value writes the destination only by its last instruction, after reading all of its
+/// operands. The others (e.g. `a and b`, a call) are compiled to a temporary and then moved.
+pub(crate) fn can_compile_to_local(value: &ir::Value) -> bool {
+    match value {
+        ir::Value::Binary { op, .. } => !matches!(
+            op,
+            ir::BinaryOp::And(_) | ir::BinaryOp::Or(_) | ir::BinaryOp::NilCoalesce(_)
+        ),
+        ir::Value::Field { optional, .. } => !optional,
+        ir::Value::Prefix { .. } | ir::Value::Index { .. } => true,
+        value => has_no_effect(value),
+    }
+}
+
+/// Returns the register to place a call at: `dst` itself if the arguments can follow it,
+/// otherwise a new temporary. Pass it to `finish_call_base()` after the call.
+///
+/// The arrays and tables are made in the same way.
+pub(crate) fn start_call_base(dst: Reg, ctx: &mut Context) -> Reg {
+    if ctx.is_last_reg(dst) {
+        dst
+    } else {
+        ctx.alloc_reg()
+    }
+}
+
+/// Moves the result of the call at `base` to `dst`. (See `start_call_base()`)
+pub(crate) fn finish_call_base(dst: Reg, base: Reg, fragment: &mut Fragment, ctx: &mut Context) {
+    ctx.free_reg(base.offset(1));
+    if base != dst {
+        fragment.append(ICodeSource::Move(dst, base.take()));
+        ctx.free_reg(base);
+    }
+}
+
+/// A step from the matched value to the part checked by a pattern.
+#[derive(Clone)]
+enum PathStep {
+    Field(UString, TextRange),
+    Index(i64),
+}
+
+/// Loads the part of `scrutinee` at `path` to `dst`, and returns the register holding it.
+fn load_path(
+    path: &[PathStep],
+    scrutinee: Reg,
+    dst: Reg,
+    fragment: &mut Fragment,
+    ctx: &mut Context,
+) -> Reg {
+    use ICodeSource::*;
+    let mut container = scrutinee;
+    for step in path {
+        let key = ctx.alloc_reg();
+        let range = match step {
+            PathStep::Field(name, range) => {
+                fragment.append(LoadStringObject(key, name.clone()));
+                *range
+            }
+            PathStep::Index(index) => {
+                fragment.append(LoadIntObject(key, *index));
+                TextRange::default()
+            }
+        };
+        fragment.append(GetItem(dst, container, key.take(), range));
+        ctx.free_reg(key);
+        container = dst.take();
+    }
+    container
+}
+
+/// Compiles the checks of `pattern` against the local `scrutinee`, pushing one fragment per check.
+/// Each fragment stores a bool to `dst`; the pattern matches if all of them store `true`.
+///
+/// The checks are ordered so that a container is indexed only after its shape is checked.
+pub(crate) fn compile_pattern_tests(
+    pattern: &ir::Pattern,
+    scrutinee: Reg,
+    dst: Reg,
+    tests: &mut Vec<Fragment>,
+    ctx: &mut Context,
+) {
+    fn go(
+        pattern: &ir::Pattern,
+        path: &mut Vec<PathStep>,
+        scrutinee: Reg,
+        dst: Reg,
+        tests: &mut Vec<Fragment>,
+        ctx: &mut Context,
+    ) {
+        use ICodeSource::*;
+        match pattern {
+            ir::Pattern::Wildcard | ir::Pattern::Binding(_) => {}
+            ir::Pattern::Literal(value) => {
+                let (syntax, value) = ctx.strage.get(value).unwrap();
+                let mut fragment = Fragment::new();
+                let target = load_path(path, scrutinee, dst, &mut fragment, ctx);
+                let literal = compile_temporary(value, &mut fragment, ctx);
+                fragment.append(Eq(dst, target, literal, syntax.text_range()));
+                ctx.free_reg(literal.plain());
+                tests.push(fragment);
+            }
+            ir::Pattern::Table(fields) => {
+                let mut fragment = Fragment::new();
+                let target = load_path(path, scrutinee, dst, &mut fragment, ctx);
+                fragment.append(TestTable(dst, target));
+                tests.push(fragment);
+                for (key, pattern) in fields.iter() {
+                    let (syntax, key) = ctx.strage.get(key).unwrap();
+                    path.push(PathStep::Field(UString::intern(key), syntax.text_range()));
+                    go(pattern, path, scrutinee, dst, tests, ctx);
+                    path.pop();
+                }
+            }
+            ir::Pattern::Array(elements) => {
+                let mut fragment = Fragment::new();
+                let target = load_path(path, scrutinee, dst, &mut fragment, ctx);
+                fragment.append(TestArray(dst, target, elements.len()));
+                tests.push(fragment);
+                for (i, pattern) in elements.iter().enumerate() {
+                    path.push(PathStep::Index(i as i64));
+                    go(pattern, path, scrutinee, dst, tests, ctx);
+                    path.pop();
+                }
+            }
+        }
+    }
+    go(pattern, &mut Vec::new(), scrutinee, dst, tests, ctx);
+}
+
+/// Compiles the bindings of `pattern`, storing the matched parts of the local `scrutinee` to new
+/// locals in order of appearance.
+pub(crate) fn compile_pattern_bindings<'src>(
+    pattern: &ir::Pattern,
+    scrutinee: Reg,
+    fragment: &mut Fragment,
+    ctx: &mut Context<'src>,
+) {
+    fn go<'src>(
+        pattern: &ir::Pattern,
+        path: &mut Vec<PathStep>,
+        scrutinee: Reg,
+        fragment: &mut Fragment,
+        ctx: &mut Context<'src>,
+    ) {
+        match pattern {
+            ir::Pattern::Wildcard | ir::Pattern::Literal(_) => {}
+            ir::Pattern::Binding(symbol) => {
+                let name = ctx.strage.get(symbol).unwrap().1.text();
+                let local = ctx.alloc_reg();
+                let value = load_path(path, scrutinee, local, fragment, ctx);
+                if value == scrutinee {
+                    fragment.append(ICodeSource::Move(local, scrutinee));
+                }
+                ctx.bind_local(name, local);
+            }
+            ir::Pattern::Table(fields) => {
+                for (key, pattern) in fields.iter() {
+                    let (syntax, key) = ctx.strage.get(key).unwrap();
+                    path.push(PathStep::Field(UString::intern(key), syntax.text_range()));
+                    go(pattern, path, scrutinee, fragment, ctx);
+                    path.pop();
+                }
+            }
+            ir::Pattern::Array(elements) => {
+                for (i, pattern) in elements.iter().enumerate() {
+                    path.push(PathStep::Index(i as i64));
+                    go(pattern, path, scrutinee, fragment, ctx);
+                    path.pop();
+                }
+            }
+        }
+    }
+    go(pattern, &mut Vec::new(), scrutinee, fragment, ctx);
+}
@@ -1,14 +1,18 @@
 use super::*;
 use core::{cell::RefCell, mem::forget};
-use foundation::{il::LocalId, ir::Strage};
+use foundation::{il::Reg, ir::Strage};
 use std::rc::Rc;
 
 #[derive(Debug)]
 pub(crate) struct Context<'s> {
     block_vars_count: internal::NestedCounter,
     loop_vars_count: internal::NestedCounter,
-    id_generator: internal::LocalIdGenerator<'s>,
+    registers: internal::RegisterAllocator<'s>,
     func_list: Rc<RefCell<Vec<Fragment>>>,
+    /// The names of the locals of the current function captured by the nested functions.
+    escape: database::CaptureHashSetRef<'s>,
+    /// The registers of the live locals whose names are in `escape`.
+    escaping_locals: Vec<Reg>,
     pub(crate) strage: &'s Strage,
     pub(crate) capture_db: &'s database::FunctionCapture,
 }
@@ -18,31 +22,38 @@ impl<'s> Context<'s> {
         Self {
             block_vars_count: internal::NestedCounter::new(),
             loop_vars_count: internal::NestedCounter::new(),
-            id_generator: internal::LocalIdGenerator::new(),
+            registers: internal::RegisterAllocator::new(),
             func_list: Rc::new(RefCell::new(Vec::new())),
+            escape: capture_db.get_escape(database::FunctionCaptureKey::Module),
+            escaping_locals: Vec::new(),
             strage,
             capture_db,
         }
     }
 
-    pub(crate) fn new_with(ctx: &mut Self) -> Self {
+    /// Creates the context for the function `func` nested in `ctx`.
+    pub(crate) fn new_with(ctx: &mut Self, func: &ir::FunctionKey) -> Self {
         Self {
             block_vars_count: internal::NestedCounter::new(),
             loop_vars_count: internal::NestedCounter::new(),
-            id_generator: internal::LocalIdGenerator::new(),
+            registers: internal::RegisterAllocator::new(),
             func_list: Rc::clone(&ctx.func_list),
+            escape: ctx.capture_db.get_escape(func),
+            escaping_locals: Vec::new(),
             strage: ctx.strage,
             capture_db: ctx.capture_db,
         }
     }
 
     pub(crate) fn start_block(&mut self) -> BlockMarker {
-        self.block_vars_count.start_section();
+        let base = self.registers.next_reg();
+        self.block_vars_count.start_section(base);
         BlockMarker
     }
 
     pub(crate) fn start_loop(&mut self) -> LoopMarker {
-        self.loop_vars_count.start_section();
+        let base = self.registers.next_reg();
+        self.loop_vars_count.start_section(base);
         LoopMarker
     }
 
@@ -58,10 +69,75 @@ impl<'s> Context<'s> {
             .expect("[BUG] This should be called after `Context::begin_block()` is called.")
     }
 
-    pub(crate) fn add_local(&mut self, name: &'s str) -> LocalId {
+    /// The first register of the current block. The registers from it are dropped at the end of
+    /// the block.
+    pub(crate) fn get_block_base(&self) -> Reg {
+        self.block_vars_count
+            .get_current_base()
+            .expect("[BUG] This should be called after `Context::begin_block()` is called.")
+    }
+
+    /// The first register of the current loop body. The registers from it are dropped at `break`
+    /// and `continue`.
+    pub(crate) fn get_loop_base(&self) -> Reg {
+        self.loop_vars_count
+            .get_current_base()
+            .expect("[BUG] This should be called after `Context::start_loop()` is called.")
+    }
+
+    /// Allocates a new register for the local variable `name`.
+    pub(crate) fn add_local(&mut self, name: &'s str) -> Reg {
+        let reg = self.registers.alloc();
+        self.bind_local(name, reg);
+        reg
+    }
+
+    /// Makes the allocated register `reg` the local variable `name`, so that it lives until the end
+    /// of the current block.
+    pub(crate) fn bind_local(&mut self, name: &'s str, reg: Reg) {
+        if self.escape.contains(name) {
+            self.escaping_locals.push(reg);
+        }
+        self.block_vars_count.increment(1);
+        self.loop_vars_count.increment(1);
+        self.registers.bind(name, reg);
+    }
+
+    /// Allocates a new register for the local `name` captured from the enclosing function. It is
+    /// an upvalue already, so it is not closed by `CloseUpvalues`.
+    pub(crate) fn add_capture(&mut self, name: &'s str) -> Reg {
+        let reg = self.registers.alloc();
         self.block_vars_count.increment(1);
         self.loop_vars_count.increment(1);
-        self.id_generator.add_local(name)
+        self.registers.bind(name, reg);
+        reg
+    }
+
+    /// Returns `true` if a live local from `reg` may be captured by a function, so that its upvalue
+    /// must be closed before it goes out of scope.
+    pub(crate) fn has_escaping_local_from(&self, reg: Reg) -> bool {
+        self.escaping_locals.iter().any(|local| *local >= reg)
+    }
+
+    /// Allocates a new register for a temporary.
+    pub(crate) fn alloc_reg(&mut self) -> Reg {
+        self.registers.alloc()
+    }
+
+    /// The register which `alloc_reg()` will return next.
+    pub(crate) fn next_reg(&self) -> Reg {
+        self.registers.next_reg()
+    }
+
+    /// Frees the temporaries from `reg`.
+    pub(crate) fn free_reg(&mut self, reg: Reg) {
+        self.registers.free(reg);
+    }
+
+    /// Returns `true` if `reg` is the last allocated register, so that a call can place its
+    /// arguments after it.
+    pub(crate) fn is_last_reg(&self, reg: Reg) -> bool {
+        reg.as_usize() + 1 == self.registers.next_reg().as_usize()
     }
 
     pub(crate) fn add_function(&mut self, fragment: Fragment) -> FunctionListId {
@@ -71,13 +147,15 @@ impl<'s> Context<'s> {
     }
 
     pub(crate) fn drop_local(&mut self, count: usize) {
-        self.id_generator.drop_local(count);
+        self.registers.drop_local(count);
         self.block_vars_count.decrement(count);
         self.loop_vars_count.decrement(count);
+        let next = self.registers.next_reg();
+        self.escaping_locals.retain(|local| *local < next);
     }
 
-    pub(crate) fn resolve_local(&self, name: &'s str) -> LocalId {
-        self.id_generator.resolve_local(name).unwrap_or_else(|| {
+    pub(crate) fn resolve_local(&self, name: &'s str) -> Reg {
+        self.registers.resolve_local(name).unwrap_or_else(|| {
             panic!("All undefined local variables error should be caught by upper layer. (undefined: {name})")
         })
     }
@@ -89,10 +167,13 @@ impl<'s> Context<'s> {
                 .into_inner();
             let mut codes = Vec::new();
             let mut id2idx = Vec::new();
-            codes.extend(fragment.finish());
+            // The jumps do not go out of the function, so each function is optimized alone.
+            codes.extend(peephole::optimize(fragment.finish()));
+            let index = |len: usize| u32::try_from(len).expect("overflow: too many instructions");
             for func in func_list {
-                id2idx.push(codes.len());
-                codes.extend(func.finish());
+                let start = index(codes.len());
+                codes.extend(peephole::optimize(func.finish()));
+                id2idx.push((start, index(codes.len())));
             }
             (codes, id2idx)
         };
@@ -103,74 +184,92 @@ impl<'s> Context<'s> {
             use ICodeSource as Src;
             #[rustfmt::skip]
             let code = match code {
-                Src::LoadIntObject(x)       => LoadIntObject(x),
-                Src::LoadFloatObject(x)     => LoadFloatObject(x),
-                Src::LoadStringObject(x)    => LoadStringObject(x),
-                Src::LoadBoolObject(x)      => LoadBoolObject(x),
-                Src::LoadNilObject          => LoadNilObject,
-                Src::LoadLocal(x)           => LoadLocal(x),
-                Src::Unload                 => Unload,
-                Src::StoreLocal(x)          => StoreLocal(x),
-                Src::StoreNewLocal          => StoreNewLocal,
-                Src::MakeArray(x)           => MakeArray(x),
-                Src::MakeTable(x, ranges)   => {
+                Src::LoadIntObject(r, x)          => LoadIntObject(r, x),
+                Src::LoadFloatObject(r, x)        => LoadFloatObject(r, x),
+                Src::LoadStringObject(r, x)       => LoadStringObject(r, x),
+                Src::LoadBoolObject(r, x)         => LoadBoolObject(r, x),
+                Src::LoadNilObject(r)             => LoadNilObject(r),
+                Src::Move(r0, r1)                 => Move(r0, r1),
+                Src::MakeArray(r, x)              => MakeArray(r, x),
+                Src::MakeTable(r, x, ranges)      => {
                     for (extra, range) in ranges.iter().enumerate() {
                         if let Some(range) = range {
                             infos.insert(i, extra, *range);
                         }
                     }
-                    MakeTable(x)
+                    MakeTable(r, x)
+                }
+                Src::DropLocal(r)                 => DropLocal(r),
+                Src::CloseUpvalues(r)             => CloseUpvalues(r),
+                Src::Jump(x)                      => Jump(x),
+                Src::JumpIfTrue(r, x)             => JumpIfTrue(r, x),
+                Src::JumpIfFalse(r, x)            => JumpIfFalse(r, x),
+                Src::JumpIfNil(r, x)              => JumpIfNil(r, x),
+                Src::JumpIfNotNil(r, x)           => JumpIfNotNil(r, x),
+                Src::JumpIfNotLess(r0, r1, r2, x, range)      => { infos.insert(i, 0, range); JumpIfNotLess(r0, r1, r2, x) }
+                Src::JumpIfNotLessEq(r0, r1, r2, x, range)    => { infos.insert(i, 0, range); JumpIfNotLessEq(r0, r1, r2, x) }
+                Src::JumpIfNotGreater(r0, r1, r2, x, range)   => { infos.insert(i, 0, range); JumpIfNotGreater(r0, r1, r2, x) }
+                Src::JumpIfNotGreaterEq(r0, r1, r2, x, range) => { infos.insert(i, 0, range); JumpIfNotGreaterEq(r0, r1, r2, x) }
+                Src::JumpIfNotEq(r0, r1, r2, x, range)        => { infos.insert(i, 0, range); JumpIfNotEq(r0, r1, r2, x) }
+                Src::Call(r, x, range0, ranges)   => {
+                    infos.insert(i, 0, range0);
+                    for (extra, range) in ranges.iter().enumerate() {
+                        infos.insert(i, extra + 1, *range);
+                    }
+                    Call(r, x)
                 }
-                Src::DropLocal(x)            => DropLocal(x),
-                Src::Jump(x)                 => Jump(x),
-                Src::JumpIfTrue(x)           => JumpIfTrue(x),
-                Src::JumpIfFalse(x)          => JumpIfFalse(x),
-                Src::Call(x, range0, ranges) => {
+                Src::TailCall(r, x, range0, ranges) => {
                     infos.insert(i, 0, range0);
                     for (extra, range) in ranges.iter().enumerate() {
                         infos.insert(i, extra + 1, *range);
                     }
-                    Call(x)
+                    TailCall(r, x)
                 }
-                Src::CallMethod(x, y, ranges) => {
+                Src::CallMethod(r, x, y, ranges)  => {
                     for (extra, range) in ranges.iter().enumerate() {
                         infos.insert(i, extra, *range);
                     }
-                    CallMethod(x, y)
+                    CallMethod(r, x, y)
                 }
-                Src::SetItem(range)         => { infos.insert(i, 0, range); SetItem },
-                Src::GetItem(range)         => { infos.insert(i, 0, range); GetItem },
-                Src::SetMethod(x, _)        => SetMethod(x),
-                Src::Add(range)             => { infos.insert(i, 0, range); Add }
-                Src::Sub(range)             => { infos.insert(i, 0, range); Sub }
-                Src::Mul(range)             => { infos.insert(i, 0, range); Mul }
-                Src::Div(range)             => { infos.insert(i, 0, range); Div }
-                Src::Mod(range)             => { infos.insert(i, 0, range); Mod }
-                Src::Unm(range)             => { infos.insert(i, 0, range); Unm }
-                Src::Unp(range)             => { infos.insert(i, 0, range); Unp }
-                Src::Not(range)             => { infos.insert(i, 0, range); Not }
-                Src::Eq(range)              => { infos.insert(i, 0, range); Eq }
-                Src::NotEq(range)           => { infos.insert(i, 0, range); NotEq }
-                Src::Less(range)            => { infos.insert(i, 0, range); Less }
-                Src::LessEq(range)          => { infos.insert(i, 0, range); LessEq }
-                Src::Greater(range)         => { infos.insert(i, 0, range); Greater }
-                Src::GreaterEq(range)       => { infos.insert(i, 0, range); GreaterEq }
-                Src::Concat(range)          => { infos.insert(i, 0, range); Concat }
-                Src::BitAnd(range)          => { infos.insert(i, 0, range); BitAnd }
-                Src::BitOr(range)           => { infos.insert(i, 0, range); BitOr }
-                Src::BitXor(range)          => { infos.insert(i, 0, range); BitXor }
-                Src::BitNot(range)          => { infos.insert(i, 0, range); BitNot }
-                Src::ShiftL(range)          => { infos.insert(i, 0, range); ShiftL }
-                Src::ShiftR(range)          => { infos.insert(i, 0, range); ShiftR }
-                Src::GetIter                => GetIter,
-                Src::IterMoveNext           => IterMoveNext,
-                Src::IterCurrent            => IterCurrent,
-                Src::BeginFuncSection       => BeginFuncSection,
-                Src::FuncSetProperty(x, id) => FuncSetProperty(x, funcid2index[id.0]),
-                Src::FuncAddCapture(x)      => FuncAddCapture(x),
-                Src::EndFuncSection         => EndFuncSection,
-                Src::Leave                  => Leave,
-                Src::Tombstone              => panic!("[BUG] Tombstone should not be in the final code."),
+                Src::SetItem(r0, r1, r2, range)   => { infos.insert(i, 0, range); SetItem(r0, r1, r2) },
+                Src::GetItem(r0, r1, r2, range)   => { infos.insert(i, 0, range); GetItem(r0, r1, r2) },
+                Src::TestTable(r0, r1)            => TestTable(r0, r1),
+                Src::TestArray(r0, r1, x)         => TestArray(r0, r1, x),
+                Src::SetMethod(r0, r1, x, range)  => { infos.insert(i, 0, range); SetMethod(r0, r1, x) },
+                Src::Add(r0, r1, r2, range)       => { infos.insert(i, 0, range); Add(r0, r1, r2) }
+                Src::Sub(r0, r1, r2, range)       => { infos.insert(i, 0, range); Sub(r0, r1, r2) }
+                Src::AddInt(r0, r1, x, range)     => { infos.insert(i, 0, range); AddInt(r0, r1, x) }
+                Src::SubInt(r0, r1, x, range)     => { infos.insert(i, 0, range); SubInt(r0, r1, x) }
+                Src::Mul(r0, r1, r2, range)       => { infos.insert(i, 0, range); Mul(r0, r1, r2) }
+                Src::Div(r0, r1, r2, range)       => { infos.insert(i, 0, range); Div(r0, r1, r2) }
+                Src::Mod(r0, r1, r2, range)       => { infos.insert(i, 0, range); Mod(r0, r1, r2) }
+                Src::Unm(r0, r1, range)           => { infos.insert(i, 0, range); Unm(r0, r1) }
+                Src::Unp(r0, r1, range)           => { infos.insert(i, 0, range); Unp(r0, r1) }
+                Src::Not(r0, r1, range)           => { infos.insert(i, 0, range); Not(r0, r1) }
+                Src::Eq(r0, r1, r2, range)        => { infos.insert(i, 0, range); Eq(r0, r1, r2) }
+                Src::NotEq(r0, r1, r2, range)     => { infos.insert(i, 0, range); NotEq(r0, r1, r2) }
+                Src::Less(r0, r1, r2, range)      => { infos.insert(i, 0, range); Less(r0, r1, r2) }
+                Src::LessEq(r0, r1, r2, range)    => { infos.insert(i, 0, range); LessEq(r0, r1, r2) }
+                Src::Greater(r0, r1, r2, range)   => { infos.insert(i, 0, range); Greater(r0, r1, r2) }
+                Src::GreaterEq(r0, r1, r2, range) => { infos.insert(i, 0, range); GreaterEq(r0, r1, r2) }
+                Src::Concat(r0, r1, r2, range)    => { infos.insert(i, 0, range); Concat(r0, r1, r2) }
+                Src::Format(r0, r1, spec, range)  => { infos.insert(i, 0, range); Format(r0, r1, spec) }
+                Src::BitAnd(r0, r1, r2, range)    => { infos.insert(i, 0, range); BitAnd(r0, r1, r2) }
+                Src::BitOr(r0, r1, r2, range)     => { infos.insert(i, 0, range); BitOr(r0, r1, r2) }
+                Src::BitXor(r0, r1, r2, range)    => { infos.insert(i, 0, range); BitXor(r0, r1, r2) }
+                Src::BitNot(r0, r1, range)        => { infos.insert(i, 0, range); BitNot(r0, r1) }
+                Src::ShiftL(r0, r1, r2, range)    => { infos.insert(i, 0, range); ShiftL(r0, r1, r2) }
+                Src::ShiftR(r0, r1, r2, range)    => { infos.insert(i, 0, range); ShiftR(r0, r1, r2) }
+                Src::GetIter(r0, r1)              => GetIter(r0, r1),
+                Src::IterMoveNext(r0, r1)         => IterMoveNext(r0, r1),
+                Src::IterCurrent(r0, r1)          => IterCurrent(r0, r1),
+                Src::BeginFuncSection(r)          => BeginFuncSection(r),
+                Src::FuncSetProperty(x, id)       => { let (start, end) = funcid2index[id.0]; FuncSetProperty(x, start, end) }
+                Src::FuncAddCapture(r)            => FuncAddCapture(r),
+                Src::EndFuncSection               => EndFuncSection,
+                Src::Nop                          => Nop,
+                Src::Leave(r)                     => Leave(r),
+                Src::Tombstone                    => panic!("[BUG] Tombstone should not be in the final code."),
             };
             codes.push(code);
         }
@@ -183,9 +282,12 @@ pub(crate) struct BlockMarker;
 impl BlockMarker {
     pub(crate) fn finish(self, ctx: &mut Context<'_>) {
         forget(self);
+        let base = ctx.get_block_base();
         let block_cnt = ctx.block_vars_count.end_section();
-        ctx.id_generator.drop_local(block_cnt);
+        ctx.registers.drop_local(block_cnt);
+        ctx.registers.free(base);
         ctx.loop_vars_count.decrement(block_cnt);
+        ctx.escaping_locals.retain(|local| *local < base);
     }
 }
 impl Drop for BlockMarker {
@@ -221,7 +323,8 @@ mod internal {
 
     #[derive(Debug)]
     pub struct NestedCounter {
-        stack: Vec<usize>,
+        // (the first register of the section, the number of the locals in the section)
+        stack: Vec<(Reg, usize)>,
     }
 
     impl NestedCounter {
@@ -229,77 +332,101 @@ mod internal {
             Self { stack: Vec::new() }
         }
 
-        pub fn start_section(&mut self) {
-            self.stack.push(0);
+        pub fn start_section(&mut self, base: Reg) {
+            self.stack.push((base, 0));
         }
 
         // TODO: use dorp marker
         pub fn end_section(&mut self) -> usize {
-            self.stack.pop().expect(
-                "[BUG] this should be called after `NestedCounter::start_new_section()` is called.",
-            )
+            self.stack
+                .pop()
+                .expect(
+                    "[BUG] this should be called after `NestedCounter::start_new_section()` is called.",
+                )
+                .1
         }
 
         pub fn increment(&mut self, count: usize) {
-            if let Some(last) = self.stack.last_mut() {
+            if let Some((_, last)) = self.stack.last_mut() {
                 *last += count;
             }
         }
 
         pub fn decrement(&mut self, count: usize) {
-            if let Some(last) = self.stack.last_mut() {
+            if let Some((_, last)) = self.stack.last_mut() {
                 *last -= count;
             }
         }
 
         pub fn get_current_count(&self) -> Option<usize> {
-            self.stack.last().copied()
+            self.stack.last().map(|(_, count)| *count)
+        }
+
+        pub fn get_current_base(&self) -> Option<Reg> {
+            self.stack.last().map(|(base, _)| *base)
         }
     }
 
+    /// Allocates the registers in a stack manner: the locals and the temporaries are allocated
+    /// from the lowest free register, and freed in reverse order.
     #[derive(Debug)]
-    pub struct LocalIdGenerator<'s> {
-        map: FxHashMap<&'s str, LocalId>,
-        vec: Vec<(&'s str, LocalId)>,
+    pub struct RegisterAllocator<'s> {
+        map: FxHashMap<&'s str, Reg>,
+        // (name, the register to restore the name to when the local is dropped)
+        vec: Vec<(&'s str, Reg)>,
+        next: usize,
     }
 
-    impl<'s> LocalIdGenerator<'s> {
+    impl<'s> RegisterAllocator<'s> {
         pub fn new() -> Self {
             Self {
                 map: FxHashMap::default(),
                 vec: Vec::new(),
+                next: 0,
             }
         }
 
-        pub fn add_local(&mut self, name: &'s str) -> LocalId {
-            let id = LocalId::new(self.vec.len());
-            let old_id = self.map.insert(name, id);
-            let restore = if let Some(old_id) = old_id {
-                (name, old_id)
-            } else {
-                (name, id)
-            };
-            self.vec.push(restore);
-            id
+        pub fn next_reg(&self) -> Reg {
+            Reg::new(self.next)
+        }
+
+        pub fn alloc(&mut self) -> Reg {
+            let reg = Reg::new(self.next);
+            self.next += 1;
+            reg
+        }
+
+        pub fn free(&mut self, reg: Reg) {
+            debug_assert!(reg.as_usize() <= self.next);
+            self.next = reg.as_usize();
+        }
+
+        pub fn bind(&mut self, name: &'s str, reg: Reg) {
+            debug_assert!(reg.as_usize() < self.next);
+            let old_reg = self.map.insert(name, reg);
+            self.vec.push((name, old_reg.unwrap_or(reg)));
         }
 
-        pub fn resolve_local(&self, name: &'s str) -> Option<LocalId> {
+        pub fn resolve_local(&self, name: &'s str) -> Option<Reg> {
             self.map.get(name).copied()
         }
 
+        /// Drops the last `count` locals, and frees their registers.
         pub fn drop_local(&mut self, count: usize) {
             for _ in 0..count {
-                let (name, id) = self.vec.pop().expect(
+                let (name, restore) = self.vec.pop().expect(
                     "[BUG] `count` should be less than or equal to the number of variables.",
                 );
                 let mut stored_entry = match self.map.entry(name) {
                     std::collections::hash_map::Entry::Occupied(x) => x,
                     std::collections::hash_map::Entry::Vacant(_) => {
-                        unreachable!("This is ensured by `LocalIdGenerator::add_local()`.")
+                        unreachable!("This is ensured by `RegisterAllocator::bind()`.")
                     }
                 };
-                if stored_entry.get() != &id {
-                    stored_entry.insert(id);
+                let reg = *stored_entry.get();
+                self.next = self.next.min(reg.as_usize());
+                if reg != restore {
+                    stored_entry.insert(restore);
                 } else {
                     stored_entry.remove();
                 }
@@ -26,6 +26,16 @@ impl Fragment {
         fragment
     }
 
+    pub(crate) fn with_compile_to<'node, 'src: 'node>(
+        compilable: &'node impl CompilableTo<'node, 'src>,
+        dst: Reg,
+        ctx: &mut Context<'src>,
+    ) -> Self {
+        let mut fragment = Self::new();
+        compilable.compile_to(dst, &mut fragment, ctx);
+        fragment
+    }
+
     /// Sets the jump offset for all forward jumps from the end of the fragment.
     pub(crate) fn patch_forward_jump(&mut self, offset: isize) {
         let len = self.code.len();
@@ -73,6 +83,16 @@ impl Fragment {
         self
     }
 
+    pub(crate) fn append_compile_to<'node, 'src: 'node>(
+        &mut self,
+        compilable: &'node impl CompilableTo<'node, 'src>,
+        dst: Reg,
+        ctx: &mut Context<'src>,
+    ) -> &mut Self {
+        compilable.compile_to(dst, self, ctx);
+        self
+    }
+
     pub(crate) fn append_forward_jump(&mut self) {
         self.code.push(ICodeSource::Tombstone);
         let pos = self.code.len() - 1;
@@ -125,6 +145,7 @@ mod tests {
     use smallvec::smallvec;
 
     use super::ICodeSource::*;
+    use foundation::il::Reg;
 
     #[test]
     fn patch_forward_jump() {
@@ -181,12 +202,12 @@ mod tests {
     #[test]
     fn append_fragment() {
         let mut fragment = Fragment {
-            code: vec![Tombstone, Unload, Tombstone],
+            code: vec![Tombstone, LoadNilObject(Reg::new(0)), Tombstone],
             backward_jump_pos: smallvec![2],
             forward_jump_pos: smallvec![0],
         };
         fragment.append_fragment(Fragment {
-            code: vec![Tombstone, Leave, Tombstone],
+            code: vec![Tombstone, Leave(Reg::new(0)), Tombstone],
             backward_jump_pos: smallvec![0],
             forward_jump_pos: smallvec![2],
         });
@@ -194,12 +215,12 @@ mod tests {
         assert_eq!(
             fragment.code,
             vec![
-                Tombstone, // 0: forward jump
-                Unload,    // 1:
-                Tombstone, // 2: backward jump
-                Tombstone, // 3: backward jump
-                Leave,     // 4:
-                Tombstone, // 5: forward jump
+                Tombstone,                  // 0: forward jump
+                LoadNilObject(Reg::new(0)), // 1:
+                Tombstone,                  // 2: backward jump
+                Tombstone,                  // 3: backward jump
+                Leave(Reg::new(0)),         // 4:
+                Tombstone,                  // 5: forward jump
             ]
         );
         assert_eq!(fragment.backward_jump_pos, SmallVec::<_, 4>::from([2, 3]));
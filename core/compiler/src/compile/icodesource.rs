@@ -1,5 +1,9 @@
 use super::*;
-use foundation::{object::UString, syntax::TextRange};
+use foundation::{
+    il::Reg,
+    object::{FormatSpec, MethodName, UString},
+    syntax::TextRange,
+};
 
 fn _size_check() {
     const {
@@ -9,113 +13,173 @@ fn _size_check() {
 
 #[derive(Clone, Debug, PartialEq)]
 pub(crate) enum ICodeSource {
-    LoadIntObject(i64),
-    LoadFloatObject(f64),
-    LoadStringObject(UString),
-    LoadBoolObject(bool),
-    LoadNilObject,
-    LoadLocal(il::LocalId),
+    LoadIntObject(Reg, i64),
+    LoadFloatObject(Reg, f64),
+    LoadStringObject(Reg, UString),
+    LoadBoolObject(Reg, bool),
+    LoadNilObject(Reg),
 
-    Unload,
+    Move(Reg, Reg),
 
-    StoreLocal(il::LocalId),
-    StoreNewLocal,
-
-    MakeArray(usize),
+    MakeArray(Reg, usize),
 
     // Exeption
     // - The type of the key is not a string.
     // ---
-    // .1: The text range of table keys.
+    // .2: The text range of table keys.
     //     If the key is ensured to be type of string, the text range is None.
-    MakeTable(usize, Box<[Option<TextRange>]>),
+    MakeTable(Reg, usize, Box<[Option<TextRange>]>),
 
-    DropLocal(usize),
+    DropLocal(Reg),
+    CloseUpvalues(Reg),
 
     Jump(isize),
-    JumpIfTrue(isize),
-    JumpIfFalse(isize),
+    JumpIfTrue(Reg, isize),
+    JumpIfFalse(Reg, isize),
+    JumpIfNil(Reg, isize),
+    JumpIfNotNil(Reg, isize),
+
+    // Fused by the peephole optimizer (See `peephole`)
+    // ---
+    // .4: The operator text range
+    JumpIfNotLess(Reg, Reg, Reg, isize, TextRange),
+    JumpIfNotLessEq(Reg, Reg, Reg, isize, TextRange),
+    JumpIfNotGreater(Reg, Reg, Reg, isize, TextRange),
+    JumpIfNotGreaterEq(Reg, Reg, Reg, isize, TextRange),
+    JumpIfNotEq(Reg, Reg, Reg, isize, TextRange),
 
     // Exeption
     // - The callee is not type of Function or RustFunction or Table.
-    // - No `__call` method defined for the popped table type value.
+    // - No `__call` method defined for the table type callee.
     // - The number of calee arguments is not equal to the specified argument count.
     // ---
-    // .1: Callee text range
-    // .2: Each argument text range
-    Call(u8, TextRange, Box<[TextRange]>),
+    // .2: Callee text range
+    // .3: Each argument text range
+    Call(Reg, u8, TextRange, Box<[TextRange]>),
+
+    // Same as `Call`
+    TailCall(Reg, u8, TextRange, Box<[TextRange]>),
 
     // Exeption
     // - The receiver is not type of Table.
     // - The specified method name is not defined in the receiver table object.
     // - The number of method arguments is not equal to the specified argument count.
     // ---
-    // .2: [0]   Receiver text range.
+    // .3: [0]   Receiver text range.
     //     [1]   Method name text range.
     //     [2..] Each argument text range.
-    CallMethod(u8, UString, Box<[TextRange]>),
+    CallMethod(Reg, u8, MethodName, Box<[TextRange]>),
 
     // Exeption
     // - The container is not type of Table or Array.
     // - The key is not type of Int if the container is Array.
     // - The key is not type of String if the container is Table.
     // ---
-    // .0: The key text range
-    SetItem(TextRange),
+    // .3: The key text range
+    SetItem(Reg, Reg, Reg, TextRange),
 
     // Exeption
     // - The container is not type of Table or Array.
     // - The key is not type of Int if the container is Array.
     // - The key is not type of String if the container is Table.
     // ---
-    // .0: The key text range
-    GetItem(TextRange),
+    // .3: The key text range
+    GetItem(Reg, Reg, Reg, TextRange),
+
+    TestTable(Reg, Reg),
+    TestArray(Reg, Reg, usize),
 
     // Exeption
     // The container is not type of Table.
     // --
-    // .1: The container text range
-    SetMethod(UString, TextRange),
+    // .3: The container text range
+    SetMethod(Reg, Reg, MethodName, TextRange),
 
     // Exeption
-    // - Popped values are not type of Int or Float or Table.
-    // - No `__***` method defined for the popped table type value.
+    // - The operands are not type of Int or Float or Table.
+    // - No `__***` method defined for the table type operand.
     // - Division by zero.
     // ---
-    // .0: The operator text range
-    Add(TextRange),
-    Sub(TextRange),
-    Mul(TextRange),
-    Div(TextRange),
-    Mod(TextRange),
-    Unm(TextRange),
-    Unp(TextRange),
-    Not(TextRange),
-    Eq(TextRange),
-    NotEq(TextRange),
-    Less(TextRange),
-    LessEq(TextRange),
-    Greater(TextRange),
-    GreaterEq(TextRange),
-    Concat(TextRange),
-    BitAnd(TextRange),
-    BitOr(TextRange),
-    BitXor(TextRange),
-    BitNot(TextRange),
-    ShiftL(TextRange),
-    ShiftR(TextRange),
-
-    GetIter,
-    IterMoveNext,
-    IterCurrent,
-
-    BeginFuncSection,
+    // .3 (.2 for unary): The operator text range
+    Add(Reg, Reg, Reg, TextRange),
+    Sub(Reg, Reg, Reg, TextRange),
+    AddInt(Reg, Reg, i64, TextRange), // Fused by the peephole optimizer
+    SubInt(Reg, Reg, i64, TextRange), // Fused by the peephole optimizer
+    Mul(Reg, Reg, Reg, TextRange),
+    Div(Reg, Reg, Reg, TextRange),
+    Mod(Reg, Reg, Reg, TextRange),
+    Unm(Reg, Reg, TextRange),
+    Unp(Reg, Reg, TextRange),
+    Not(Reg, Reg, TextRange),
+    Eq(Reg, Reg, Reg, TextRange),
+    NotEq(Reg, Reg, Reg, TextRange),
+    Less(Reg, Reg, Reg, TextRange),
+    LessEq(Reg, Reg, Reg, TextRange),
+    Greater(Reg, Reg, Reg, TextRange),
+    GreaterEq(Reg, Reg, Reg, TextRange),
+    Concat(Reg, Reg, Reg, TextRange),
+    BitAnd(Reg, Reg, Reg, TextRange),
+    BitOr(Reg, Reg, Reg, TextRange),
+    BitXor(Reg, Reg, Reg, TextRange),
+    BitNot(Reg, Reg, TextRange),
+    ShiftL(Reg, Reg, Reg, TextRange),
+    ShiftR(Reg, Reg, Reg, TextRange),
+
+    // Exeption
+    // - The type of the format spec cannot be applied to the value.
+    // ---
+    // .3: The format spec text range
+    Format(Reg, Reg, Box<FormatSpec>, TextRange),
+
+    GetIter(Reg, Reg),
+    IterMoveNext(Reg, Reg),
+    IterCurrent(Reg, Reg),
+
+    BeginFuncSection(Reg),
     FuncSetProperty(u8, FunctionListId),
-    FuncAddCapture(il::LocalId),
+    FuncAddCapture(Reg),
     EndFuncSection,
 
-    // Nop,
-    Leave,
+    Nop,
+    Leave(Reg),
 
     Tombstone,
 }
+
+impl ICodeSource {
+    /// Returns the jump offset if the code is a jump.
+    pub(crate) fn jump_offset(&self) -> Option<isize> {
+        use ICodeSource::*;
+        match self {
+            Jump(offset)
+            | JumpIfTrue(_, offset)
+            | JumpIfFalse(_, offset)
+            | JumpIfNil(_, offset)
+            | JumpIfNotNil(_, offset)
+            | JumpIfNotLess(_, _, _, offset, _)
+            | JumpIfNotLessEq(_, _, _, offset, _)
+            | JumpIfNotGreater(_, _, _, offset, _)
+            | JumpIfNotGreaterEq(_, _, _, offset, _)
+            | JumpIfNotEq(_, _, _, offset, _) => Some(*offset),
+            _ => None,
+        }
+    }
+
+    /// Sets the jump offset. The code must be a jump.
+    pub(crate) fn set_jump_offset(&mut self, new_offset: isize) {
+        use ICodeSource::*;
+        match self {
+            Jump(offset)
+            | JumpIfTrue(_, offset)
+            | JumpIfFalse(_, offset)
+            | JumpIfNil(_, offset)
+            | JumpIfNotNil(_, offset)
+            | JumpIfNotLess(_, _, _, offset, _)
+            | JumpIfNotLessEq(_, _, _, offset, _)
+            | JumpIfNotGreater(_, _, _, offset, _)
+            | JumpIfNotGreaterEq(_, _, _, offset, _)
+            | JumpIfNotEq(_, _, _, offset, _) => *offset = new_offset,
+            code => panic!("[BUG] `{code:?}` is not a jump."),
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use super::*;
+
+/// Optimizes the code of a function.
+///
+/// 1. Threads the jumps to a `Jump` through to its destination.
+/// 2. Fuses the common sequences into the specialized instructions:
+///    - `LoadIntObject t k; Add d x t*` into `AddInt d x k` (and `Sub` into `SubInt`)
+///    - `Less t l r; JumpIfFalse t* o` into `JumpIfNotLess t l r o+1; JumpIfFalse t* o` (and the
+///      other comparisons). The `JumpIfFalse` is kept for the comparison by a method.
+/// 3. Removes the `Nop`s and the jumps to the next instruction, and fixes the jump offsets.
+///
+/// The text ranges are carried by the codes, so the source info built from the result is correct.
+pub(super) fn optimize(mut code: Vec<ICodeSource>) -> Vec<ICodeSource> {
+    thread_jumps(&mut code);
+    fuse(&mut code);
+    remove_nops(code)
+}
+
+fn thread_jumps(code: &mut [ICodeSource]) {
+    for i in 0..code.len() {
+        let Some(offset) = code[i].jump_offset() else {
+            continue;
+        };
+        let mut target = i as isize + offset;
+        // The number of hops is limited so as not to loop forever in `while true do end`.
+        for _ in 0..code.len() {
+            match code.get(target as usize) {
+                Some(ICodeSource::Jump(next)) if *next != 0 => target += next,
+                _ => break,
+            }
+        }
+        code[i].set_jump_offset(target - i as isize);
+    }
+}
+
+fn fuse(code: &mut [ICodeSource]) {
+    use ICodeSource::*;
+
+    let mut is_target = vec![false; code.len() + 1];
+    for (i, c) in code.iter().enumerate() {
+        if let Some(offset) = c.jump_offset() {
+            is_target[(i as isize + offset) as usize] = true;
+        }
+    }
+
+    let mut i = 0;
+    while i + 1 < code.len() {
+        let fused = match (&code[i], &code[i + 1]) {
+            // The `Add` must not be jumped to, because it is removed.
+            (LoadIntObject(t, k), Add(d, x, r, range))
+                if !is_target[i + 1] && *r == t.take() && x.plain() != *t =>
+            {
+                Some((AddInt(*d, *x, *k, *range), true))
+            }
+            (LoadIntObject(t, k), Sub(d, x, r, range))
+                if !is_target[i + 1] && *r == t.take() && x.plain() != *t =>
+            {
+                Some((SubInt(*d, *x, *k, *range), true))
+            }
+            (Less(d, l, r, range), JumpIfFalse(c, o)) if *c == d.take() => {
+                Some((JumpIfNotLess(*d, *l, *r, o + 1, *range), false))
+            }
+            (LessEq(d, l, r, range), JumpIfFalse(c, o)) if *c == d.take() => {
+                Some((JumpIfNotLessEq(*d, *l, *r, o + 1, *range), false))
+            }
+            (Greater(d, l, r, range), JumpIfFalse(c, o)) if *c == d.take() => {
+                Some((JumpIfNotGreater(*d, *l, *r, o + 1, *range), false))
+            }
+            (GreaterEq(d, l, r, range), JumpIfFalse(c, o)) if *c == d.take() => {
+                Some((JumpIfNotGreaterEq(*d, *l, *r, o + 1, *range), false))
+            }
+            (Eq(d, l, r, range), JumpIfFalse(c, o)) if *c == d.take() => {
+                Some((JumpIfNotEq(*d, *l, *r, o + 1, *range), false))
+            }
+            _ => None,
+        };
+        match fused {
+            Some((fused, removes_next)) => {
+                code[i] = fused;
+                if removes_next {
+                    code[i + 1] = Nop;
+                }
+                i += 2;
+            }
+            None => i += 1,
+        }
+    }
+}
+
+fn remove_nops(mut code: Vec<ICodeSource>) -> Vec<ICodeSource> {
+    // A jump to the next instruction, skipping only `Nop`s, does nothing.
+    for i in 0..code.len() {
+        if let ICodeSource::Jump(offset) = code[i] {
+            if offset > 0 && code[i + 1..i + offset as usize].iter().all(is_nop) {
+                code[i] = ICodeSource::Nop;
+            }
+        }
+    }
+
+    // `new_index[i]` is the index of `code[i]`, or of the next kept code if it is removed.
+    let mut new_index = Vec::with_capacity(code.len() + 1);
+    let mut kept = 0;
+    for c in code.iter() {
+        new_index.push(kept);
+        if !is_nop(c) {
+            kept += 1;
+        }
+    }
+    new_index.push(kept);
+
+    for (i, c) in code.iter_mut().enumerate() {
+        if let Some(offset) = c.jump_offset() {
+            let target = (i as isize + offset) as usize;
+            c.set_jump_offset(new_index[target] as isize - new_index[i] as isize);
+        }
+    }
+    code.retain(|c| !is_nop(c));
+    code
+}
+
+fn is_nop(code: &ICodeSource) -> bool {
+    matches!(code, ICodeSource::Nop)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use foundation::syntax::TextRange;
+    use pretty_assertions::assert_eq;
+
+    use super::ICodeSource::*;
+
+    fn r(i: usize) -> Reg {
+        Reg::new(i)
+    }
+
+    #[test]
+    fn thread_jump_chain() {
+        let code = vec![
+            JumpIfFalse(r(0), 2), // 0: -> 2 -> 4
+            LoadNilObject(r(1)),  // 1:
+            Jump(2),              // 2: -> 4
+            LoadNilObject(r(1)),  // 3:
+            Leave(r(1)),          // 4:
+        ];
+        assert_eq!(
+            optimize(code),
+            vec![
+                JumpIfFalse(r(0), 4),
+                LoadNilObject(r(1)),
+                Jump(2),
+                LoadNilObject(r(1)),
+                Leave(r(1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn fuse_add_int() {
+        let range = TextRange::default();
+        let code = vec![
+            LoadIntObject(r(2), 1),              // 0:
+            Add(r(1), r(1), r(2).take(), range), // 1:
+            Jump(-2),                            // 2: -> 0
+        ];
+        assert_eq!(optimize(code), vec![AddInt(r(1), r(1), 1, range), Jump(-1)]);
+    }
+
+    #[test]
+    fn no_fuse_jump_target() {
+        let range = TextRange::default();
+        let code = vec![
+            LoadIntObject(r(2), 1),              // 0:
+            Add(r(1), r(1), r(2).take(), range), // 1:
+            Jump(-1),                            // 2: -> 1
+        ];
+        assert_eq!(optimize(code.clone()), code);
+    }
+
+    #[test]
+    fn fuse_compare_and_jump() {
+        let range = TextRange::default();
+        let code = vec![
+            Less(r(2), r(0), r(1), range), // 0:
+            JumpIfFalse(r(2).take(), 3),   // 1: -> 4
+            Jump(1),                       // 2: -> 3 (removed)
+            LoadNilObject(r(2)),           // 3:
+            Leave(r(2).take()),            // 4:
+        ];
+        assert_eq!(
+            optimize(code),
+            vec![
+                JumpIfNotLess(r(2), r(0), r(1), 3, range),
+                JumpIfFalse(r(2).take(), 2),
+                LoadNilObject(r(2)),
+                Leave(r(2).take()),
+            ]
+        );
+    }
+}
@@ -6,6 +6,8 @@ use std::rc::Rc;
 
 pub struct FunctionCapture {
     pub(crate) map: FxHashMap<FunctionCaptureKey, FxHashSet<CompactString>>,
+    /// The names of the locals of each function which are captured by the nested functions.
+    pub(crate) escape_map: FxHashMap<FunctionCaptureKey, FxHashSet<CompactString>>,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -38,7 +40,7 @@ impl From<&Module> for FunctionCaptureKey {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub enum CaptureHashSetRef<'s> {
     Empty,
     Occupied(&'s FxHashSet<CompactString>),
@@ -56,6 +58,17 @@ impl FunctionCapture {
         }
     }
 
+    /// Returns the names of the locals of the function `key` which are captured by the functions
+    /// nested in it.
+    ///
+    /// This is by name, so a local shadowed by another local of the same name is also included.
+    pub fn get_escape(&self, key: impl Into<FunctionCaptureKey>) -> CaptureHashSetRef<'_> {
+        match self.escape_map.get(&key.into()) {
+            Some(set) => CaptureHashSetRef::Occupied(set),
+            None => CaptureHashSetRef::Empty,
+        }
+    }
+
     pub fn iter_captures(
         &self,
     ) -> impl Iterator<Item = (&FunctionCaptureKey, &FxHashSet<CompactString>)> {
@@ -231,6 +244,7 @@ impl<'strage> Walkable<'strage> for Effect {
                 table,
                 name: _,
                 args,
+                optional: _,
             } => {
                 w.go(table);
                 for (_, arg) in w.strage.get(args) {
@@ -279,6 +293,21 @@ impl<'strage> Walkable<'strage> for Value {
                     w.go(else_tail);
                 });
             }
+            Match { value, arms } => {
+                w.go(value);
+                for MatchArm {
+                    pattern,
+                    body,
+                    tail,
+                } in arms.iter()
+                {
+                    w.go_branch(|w| {
+                        w.go(pattern);
+                        w.go(body);
+                        w.go(tail);
+                    });
+                }
+            }
             Prefix { op: _, value } => {
                 w.go(value);
             }
@@ -296,13 +325,18 @@ impl<'strage> Walkable<'strage> for Value {
                 w.go(value);
                 w.go(index);
             }
-            Field { value, name: _ } => {
+            Field {
+                value,
+                name: _,
+                optional: _,
+            } => {
                 w.go(value);
             }
             MethodCall {
                 value,
                 name: _,
                 args,
+                optional: _,
             } => {
                 w.go(value);
                 for (_, arg) in w.strage.get(args) {
@@ -346,6 +380,34 @@ impl<'strage> Walkable<'strage> for Value {
                     w.go(value);
                 }
             }
+            Interpolation { parts } => {
+                for part in parts.iter() {
+                    if let InterpolationPart::Value(value, _) = part {
+                        w.go(value);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<'strage> Walkable<'strage> for Pattern {
+    fn accept(&self, w: &mut Walker<'strage>) {
+        match self {
+            Pattern::Wildcard | Pattern::Literal(_) => {}
+            Pattern::Binding(name) => {
+                w.insert_def(name);
+            }
+            Pattern::Table(fields) => {
+                for (_, pattern) in fields.iter() {
+                    w.go(pattern);
+                }
+            }
+            Pattern::Array(elements) => {
+                for pattern in elements.iter() {
+                    w.go(pattern);
+                }
+            }
         }
     }
 }
@@ -373,6 +435,7 @@ mod internal {
     pub(crate) fn walk(module: &Module, defaults: Rc<[&'static str]>) -> FunctionCapture {
         let db = Rc::new(RefCell::new(FunctionCapture {
             map: FxHashMap::default(),
+            escape_map: FxHashMap::default(),
         }));
         let mut walker = Walker {
             strage: module.strage(),
@@ -461,6 +524,15 @@ mod internal {
                     syntax.parent().unwrap().parent().unwrap().text(),
                 );
             } else {
+                // The default variables are the locals of the module.
+                if let Some((owner, _)) = self.master.get(found_index.max(0) as usize) {
+                    self.db
+                        .borrow_mut()
+                        .escape_map
+                        .entry(*owner)
+                        .or_default()
+                        .insert(CompactString::from(symbol_str));
+                }
                 for (func, defs) in &mut self.master[(found_index + 1) as usize..] {
                     defs.insert(symbol_str, 1);
                     self.db
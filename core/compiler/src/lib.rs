@@ -1,7 +1,9 @@
 mod compile;
 mod lower_ast;
+mod optimize;
 
 pub mod database;
 
 pub use compile::compile;
 pub use lower_ast::lower_ast;
+pub use optimize::optimize;
@@ -1,25 +1,25 @@
 use compact_str::CompactString;
 use foundation::{
     ir,
-    syntax::{ast, ast::AstNode, SyntaxError, SyntaxNode, SyntaxToken},
+    syntax::{ast, ast::AstNode, SyntaxError, SyntaxNode, SyntaxToken, SyntaxWarning},
 };
 
 mod effect;
 use effect::effect;
 
 mod value;
-use value::value;
+use value::{binary_op, value};
 
 mod context;
 use context::{Context, ScopeKind};
 
-pub fn lower_ast(program: ast::Program) -> (ir::Module, Vec<SyntaxError>) {
+pub fn lower_ast(program: ast::Program) -> (ir::Module, Vec<SyntaxError>, Vec<SyntaxWarning>) {
     let mut ctx = Context::new();
     let effects: Vec<_> = program.into_lowered(&mut ctx);
     let key = ctx.builder.add_effects(effects);
-    let (mod_builder, errors) = ctx.finish();
+    let (mod_builder, errors, warnings) = ctx.finish();
     let module = mod_builder.finish_with(key);
-    (module, errors)
+    (module, errors, warnings)
 }
 
 trait IntoLowered<T> {
@@ -1,13 +1,14 @@
 use core::mem::forget;
 use foundation::{
     ir::{ModuleBuilder, ScopeIndex},
-    syntax::{SyntaxError, TextRange},
+    syntax::{SyntaxError, SyntaxWarning, TextRange},
 };
 use std::borrow::Cow;
 
 pub(super) struct Context {
     pub(super) builder: ModuleBuilder,
     errors: Vec<SyntaxError>,
+    warnings: Vec<SyntaxWarning>,
     current_scope: ScopeIndex,
     next_scope: ScopeIndex,
     in_loop_scope: bool,
@@ -19,23 +20,25 @@ impl Context {
         Self {
             builder: ModuleBuilder::new(),
             errors: Vec::new(),
+            warnings: Vec::new(),
             current_scope,
             next_scope: current_scope.make_next(),
             in_loop_scope: false,
         }
     }
 
-    pub(super) fn finish(self) -> (ModuleBuilder, Vec<SyntaxError>) {
+    pub(super) fn finish(self) -> (ModuleBuilder, Vec<SyntaxError>, Vec<SyntaxWarning>) {
         let Context {
             builder,
             errors,
+            warnings,
             current_scope,
             next_scope: _,
             in_loop_scope,
         } = self;
         debug_assert_eq!(current_scope.as_u32(), 1);
         debug_assert!(!in_loop_scope);
-        (builder, errors)
+        (builder, errors, warnings)
     }
 
     pub(super) fn is_in_loop(&self) -> bool {
@@ -50,6 +53,11 @@ impl Context {
         self.errors.push(SyntaxError::new(message.into(), range));
     }
 
+    pub(super) fn push_warning(&mut self, message: impl Into<Cow<'static, str>>, range: TextRange) {
+        self.warnings
+            .push(SyntaxWarning::new(message.into(), range));
+    }
+
     pub(super) fn start_scope(&mut self, kind: ScopeKind) -> ScopeMarker {
         let current = self.current_scope;
         let in_loop = self.in_loop_scope;
@@ -185,6 +185,10 @@ pub(super) fn effect(ctx: &mut Context, statement: ast::Statement) -> ir::Effect
                 ast::Expression::Paren(_) => {
                     unreachable!("Paren expression is unwrapped above while loop");
                 }
+                ast::Expression::Match(node) => {
+                    let sn = node.syntax().clone();
+                    (sn, value(ctx, ast::Expression::Match(node)))
+                }
                 ast::Expression::Prefix(node) => {
                     let op = node.op().map(|(_, op)| op);
                     let range = node.syntax().text_range();
@@ -236,6 +240,11 @@ pub(super) fn effect(ctx: &mut Context, statement: ast::Statement) -> ir::Effect
                     let sn = node.syntax().clone();
                     (sn, value(ctx, ast::Expression::FuncConst(node)))
                 }
+                ast::Expression::InterpolatedString(node) => {
+                    ctx.push_error("Unexpected string value", node.syntax().text_range());
+                    let sn = node.syntax().clone();
+                    (sn, value(ctx, ast::Expression::InterpolatedString(node)))
+                }
             };
             ir::Effect::NoEffectValue {
                 value: ctx.builder.add_value(no_effect_value),
@@ -295,6 +304,7 @@ fn method_call_(ctx: &mut Context, node: ast::MethodCallExpr) -> ir::Effect {
         table: ctx.builder.add_value(value),
         name: ctx.builder.add_string(name),
         args: ctx.builder.add_value_many(args),
+        optional: node.is_optional(),
     }
 }
 
@@ -378,9 +388,19 @@ fn if_(ctx: &mut Context, node: ast::IfExpr) -> ir::Effect {
     }
 }
 
+fn unwrap_paren(mut expr: ast::Expression) -> Option<ast::Expression> {
+    while let ast::Expression::Paren(paren) = expr {
+        expr = paren.expr()?;
+    }
+    Some(expr)
+}
+
 fn binary_(ctx: &mut Context, node: ast::BinaryExpr) -> ir::Effect {
     let op_token = match node.op() {
         Some((token, ast::BinaryOp::Assign)) => token,
+        Some((token, op)) if op.compound_assign_op().is_some() => {
+            return compound_assign_(ctx, node, token, op);
+        }
         op => {
             let op = op.map(|(_, op)| op);
             let range = node.syntax().text_range();
@@ -407,24 +427,16 @@ fn binary_(ctx: &mut Context, node: ast::BinaryExpr) -> ir::Effect {
         (sn, value(ctx, expr))
     });
 
-    let lhs = {
-        fn unwrap_paren(mut expr: ast::Expression) -> Option<ast::Expression> {
-            while let ast::Expression::Paren(paren) = expr {
-                expr = paren.expr()?;
-            }
-            Some(expr)
-        }
-        match node.lhs().and_then(unwrap_paren) {
-            Some(lhs) => lhs,
-            None => {
-                ctx.push_error("Invalid left-hand side expression", op_token.text_range());
-                // lhs error is no need to report an error because `()` expression (invalid) should be
-                // reported by the parser.
-                return ir::Effect::SetLocal {
-                    local: ctx.builder.add_symbol(None),
-                    value: ctx.builder.add_value(rhs),
-                };
-            }
+    let lhs = match node.lhs().and_then(unwrap_paren) {
+        Some(lhs) => lhs,
+        None => {
+            ctx.push_error("Invalid left-hand side expression", op_token.text_range());
+            // lhs error is no need to report an error because `()` expression (invalid) should be
+            // reported by the parser.
+            return ir::Effect::SetLocal {
+                local: ctx.builder.add_symbol(None),
+                value: ctx.builder.add_value(rhs),
+            };
         }
     };
     match lhs {
@@ -444,6 +456,7 @@ fn binary_(ctx: &mut Context, node: ast::BinaryExpr) -> ir::Effect {
             }
         }
         ast::Expression::Field(node) => {
+            reject_optional_target(ctx, &node);
             let target = node.expr().map(|expr| {
                 let sn = expr.syntax().clone();
                 (sn, value(ctx, expr))
@@ -474,6 +487,7 @@ fn binary_(ctx: &mut Context, node: ast::BinaryExpr) -> ir::Effect {
         }
         ast::Expression::Paren(_) => unreachable!("Paren expression is unwrapped above"),
         ast::Expression::If(_) => todo!(),
+        ast::Expression::Match(node) => invalid_target(ctx, node.syntax(), rhs),
         ast::Expression::Do(_) => todo!(),
         ast::Expression::Call(_) => todo!(),
         ast::Expression::MethodCall(_) => todo!(),
@@ -483,5 +497,203 @@ fn binary_(ctx: &mut Context, node: ast::BinaryExpr) -> ir::Effect {
         ast::Expression::ArrayConst(_) => todo!(),
         ast::Expression::TableConst(_) => todo!(),
         ast::Expression::FuncConst(_) => todo!(),
+        ast::Expression::InterpolatedString(node) => invalid_target(ctx, node.syntax(), rhs),
+    }
+}
+
+/// Reports `target` as an invalid left-hand side, and lowers the assignment of `rhs` to nowhere.
+fn invalid_target(
+    ctx: &mut Context,
+    target: &SyntaxNode,
+    rhs: Option<(SyntaxNode, ir::Value)>,
+) -> ir::Effect {
+    ctx.push_error("Invalid left-hand side expression", target.text_range());
+    ir::Effect::SetLocal {
+        local: ctx.builder.add_symbol(None),
+        value: ctx.builder.add_value(rhs),
+    }
+}
+
+fn reject_optional_target(ctx: &mut Context, node: &ast::FieldExpr) {
+    if let Some(token) = node.question_dot_token() {
+        ctx.push_error(
+            "Optional chaining is not allowed in assignment target",
+            token.text_range(),
+        );
+    }
+}
+
+/// Lowers `[lhs] [op]= [rhs]` to `[lhs] = [lhs] [op] [rhs]`, where the container (and the index) of
+/// `[lhs]` is evaluated only once.
+fn compound_assign_(
+    ctx: &mut Context,
+    node: ast::BinaryExpr,
+    op_token: SyntaxToken,
+    op: ast::BinaryOp,
+) -> ir::Effect {
+    let op = op
+        .compound_assign_op()
+        .expect("`op` must be a compound assignment operator");
+    let lhs = match node.lhs().and_then(unwrap_paren) {
+        Some(
+            lhs @ (ast::Expression::Index(_)
+            | ast::Expression::Field(_)
+            | ast::Expression::LocalVar(_)),
+        ) => lhs,
+        lhs => {
+            let range = lhs.map_or(op_token.text_range(), |lhs| lhs.syntax().text_range());
+            ctx.push_error("Invalid left-hand side expression", range);
+            let rhs = node.rhs().map(|expr| {
+                let sn = expr.syntax().clone();
+                (sn, value(ctx, expr))
+            });
+            return ir::Effect::SetLocal {
+                local: ctx.builder.add_symbol(None),
+                value: ctx.builder.add_value(rhs),
+            };
+        }
+    };
+    let lhs_sn = lhs.syntax().clone();
+
+    // [lhs] [op] [rhs]
+    let make_value = |ctx: &mut Context, current: ir::Value| {
+        let rhs = node.rhs().map(|expr| {
+            let sn = expr.syntax().clone();
+            (sn, value(ctx, expr))
+        });
+        let value = ir::Value::Binary {
+            op: binary_op(ctx, op, op_token),
+            lhs: ctx.builder.add_value(Some((lhs_sn, current))),
+            rhs: ctx.builder.add_value(rhs),
+        };
+        ctx.builder.add_value(Some((node.syntax().clone(), value)))
+    };
+
+    match lhs {
+        ast::Expression::Index(lhs) => {
+            let scope = ctx.start_scope(ScopeKind::Nest);
+            let mut effects = Vec::new();
+            let target = OnceValue::new(ctx, lhs.expr(), "@target", &mut effects);
+            let index = OnceValue::new(ctx, lhs.index(), "@index", &mut effects);
+            let current = ir::Value::Index {
+                value: target.get(ctx),
+                index: index.get(ctx),
+            };
+            let effect = ir::Effect::SetIndex {
+                target: target.get(ctx),
+                index: index.get(ctx),
+                value: make_value(ctx, current),
+            };
+            effects.push((node.syntax().clone(), effect));
+            scope.finish(ctx);
+            ir::Effect::Scope {
+                body: ctx.builder.add_effects(effects),
+            }
+        }
+        ast::Expression::Field(lhs) => {
+            reject_optional_target(ctx, &lhs);
+            let scope = ctx.start_scope(ScopeKind::Nest);
+            let mut effects = Vec::new();
+            let target = OnceValue::new(ctx, lhs.expr(), "@target", &mut effects);
+            let field = lhs
+                .field()
+                .and_then(|name| name.ident_token())
+                .map(|token| {
+                    let string = CompactString::from(token.text());
+                    (token, string)
+                });
+            let current = ir::Value::Field {
+                value: target.get(ctx),
+                name: ctx.builder.add_string(field.clone()),
+                optional: false,
+            };
+            let effect = ir::Effect::SetField {
+                target: target.get(ctx),
+                field: ctx.builder.add_string(field),
+                value: make_value(ctx, current),
+            };
+            effects.push((node.syntax().clone(), effect));
+            scope.finish(ctx);
+            ir::Effect::Scope {
+                body: ctx.builder.add_effects(effects),
+            }
+        }
+        ast::Expression::LocalVar(lhs) => {
+            let symbol = lhs.ident_token().map(|token| {
+                let scope = ctx.scope_index();
+                let text = CompactString::from(token.text());
+                (token, ir::Symbol::new(text, scope))
+            });
+            let current = ir::Value::Local {
+                name: ctx.builder.add_symbol(symbol.clone()),
+            };
+            ir::Effect::SetLocal {
+                local: ctx.builder.add_symbol(symbol),
+                value: make_value(ctx, current),
+            }
+        }
+        _ => unreachable!("Invalid lhs is handled above"),
+    }
+}
+
+/// The part of the compound assignment target, which must be evaluated only once.
+enum OnceValue {
+    Missing,
+    /// The expression without side effects, re-evaluated at each use.
+    Pure(SyntaxNode),
+    /// The result of the expression stored in the hidden local variable.
+    Stored(SyntaxNode, SyntaxToken, ir::Symbol),
+}
+
+impl OnceValue {
+    /// `name` must not be a valid identifier not to conflict with user variables.
+    fn new(
+        ctx: &mut Context,
+        expr: Option<ast::Expression>,
+        name: &str,
+        effects: &mut Vec<(SyntaxNode, ir::Effect)>,
+    ) -> Self {
+        let Some(expr) = expr else {
+            return OnceValue::Missing;
+        };
+        if matches!(
+            expr,
+            ast::Expression::LocalVar(_) | ast::Expression::Literal(_)
+        ) {
+            return OnceValue::Pure(expr.syntax().clone());
+        }
+        let sn = expr.syntax().clone();
+        let Some(token) = sn.first_token() else {
+            return OnceValue::Missing;
+        };
+        let symbol = ir::Symbol::new(CompactString::from(name), ctx.scope_index());
+        let value = value(ctx, expr);
+        let effect = ir::Effect::MakeLocal {
+            name: ctx
+                .builder
+                .add_symbol(Some((token.clone(), symbol.clone()))),
+            value: ctx.builder.add_value(Some((sn.clone(), value))),
+        };
+        effects.push((sn.clone(), effect));
+        OnceValue::Stored(sn, token, symbol)
+    }
+
+    fn get(&self, ctx: &mut Context) -> ir::ValueKey {
+        match self {
+            OnceValue::Missing => ctx.builder.add_value(None),
+            OnceValue::Pure(sn) => {
+                let expr = ast::Expression::cast(sn.clone()).unwrap();
+                let value = value(ctx, expr);
+                ctx.builder.add_value(Some((sn.clone(), value)))
+            }
+            OnceValue::Stored(sn, token, symbol) => {
+                let value = ir::Value::Local {
+                    name: ctx
+                        .builder
+                        .add_symbol(Some((token.clone(), symbol.clone()))),
+                };
+                ctx.builder.add_value(Some((sn.clone(), value)))
+            }
+        }
     }
 }
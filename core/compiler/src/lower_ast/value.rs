@@ -1,6 +1,11 @@
 use super::*;
 use compact_str::CompactString;
-use foundation::object::UString;
+use core::{iter::Peekable, str::CharIndices};
+use foundation::{
+    object::{FormatSpec, FormatSpecParseError, UString},
+    syntax::{TextRange, TextSize, T},
+};
+use std::borrow::Cow;
 
 pub(super) fn value(ctx: &mut Context, expression: ast::Expression) -> ir::Value {
     match expression {
@@ -108,6 +113,49 @@ pub(super) fn value(ctx: &mut Context, expression: ast::Expression) -> ir::Value
             }
         }
 
+        // match [expr] with [| [pattern] then [body]]* end
+        ast::Expression::Match(node) => {
+            let scrutinee = node.scrutinee().map(|expr| {
+                let sn = expr.syntax().clone();
+                (sn, value(ctx, expr))
+            });
+            let mut has_wildcard = false;
+            let arms = node
+                .arms()
+                .map(|arm| {
+                    let scope = ctx.start_scope(ScopeKind::Nest);
+                    let pattern = match arm.pattern() {
+                        Some(node) => pattern(ctx, node),
+                        None => ir::Pattern::Wildcard, // error is reported in parser
+                    };
+                    has_wildcard |= pattern.is_irrefutable();
+                    let (body, tail) = match arm.body() {
+                        Some(body) => body.into_lowered(ctx),
+                        None => (Vec::new(), None),
+                    };
+                    scope.finish(ctx);
+                    ir::MatchArm {
+                        pattern,
+                        body: ctx.builder.add_effects(body),
+                        tail: ctx.builder.add_value(tail),
+                    }
+                })
+                .collect::<Box<[_]>>();
+            if !has_wildcard {
+                let range = node
+                    .match_token()
+                    .map_or(node.syntax().text_range(), |token| token.text_range());
+                ctx.push_warning(
+                    "'match' expression has no wildcard arm, evaluates to nil if no arm matches",
+                    range,
+                );
+            }
+            ir::Value::Match {
+                value: ctx.builder.add_value(scrutinee),
+                arms,
+            }
+        }
+
         // do [body] end
         ast::Expression::Do(node) => {
             let scope = ctx.start_scope(ScopeKind::Nest);
@@ -154,34 +202,7 @@ pub(super) fn value(ctx: &mut Context, expression: ast::Expression) -> ir::Value
                 lhs: ctx.builder.add_value(lhs),
                 rhs: ctx.builder.add_value(rhs),
                 op: match node.op() {
-                    Some((token, op)) => match op {
-                        ast::BinaryOp::Add => ir::BinaryOp::Add(token),
-                        ast::BinaryOp::Sub => ir::BinaryOp::Sub(token),
-                        ast::BinaryOp::Mul => ir::BinaryOp::Mul(token),
-                        ast::BinaryOp::Div => ir::BinaryOp::Div(token),
-                        ast::BinaryOp::Mod => ir::BinaryOp::Mod(token),
-                        ast::BinaryOp::Shl => ir::BinaryOp::Shl(token),
-                        ast::BinaryOp::Shr => ir::BinaryOp::Shr(token),
-                        ast::BinaryOp::Concat => ir::BinaryOp::Concat(token),
-                        ast::BinaryOp::Eq => ir::BinaryOp::Eq(token),
-                        ast::BinaryOp::Ne => ir::BinaryOp::Ne(token),
-                        ast::BinaryOp::Lt => ir::BinaryOp::Lt(token),
-                        ast::BinaryOp::Le => ir::BinaryOp::Le(token),
-                        ast::BinaryOp::Gt => ir::BinaryOp::Gt(token),
-                        ast::BinaryOp::Ge => ir::BinaryOp::Ge(token),
-                        ast::BinaryOp::And => ir::BinaryOp::And(token),
-                        ast::BinaryOp::Or => ir::BinaryOp::Or(token),
-                        ast::BinaryOp::BitAnd => ir::BinaryOp::BitAnd(token),
-                        ast::BinaryOp::BitOr => ir::BinaryOp::BitOr(token),
-                        ast::BinaryOp::BitXor => ir::BinaryOp::BitXor(token),
-                        ast::BinaryOp::Assign => {
-                            ctx.push_error(
-                                "Assignment operator is not allowed in expression. For equality comparison, use `==` instead of `=`.",
-                                token.text_range(),
-                            );
-                            ir::BinaryOp::Assign(token)
-                        }
-                    },
+                    Some((token, op)) => binary_op(ctx, op, token),
                     None => ir::BinaryOp::Missing,
                 },
             }
@@ -223,7 +244,7 @@ pub(super) fn value(ctx: &mut Context, expression: ast::Expression) -> ir::Value
             }
         }
 
-        // [expr].[ident]
+        // [expr].[ident] / [expr]?.[ident]
         ast::Expression::Field(node) => {
             let value = node.expr().map(|expr| {
                 let sn = expr.syntax().clone();
@@ -239,10 +260,11 @@ pub(super) fn value(ctx: &mut Context, expression: ast::Expression) -> ir::Value
             ir::Value::Field {
                 value: ctx.builder.add_value(value),
                 name: ctx.builder.add_string(name),
+                optional: node.is_optional(),
             }
         }
 
-        // [expr]->[ident]( [arg_list] )
+        // [expr]->[ident]( [arg_list] ) / [expr]?->[ident]( [arg_list] )
         ast::Expression::MethodCall(node) => {
             let value = node.expr().map(|expr| {
                 let sn = expr.syntax().clone();
@@ -263,6 +285,7 @@ pub(super) fn value(ctx: &mut Context, expression: ast::Expression) -> ir::Value
                 value: ctx.builder.add_value(value),
                 name: ctx.builder.add_string(name),
                 args: ctx.builder.add_value_many(args),
+                optional: node.is_optional(),
             }
         }
 
@@ -354,12 +377,22 @@ pub(super) fn value(ctx: &mut Context, expression: ast::Expression) -> ir::Value
                         "We expected all strings must start with `\"` or `'`, because these char are used in lexer as marker of string start. but got: {:?}",
                         start
                     );
-                    if start == end {
+                    if text.len() >= 2 && start == end {
                         text = &text[1..(text.len() - 1)];
                     } else {
                         // non-terminated error is handled in parser
                         text = &text[1..text.len()];
                     }
+                    let offset = token.text_range().start() + TextSize::of(start);
+                    ir::Value::String(string_content(ctx, text, offset))
+                }
+                ast::LiteralKind::RawString(token) => {
+                    // r"...", r'...'
+                    let text = &token.text()[1..];
+                    let quote = text.chars().next().unwrap_or('\0');
+                    let text = &text[1..];
+                    // non-terminated error is handled in parser
+                    let text = text.strip_suffix(quote).unwrap_or(text);
                     ir::Value::String(UString::from(text))
                 }
                 ast::LiteralKind::Bool(value) => ir::Value::Bool(value),
@@ -451,5 +484,277 @@ pub(super) fn value(ctx: &mut Context, expression: ast::Expression) -> ir::Value
             let func_key = ctx.builder.add_function(params, effects);
             ir::Value::Function(func_key)
         }
+
+        // "[string]{[expr]:[format_spec]}[string]{[expr]}[string]"
+        ast::Expression::InterpolatedString(node) => {
+            let mut quote = '"';
+            let mut parts = Vec::new();
+            for part in node.parts() {
+                match part {
+                    ast::InterpolatedStringPart::String(token) => {
+                        let text = token.text();
+                        let text = match token.kind() {
+                            T![string_start] => {
+                                quote = text.chars().next().unwrap_or('"');
+                                &text[1..(text.len() - 1)]
+                            }
+                            T![string_mid] => &text[1..(text.len() - 1)],
+                            T![string_end] => {
+                                // non-terminated error is handled in parser
+                                let text = &text[1..];
+                                text.strip_suffix(quote).unwrap_or(text)
+                            }
+                            _ => unreachable!(),
+                        };
+                        let offset = token.text_range().start() + TextSize::from(1);
+                        let string = string_content(ctx, text, offset);
+                        parts.push(ir::InterpolationPart::String(token, string));
+                    }
+                    ast::InterpolatedStringPart::Interpolation(interpolation) => {
+                        let Some(expr) = interpolation.expr() else {
+                            continue; // missing expression error is handled in parser
+                        };
+                        let sn = expr.syntax().clone();
+                        let value = value(ctx, expr);
+                        let format_spec = interpolation.format_spec_token().and_then(|token| {
+                            // The format spec token always starts with ':'.
+                            match FormatSpec::parse(&token.text()[1..]) {
+                                Ok(format_spec) => Some((token, format_spec)),
+                                Err(err) => {
+                                    let range = token.text_range();
+                                    let range = match err {
+                                        FormatSpecParseError::UnexpectedChar(offset) => {
+                                            let c = token.text()[(offset + 1)..].chars().next();
+                                            let start =
+                                                range.start() + TextSize::from(offset as u32 + 1);
+                                            let len = TextSize::of(c.unwrap_or_default());
+                                            TextRange::at(start, len)
+                                        }
+                                        _ => range,
+                                    };
+                                    ctx.push_error(err.to_string(), range);
+                                    None
+                                }
+                            }
+                        });
+                        let value = ctx.builder.add_value((sn, value));
+                        parts.push(ir::InterpolationPart::Value(value, format_spec));
+                    }
+                }
+            }
+            ir::Value::Interpolation {
+                parts: parts.into_boxed_slice(),
+            }
+        }
     }
 }
+
+fn pattern(ctx: &mut Context, node: ast::Pattern) -> ir::Pattern {
+    match node {
+        // _
+        ast::Pattern::Binding(node) if node.is_wildcard() => ir::Pattern::Wildcard,
+
+        // [ident]
+        ast::Pattern::Binding(node) => {
+            let symbol = node.ident_token().map(|token| {
+                let scope = ctx.scope_index();
+                let text = CompactString::from(token.text());
+                (token, ir::Symbol::new(text, scope))
+            });
+            ir::Pattern::Binding(ctx.builder.add_symbol(symbol))
+        }
+
+        // -?[literal]
+        ast::Pattern::Literal(node) => {
+            let literal = node.literal().map(|literal| {
+                let sn = node.syntax().clone();
+                let value = value(ctx, ast::Expression::Literal(literal));
+                let value = match (node.minus_token(), value) {
+                    (None, value) => value,
+                    (Some(_), ir::Value::Int(x)) => ir::Value::Int(x.wrapping_neg()),
+                    (Some(_), ir::Value::Float(x)) => ir::Value::Float(-x),
+                    (Some(_), value) => value, // error is reported in parser
+                };
+                (sn, value)
+            });
+            ir::Pattern::Literal(ctx.builder.add_value(literal))
+        }
+
+        // { [ident] (= [pattern])?, ... }
+        ast::Pattern::Table(node) => {
+            let fields = node
+                .fields()
+                .filter_map(|field| {
+                    let token = field.name()?.ident_token()?;
+                    let pattern = match field.pattern() {
+                        Some(node) => pattern(ctx, node),
+                        None if field.eq_token().is_some() => ir::Pattern::Wildcard, // error is reported in parser
+                        None => {
+                            let scope = ctx.scope_index();
+                            let text = CompactString::from(token.text());
+                            let symbol = ir::Symbol::new(text, scope);
+                            ir::Pattern::Binding(ctx.builder.add_symbol((token.clone(), symbol)))
+                        }
+                    };
+                    let key = CompactString::from(token.text());
+                    Some((ctx.builder.add_string((token, key)), pattern))
+                })
+                .collect();
+            ir::Pattern::Table(fields)
+        }
+
+        // [ [pattern], ... ]
+        ast::Pattern::Array(node) => {
+            let elements = node
+                .elements()
+                .map(|element| pattern(ctx, element))
+                .collect();
+            ir::Pattern::Array(elements)
+        }
+    }
+}
+
+pub(super) fn binary_op(ctx: &mut Context, op: ast::BinaryOp, token: SyntaxToken) -> ir::BinaryOp {
+    match op {
+        ast::BinaryOp::Add => ir::BinaryOp::Add(token),
+        ast::BinaryOp::Sub => ir::BinaryOp::Sub(token),
+        ast::BinaryOp::Mul => ir::BinaryOp::Mul(token),
+        ast::BinaryOp::Div => ir::BinaryOp::Div(token),
+        ast::BinaryOp::Mod => ir::BinaryOp::Mod(token),
+        ast::BinaryOp::Shl => ir::BinaryOp::Shl(token),
+        ast::BinaryOp::Shr => ir::BinaryOp::Shr(token),
+        ast::BinaryOp::Concat => ir::BinaryOp::Concat(token),
+        ast::BinaryOp::Eq => ir::BinaryOp::Eq(token),
+        ast::BinaryOp::Ne => ir::BinaryOp::Ne(token),
+        ast::BinaryOp::Lt => ir::BinaryOp::Lt(token),
+        ast::BinaryOp::Le => ir::BinaryOp::Le(token),
+        ast::BinaryOp::Gt => ir::BinaryOp::Gt(token),
+        ast::BinaryOp::Ge => ir::BinaryOp::Ge(token),
+        ast::BinaryOp::And => ir::BinaryOp::And(token),
+        ast::BinaryOp::Or => ir::BinaryOp::Or(token),
+        ast::BinaryOp::BitAnd => ir::BinaryOp::BitAnd(token),
+        ast::BinaryOp::BitOr => ir::BinaryOp::BitOr(token),
+        ast::BinaryOp::BitXor => ir::BinaryOp::BitXor(token),
+        ast::BinaryOp::NilCoalesce => ir::BinaryOp::NilCoalesce(token),
+        ast::BinaryOp::Assign => {
+            ctx.push_error(
+                "Assignment operator is not allowed in expression. For equality comparison, use `==` instead of `=`.",
+                token.text_range(),
+            );
+            ir::BinaryOp::Assign(token)
+        }
+        ast::BinaryOp::AddAssign
+        | ast::BinaryOp::SubAssign
+        | ast::BinaryOp::MulAssign
+        | ast::BinaryOp::DivAssign
+        | ast::BinaryOp::ModAssign
+        | ast::BinaryOp::ShlAssign
+        | ast::BinaryOp::ShrAssign
+        | ast::BinaryOp::ConcatAssign
+        | ast::BinaryOp::BitAndAssign
+        | ast::BinaryOp::BitOrAssign
+        | ast::BinaryOp::BitXorAssign => {
+            ctx.push_error(
+                "Compound assignment operator is not allowed in expression.",
+                token.text_range(),
+            );
+            ir::BinaryOp::Assign(token)
+        }
+    }
+}
+
+/// Converts the text of string literal (without quotes) to the string content, resolving the escape
+/// sequences and the escaped braces. `offset` is the start position of the `text` in the source.
+fn string_content(ctx: &mut Context, text: &str, offset: TextSize) -> UString {
+    let mut content = String::with_capacity(text.len());
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        match c {
+            '\\' => match unescape(&mut chars) {
+                Ok(Some(c)) => content.push(c),
+                Ok(None) => {}
+                Err(message) => {
+                    let end = chars.peek().map_or(text.len(), |(i, _)| *i);
+                    let range = TextRange::new(
+                        offset + TextSize::from(start as u32),
+                        offset + TextSize::from(end as u32),
+                    );
+                    ctx.push_error(message, range);
+                }
+            },
+            // "{{" and "}}" are the escaped braces, see the lexer.
+            '{' | '}' if chars.peek().map(|(_, c)| *c) == Some(c) => {
+                chars.next();
+                content.push(c);
+            }
+            _ => content.push(c),
+        }
+    }
+    UString::from(content.as_str())
+}
+
+/// Resolves the escape sequence following the `\`.
+///
+/// Returns `Ok(None)` for the line continuation (`\` at the end of line), which skips the newline
+/// and the leading whitespaces of the next line.
+fn unescape(chars: &mut Peekable<CharIndices>) -> Result<Option<char>, Cow<'static, str>> {
+    let Some((_, c)) = chars.next() else {
+        return Err("Missing escape character after '\\'".into());
+    };
+    let unescaped = match c {
+        'n' => '\n',
+        't' => '\t',
+        'r' => '\r',
+        '0' => '\0',
+        '\\' => '\\',
+        '"' => '"',
+        '\'' => '\'',
+        '\n' | '\r' => {
+            while chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+            return Ok(None);
+        }
+        'x' => {
+            let mut value = 0;
+            for _ in 0..2 {
+                let Some((_, digit)) = chars.next_if(|(_, c)| c.is_ascii_hexdigit()) else {
+                    return Err("Invalid hex escape, expected 2 hex digits like '\\x7F'".into());
+                };
+                value = value * 16 + digit.to_digit(16).unwrap();
+            }
+            if value > 0x7F {
+                return Err("Out of range hex escape, must be in '\\x00'..='\\x7F'".into());
+            }
+            char::from_u32(value).unwrap()
+        }
+        'u' => {
+            if chars.next_if(|(_, c)| *c == '{').is_none() {
+                return Err("Invalid unicode escape, expected '{' like '\\u{1F600}'".into());
+            }
+            let mut value: u32 = 0;
+            let mut digits = 0;
+            while let Some((_, digit)) = chars.next_if(|(_, c)| c.is_ascii_hexdigit()) {
+                value = value
+                    .saturating_mul(16)
+                    .saturating_add(digit.to_digit(16).unwrap());
+                digits += 1;
+            }
+            if chars.next_if(|(_, c)| *c == '}').is_none() {
+                return Err("Unterminated unicode escape, expected '}'".into());
+            }
+            if digits == 0 {
+                return Err("Empty unicode escape, must have at least 1 hex digit".into());
+            }
+            if digits > 6 {
+                return Err("Overlong unicode escape, must have at most 6 hex digits".into());
+            }
+            match char::from_u32(value) {
+                Some(c) => c,
+                None => {
+                    return Err(format!("Invalid unicode character escape '{:X}'", value).into())
+                }
+            }
+        }
+        c => return Err(format!("Unknown character escape: '\\{}'", c).into()),
+    };
+    Ok(Some(unescaped))
+}
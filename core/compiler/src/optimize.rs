@@ -0,0 +1,369 @@
+use foundation::ir;
+use rustc_hash::FxHashMap;
+
+mod fold;
+
+/// Optimizes `module` before it is compiled.
+///
+/// - Folds the operators on literals, e.g. `4 * 3.14` into `12.56`. The operations that raise an
+///   exception (or overflow) at runtime are left as they are, so that the VM reports them.
+/// - Replaces `if` with a literal condition by the taken branch.
+/// - Removes the effects after `return`, `break` and `continue`, which are never executed.
+///
+/// A rewritten value or effect keeps the syntax node of the original one, so the errors still
+/// point to the source.
+pub fn optimize(module: ir::Module) -> ir::Module {
+    let (effects, functions, strage) = module.into_parts();
+    let mut optimizer = Optimizer {
+        strage,
+        functions: FxHashMap::default(),
+    };
+    let effects = optimizer.effects(effects);
+    // The functions only in the removed effects are dropped.
+    let functions = functions
+        .iter()
+        .filter_map(|func| optimizer.functions.get(func).copied())
+        .collect();
+    ir::Module::new(effects, functions, optimizer.strage)
+}
+
+struct Optimizer {
+    strage: ir::Strage,
+    /// The original key to the optimized key of the visited functions.
+    functions: FxHashMap<ir::FunctionKey, ir::FunctionKey>,
+}
+
+impl Optimizer {
+    /// Optimizes the effects of `key`, and returns the key without the unreachable effects.
+    fn effects(&mut self, key: ir::EffectsKey) -> ir::EffectsKey {
+        for i in 0..key.len() {
+            let (_, effect) = self.strage.get(&key).nth(i).unwrap();
+            let effect = effect.clone();
+            let optimized = self.effect(&effect);
+            let is_terminal = matches!(
+                optimized,
+                ir::Effect::Return { .. } | ir::Effect::BreakLoop | ir::Effect::ContinueLoop
+            );
+            if optimized != effect {
+                self.strage.set_effect(&key, i, optimized);
+            }
+            if is_terminal {
+                return key.truncated(i + 1);
+            }
+        }
+        key
+    }
+
+    fn effect(&mut self, effect: &ir::Effect) -> ir::Effect {
+        use ir::Effect::*;
+        match effect.clone() {
+            MakeLocal { name, value } => {
+                self.value(&value);
+                MakeLocal { name, value }
+            }
+            MakeFunc { name, func } => MakeFunc {
+                name,
+                func: self.function(func),
+            },
+            SetLocal { local, value } => {
+                self.value(&value);
+                SetLocal { local, value }
+            }
+            SetIndex {
+                target,
+                index,
+                value,
+            } => {
+                self.value(&target);
+                self.value(&index);
+                self.value(&value);
+                SetIndex {
+                    target,
+                    index,
+                    value,
+                }
+            }
+            SetField {
+                target,
+                field,
+                value,
+            } => {
+                self.value(&target);
+                self.value(&value);
+                SetField {
+                    target,
+                    field,
+                    value,
+                }
+            }
+            SetFieldFunc { table, path, func } => SetFieldFunc {
+                table,
+                path,
+                func: self.function(func),
+            },
+            SetMethod {
+                table,
+                path,
+                name,
+                func,
+            } => SetMethod {
+                table,
+                path,
+                name,
+                func: self.function(func),
+            },
+            Branch {
+                condition,
+                then,
+                else_,
+            } => {
+                self.value(&condition);
+                let then = self.effects(then);
+                let else_ = self.effects(else_);
+                match self.truthiness(&condition) {
+                    Some(true) => Scope { body: then },
+                    Some(false) => Scope { body: else_ },
+                    None => Branch {
+                        condition,
+                        then,
+                        else_,
+                    },
+                }
+            }
+            LoopFor {
+                variable,
+                iterable,
+                effects,
+            } => {
+                self.value(&iterable);
+                LoopFor {
+                    variable,
+                    iterable,
+                    effects: self.effects(effects),
+                }
+            }
+            LoopWhile { condition, effects } => {
+                self.value(&condition);
+                LoopWhile {
+                    condition,
+                    effects: self.effects(effects),
+                }
+            }
+            Scope { body } => Scope {
+                body: self.effects(body),
+            },
+            Call { value, args } => {
+                self.value(&value);
+                self.values(&args);
+                Call { value, args }
+            }
+            MethodCall {
+                table,
+                name,
+                args,
+                optional,
+            } => {
+                self.value(&table);
+                self.values(&args);
+                MethodCall {
+                    table,
+                    name,
+                    args,
+                    optional,
+                }
+            }
+            Return { value } => {
+                self.value(&value);
+                Return { value }
+            }
+            BreakLoop => BreakLoop,
+            ContinueLoop => ContinueLoop,
+            NoEffectValue { value } => {
+                self.value(&value);
+                NoEffectValue { value }
+            }
+        }
+    }
+
+    /// Optimizes the value of `key` in place.
+    fn value(&mut self, key: &ir::ValueKey) {
+        let Some((_, value)) = self.strage.get(key) else {
+            return;
+        };
+        let value = value.clone();
+        let optimized = self.optimize_value(&value);
+        if optimized != value {
+            self.strage.set_value(key, optimized);
+        }
+    }
+
+    fn values(&mut self, key: &ir::ValueSliceKey) {
+        for key in key.keys() {
+            self.value(&key);
+        }
+    }
+
+    fn optimize_value(&mut self, value: &ir::Value) -> ir::Value {
+        use ir::Value::*;
+        match value.clone() {
+            Branch {
+                condition,
+                then,
+                then_tail,
+                else_,
+                else_tail,
+            } => {
+                self.value(&condition);
+                let then = self.effects(then);
+                self.value(&then_tail);
+                let else_ = self.effects(else_);
+                self.value(&else_tail);
+                match self.truthiness(&condition) {
+                    Some(true) => self.block(then, then_tail),
+                    Some(false) => self.block(else_, else_tail),
+                    None => Branch {
+                        condition,
+                        then,
+                        then_tail,
+                        else_,
+                        else_tail,
+                    },
+                }
+            }
+            Match { value, arms } => {
+                self.value(&value);
+                let arms = arms
+                    .iter()
+                    .map(|arm| {
+                        let body = self.effects(arm.body);
+                        self.value(&arm.tail);
+                        ir::MatchArm {
+                            pattern: arm.pattern.clone(),
+                            body,
+                            tail: arm.tail,
+                        }
+                    })
+                    .collect();
+                Match { value, arms }
+            }
+            Prefix { op, value } => {
+                self.value(&value);
+                let (_, operand) = self.strage.get(&value).unwrap();
+                fold::prefix(&op, operand).unwrap_or(Prefix { op, value })
+            }
+            Binary { op, lhs, rhs } => {
+                self.value(&lhs);
+                self.value(&rhs);
+                // `and`, `or` and `??` are decided by the left operand alone.
+                let picks_lhs = match (&op, self.truthiness(&lhs)) {
+                    (ir::BinaryOp::And(_), Some(truthy)) => Some(!truthy),
+                    (ir::BinaryOp::Or(_), Some(truthy)) => Some(truthy),
+                    (ir::BinaryOp::NilCoalesce(_), Some(_)) => {
+                        let (_, lhs) = self.strage.get(&lhs).unwrap();
+                        Some(*lhs != Nil)
+                    }
+                    _ => None,
+                };
+                let (_, l) = self.strage.get(&lhs).unwrap();
+                let (_, r) = self.strage.get(&rhs).unwrap();
+                match picks_lhs {
+                    Some(true) => l.clone(),
+                    Some(false) => r.clone(),
+                    None => fold::binary(&op, l, r).unwrap_or(Binary { op, lhs, rhs }),
+                }
+            }
+            Call { value, args } => {
+                self.value(&value);
+                self.values(&args);
+                Call { value, args }
+            }
+            Index { value, index } => {
+                self.value(&value);
+                self.value(&index);
+                Index { value, index }
+            }
+            Field {
+                value,
+                name,
+                optional,
+            } => {
+                self.value(&value);
+                Field {
+                    value,
+                    name,
+                    optional,
+                }
+            }
+            MethodCall {
+                value,
+                name,
+                args,
+                optional,
+            } => {
+                self.value(&value);
+                self.values(&args);
+                MethodCall {
+                    value,
+                    name,
+                    args,
+                    optional,
+                }
+            }
+            Block { effects, tail } => {
+                let effects = self.effects(effects);
+                self.value(&tail);
+                self.block(effects, tail)
+            }
+            Function(func) => Function(self.function(func)),
+            Array { elements } => {
+                self.values(&elements);
+                Array { elements }
+            }
+            Table { fields } => {
+                for (key, value) in fields.iter() {
+                    if let ir::TableKeyName::Value(key) = key {
+                        self.value(key);
+                    }
+                    self.value(value);
+                }
+                Table { fields }
+            }
+            Interpolation { parts } => {
+                for part in parts.iter() {
+                    if let ir::InterpolationPart::Value(value, _) = part {
+                        self.value(value);
+                    }
+                }
+                Interpolation { parts }
+            }
+            value @ (Local { .. } | Int(_) | Float(_) | String(_) | Bool(_) | Nil) => value,
+        }
+    }
+
+    /// Returns the block of `effects` and `tail`, or the literal `tail` itself if there is no
+    /// effect.
+    fn block(&self, effects: ir::EffectsKey, tail: ir::ValueKey) -> ir::Value {
+        if effects.is_empty() {
+            if let Some((_, value)) = self.strage.get(&tail) {
+                if fold::is_literal(value) {
+                    return value.clone();
+                }
+            }
+        }
+        ir::Value::Block { effects, tail }
+    }
+
+    fn function(&mut self, func: ir::FunctionKey) -> ir::FunctionKey {
+        let body = func.body(&self.strage);
+        let optimized_body = self.effects(body);
+        let optimized = func.truncated(optimized_body.len(), &self.strage);
+        self.functions.insert(func, optimized);
+        optimized
+    }
+
+    /// Returns the truthiness of the value of `key` if it is a literal.
+    fn truthiness(&self, key: &ir::ValueKey) -> Option<bool> {
+        let (_, value) = self.strage.get(key)?;
+        fold::truthiness(value)
+    }
+}
@@ -0,0 +1,135 @@
+use core::cmp::Ordering;
+use foundation::{ir, object::UString};
+
+/// Returns `true` if `value` is a literal of a primitive type.
+pub(super) fn is_literal(value: &ir::Value) -> bool {
+    matches!(
+        value,
+        ir::Value::Int(_)
+            | ir::Value::Float(_)
+            | ir::Value::String(_)
+            | ir::Value::Bool(_)
+            | ir::Value::Nil
+    )
+}
+
+/// Returns the truthiness of the literal `value`, or `None` if `value` is not a literal.
+pub(super) fn truthiness(value: &ir::Value) -> Option<bool> {
+    match value {
+        ir::Value::Nil | ir::Value::Bool(false) => Some(false),
+        value if is_literal(value) => Some(true),
+        _ => None,
+    }
+}
+
+/// Evaluates `op value` at compile time.
+///
+/// Returns `None` if the operand is not a literal, or the operation raises an exception (or
+/// overflows) at runtime, so that it is left to the VM.
+pub(super) fn prefix(op: &ir::PrefixOp, value: &ir::Value) -> Option<ir::Value> {
+    use ir::Value::*;
+    let result = match (op, value) {
+        (ir::PrefixOp::Plus(_), Int(x)) => Int(*x),
+        (ir::PrefixOp::Plus(_), Float(x)) => Float(*x),
+        (ir::PrefixOp::Minus(_), Int(x)) => Int(x.checked_neg()?),
+        (ir::PrefixOp::Minus(_), Float(x)) => Float(-x),
+        (ir::PrefixOp::Not(_), value) => Bool(!truthiness(value)?),
+        (ir::PrefixOp::BitNot(_), Int(x)) => Int(!x),
+        _ => return None,
+    };
+    Some(result)
+}
+
+/// Evaluates `lhs op rhs` at compile time. (See `prefix()`)
+///
+/// `and`, `or` and `??` are not handled here because their result may be the unevaluated `rhs`.
+pub(super) fn binary(op: &ir::BinaryOp, lhs: &ir::Value, rhs: &ir::Value) -> Option<ir::Value> {
+    use ir::Value::*;
+    if !is_literal(lhs) || !is_literal(rhs) {
+        return None;
+    }
+    let result = match op {
+        ir::BinaryOp::Add(_) => arithmetic(lhs, rhs, i64::checked_add, |l, r| l + r)?,
+        ir::BinaryOp::Sub(_) => arithmetic(lhs, rhs, i64::checked_sub, |l, r| l - r)?,
+        ir::BinaryOp::Mul(_) => arithmetic(lhs, rhs, i64::checked_mul, |l, r| l * r)?,
+        ir::BinaryOp::Div(_) => arithmetic(lhs, rhs, i64::checked_div, |l, r| l / r)?,
+        ir::BinaryOp::Mod(_) => arithmetic(lhs, rhs, i64::checked_rem, |l, r| l % r)?,
+        ir::BinaryOp::Shl(_) => match (lhs, rhs) {
+            (Int(l), Int(r)) => Int(l.checked_shl(u32::try_from(*r).ok()?)?),
+            _ => return None,
+        },
+        ir::BinaryOp::Shr(_) => match (lhs, rhs) {
+            (Int(l), Int(r)) => Int(l.checked_shr(u32::try_from(*r).ok()?)?),
+            _ => return None,
+        },
+        ir::BinaryOp::BitAnd(_) => bitwise(lhs, rhs, |l, r| l & r)?,
+        ir::BinaryOp::BitOr(_) => bitwise(lhs, rhs, |l, r| l | r)?,
+        ir::BinaryOp::BitXor(_) => bitwise(lhs, rhs, |l, r| l ^ r)?,
+        ir::BinaryOp::Concat(_) => {
+            let mut string = UString::from(to_string(lhs).as_str());
+            string += to_string(rhs).as_str();
+            String(string)
+        }
+        ir::BinaryOp::Eq(_) => Bool(lhs == rhs),
+        ir::BinaryOp::Ne(_) => Bool(lhs != rhs),
+        ir::BinaryOp::Lt(_) => compare(lhs, rhs, Ordering::is_lt)?,
+        ir::BinaryOp::Le(_) => compare(lhs, rhs, Ordering::is_le)?,
+        ir::BinaryOp::Gt(_) => compare(lhs, rhs, Ordering::is_gt)?,
+        ir::BinaryOp::Ge(_) => compare(lhs, rhs, Ordering::is_ge)?,
+        ir::BinaryOp::And(_)
+        | ir::BinaryOp::Or(_)
+        | ir::BinaryOp::NilCoalesce(_)
+        | ir::BinaryOp::Assign(_)
+        | ir::BinaryOp::Missing => return None,
+    };
+    Some(result)
+}
+
+fn arithmetic(
+    lhs: &ir::Value,
+    rhs: &ir::Value,
+    int: fn(i64, i64) -> Option<i64>,
+    float: fn(f64, f64) -> f64,
+) -> Option<ir::Value> {
+    use ir::Value::*;
+    let result = match (lhs, rhs) {
+        (Int(l), Int(r)) => Int(int(*l, *r)?),
+        (Int(l), Float(r)) => Float(float(*l as f64, *r)),
+        (Float(l), Int(r)) => Float(float(*l, *r as f64)),
+        (Float(l), Float(r)) => Float(float(*l, *r)),
+        _ => return None,
+    };
+    Some(result)
+}
+
+fn bitwise(lhs: &ir::Value, rhs: &ir::Value, int: fn(i64, i64) -> i64) -> Option<ir::Value> {
+    match (lhs, rhs) {
+        (ir::Value::Int(l), ir::Value::Int(r)) => Some(ir::Value::Int(int(*l, *r))),
+        _ => None,
+    }
+}
+
+/// Compares two numbers with `f`. As the VM does, any comparison with `NaN` is `false`.
+fn compare(lhs: &ir::Value, rhs: &ir::Value, f: fn(Ordering) -> bool) -> Option<ir::Value> {
+    use ir::Value::*;
+    let ordering = match (lhs, rhs) {
+        (Int(l), Int(r)) => l.partial_cmp(r),
+        (Int(l), Float(r)) => (*l as f64).partial_cmp(r),
+        (Float(l), Int(r)) => l.partial_cmp(&(*r as f64)),
+        (Float(l), Float(r)) => l.partial_cmp(r),
+        _ => return None,
+    };
+    Some(Bool(ordering.is_some_and(f)))
+}
+
+/// Formats the literal `value` in the same way as `Object`'s `Display`.
+fn to_string(value: &ir::Value) -> String {
+    match value {
+        ir::Value::Int(x) => x.to_string(),
+        ir::Value::Float(x) => x.to_string(),
+        ir::Value::String(x) => x.to_string(),
+        ir::Value::Bool(x) => x.to_string(),
+        ir::Value::Nil => "nil".to_string(),
+        _ => unreachable!(),
+    }
+}
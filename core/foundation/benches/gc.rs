@@ -0,0 +1,80 @@
+//! Compares the modes of the cycle collector.
+//!
+//! Run with `cargo bench -p foundation --bench gc`.
+
+#![feature(test)]
+
+extern crate test;
+
+use foundation::object::{gc, *};
+use test::{black_box, Bencher};
+
+/// Builds a ring of `len` tables.
+fn make_ring(len: usize) -> Table {
+    let first = Table::new();
+    let mut last = first.clone();
+    for i in 1..len {
+        let mut node = Table::new();
+        node.insert("id".into(), Object::Int(i as i64));
+        last.insert("next".into(), Object::Table(node.clone()));
+        last = node;
+    }
+    last.insert("next".into(), Object::Table(first.clone()));
+    first
+}
+
+/// Walks around a ring of 1000 tables, dropping a reference to a table in the ring every step.
+fn walk_ring(b: &mut Bencher, mode: gc::Mode) {
+    gc::set_mode(mode);
+    let ring = make_ring(1000);
+    b.iter(|| {
+        let mut node = ring.clone();
+        for _ in 0..1000 {
+            let next = match node.get("next") {
+                Some(Object::Table(next)) => next.clone(),
+                _ => unreachable!(),
+            };
+            node = black_box(next);
+        }
+    });
+    drop(ring);
+    gc::set_mode(gc::Mode::Immediate);
+}
+
+/// Creates and drops 1000 garbage cycles of two tables.
+fn garbage_cycles(b: &mut Bencher, mode: gc::Mode) {
+    gc::set_mode(mode);
+    b.iter(|| {
+        for _ in 0..1000 {
+            let mut table1 = Table::new();
+            let mut table2 = Table::new();
+            table1.insert("table2".into(), Object::Table(table2.clone()));
+            table2.insert("table1".into(), Object::Table(table1.clone()));
+        }
+    });
+    gc::set_mode(gc::Mode::Immediate);
+}
+
+const BATCHED: gc::Mode = gc::Mode::Batched {
+    threshold: gc::Mode::DEFAULT_THRESHOLD,
+};
+
+#[bench]
+fn walk_ring_immediate(b: &mut Bencher) {
+    walk_ring(b, gc::Mode::Immediate);
+}
+
+#[bench]
+fn walk_ring_batched(b: &mut Bencher) {
+    walk_ring(b, BATCHED);
+}
+
+#[bench]
+fn garbage_cycles_immediate(b: &mut Bencher) {
+    garbage_cycles(b, gc::Mode::Immediate);
+}
+
+#[bench]
+fn garbage_cycles_batched(b: &mut Bencher) {
+    garbage_cycles(b, BATCHED);
+}
@@ -112,6 +112,30 @@ impl<T> Slice<T> {
     pub fn as_u64(&self) -> u64 {
         (self.start.get() as u64) << 32 | self.end.get() as u64
     }
+
+    /// Returns the slice of the first `len` elements.
+    pub fn truncated(&self, len: usize) -> Self {
+        assert!(
+            len <= self.len(),
+            "Slice::truncated(len) where len > self.len()"
+        );
+        // SAFETY: The new slice is a part of `self`.
+        unsafe { Self::new(self.start.get(), self.start.get() + len as u32) }
+    }
+
+    /// Divides the slice into the first `mid` elements and the rest.
+    pub fn split_at(&self, mid: usize) -> (Self, Self) {
+        let head = self.truncated(mid);
+        // SAFETY: The new slice is a part of `self`.
+        let tail = unsafe { Self::new(head.end.get(), self.end.get()) };
+        (head, tail)
+    }
+
+    /// Returns the indices of the elements.
+    pub fn indices(&self) -> impl ExactSizeIterator<Item = Index<T>> {
+        // SAFETY: The indices are in `self.start..self.end`, which are not zero.
+        (self.start.get()..self.end.get()).map(|raw| unsafe { Index::new(raw) })
+    }
 }
 
 fn _static_assert_slice_size() {
@@ -1,9 +1,37 @@
 use super::{sorted_linear_map, sorted_linear_map::SortedLinearMap};
 use ahash::RandomState;
-use core::{borrow::Borrow, fmt::Debug, hash::Hash};
+use core::{
+    borrow::Borrow,
+    fmt::Debug,
+    hash::{BuildHasher, Hash},
+};
+use hashbrown::hash_map::RawEntryMut;
+use std::sync::OnceLock;
 
 type HashMap<K, T> = hashbrown::HashMap<K, T, RandomState>;
 
+/// Returns the hash state shared by all the maps, so that a key has the same hash in any map.
+fn hash_state() -> RandomState {
+    shared_hash_state().clone()
+}
+
+fn shared_hash_state() -> &'static RandomState {
+    static STATE: OnceLock<RandomState> = OnceLock::new();
+    // `RandomState::new()` allocates the source of the randomness, which is never freed, on the
+    // first call. It would happen at the first interned string, wherever it is.
+    STATE.get_or_init(|| {
+        let source = std::hash::RandomState::new();
+        let seed = |index: u64| source.hash_one(index);
+        RandomState::with_seeds(seed(0), seed(1), seed(2), seed(3))
+    })
+}
+
+/// Returns the hash of `key` in `LazyHashMap`, which can be computed in advance for
+/// `LazyHashMap::get_hashed()` and `LazyHashMap::insert_hashed()`.
+pub fn hash_one<T: Hash + ?Sized>(key: &T) -> u64 {
+    BuildHasher::hash_one(shared_hash_state(), key)
+}
+
 const LINEAR_MAP_SIZE_LIMIT: usize = 16;
 
 #[derive(Clone)]
@@ -26,7 +54,7 @@ impl<K: Hash + Ord, V> LazyHashMap<K, V> {
         } else {
             Self(Variant::Hashed(HashMap::with_capacity_and_hasher(
                 capacity,
-                RandomState::new(),
+                hash_state(),
             )))
         }
     }
@@ -62,7 +90,7 @@ impl<K: Hash + Ord, V> LazyHashMap<K, V> {
                 if map.len() < LINEAR_MAP_SIZE_LIMIT {
                     map.insert(key, value)
                 } else {
-                    let mut hashmap = HashMap::default();
+                    let mut hashmap = HashMap::with_hasher(hash_state());
                     for (k, v) in map.drain() {
                         hashmap.insert(k, v);
                     }
@@ -75,6 +103,33 @@ impl<K: Hash + Ord, V> LazyHashMap<K, V> {
         }
     }
 
+    /// Returns the value for `key` like `get()`, but the hash of `key` is given by `hash`, which
+    /// must be `hash_one(key)`. A small map is scanned by `==`. (See `SortedLinearMap::find()`)
+    pub fn get_hashed(&self, hash: u64, key: &K) -> Option<&V> {
+        match &self.0 {
+            Variant::Linear(map) => map.find(key),
+            Variant::Hashed(map) => map
+                .raw_entry()
+                .from_key_hashed_nocheck(hash, key)
+                .map(|(_, v)| v),
+        }
+    }
+
+    /// Inserts `value` for `key` like `insert()`, but the hash of `key` is given by `hash`, which
+    /// must be `hash_one(&key)`.
+    pub fn insert_hashed(&mut self, hash: u64, key: K, value: V) -> Option<V> {
+        let Variant::Hashed(map) = &mut self.0 else {
+            return self.insert(key, value);
+        };
+        match map.raw_entry_mut().from_key_hashed_nocheck(hash, &key) {
+            RawEntryMut::Occupied(mut entry) => Some(entry.insert(value)),
+            RawEntryMut::Vacant(entry) => {
+                entry.insert_hashed_nocheck(hash, key, value);
+                None
+            }
+        }
+    }
+
     pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
     where
         K: Borrow<Q>,
@@ -138,7 +193,7 @@ impl<K: Hash + Ord, V, const N: usize> From<[(K, V); N]> for LazyHashMap<K, V> {
             let map = SortedLinearMap::from(value);
             LazyHashMap(Variant::Linear(map))
         } else {
-            let mut map = HashMap::with_capacity_and_hasher(N, RandomState::new());
+            let mut map = HashMap::with_capacity_and_hasher(N, hash_state());
             for (k, v) in value {
                 map.insert(k, v);
             }
@@ -153,7 +208,7 @@ impl<K: Hash + Ord, V> From<Vec<(K, V)>> for LazyHashMap<K, V> {
             let map = SortedLinearMap::from(value);
             LazyHashMap(Variant::Linear(map))
         } else {
-            let mut map = HashMap::with_capacity_and_hasher(value.len(), RandomState::new());
+            let mut map = HashMap::with_capacity_and_hasher(value.len(), hash_state());
             for (k, v) in value {
                 map.insert(k, v);
             }
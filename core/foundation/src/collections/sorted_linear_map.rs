@@ -41,6 +41,13 @@ impl<K: Ord, V> SortedLinearMap<K, V> {
         }
     }
 
+    /// Returns the value for `key` like `get()`, but scans the entries by `==` instead of
+    /// comparing the orders, which is faster for the keys compared by their identity, e.g. the
+    /// interned strings.
+    pub fn find(&self, key: &K) -> Option<&V> {
+        self.data.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
     pub fn insert(&mut self, key: K, value: V) -> Option<V> {
         let index = self.data.binary_search_by_key(&&key, |(k, _)| k);
         match index {
@@ -1,12 +1,15 @@
 mod icode;
 pub use icode::ICode;
 
-mod local_id;
-pub use local_id::LocalId;
+mod reg;
+pub use reg::Reg;
 
 mod executable;
 pub use executable::Executable;
 
+mod inline_cache;
+use inline_cache::InlineCache;
+
 mod source_info;
 pub use source_info::SourceInfo;
 
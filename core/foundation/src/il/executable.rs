@@ -1,24 +1,31 @@
 use super::*;
+use crate::object::{FieldCache, MethodCache};
 use core::{
     alloc::Layout,
-    cell::Cell,
+    cell::{Cell, RefCell, UnsafeCell},
     fmt, hint,
     ptr::{self, drop_in_place, NonNull},
-    slice,
 };
 use std::alloc;
 
 pub struct Executable {
-    // | Header | Data (array of `ICode`) |
+    // | Header | Data (array of `Slot`) |
     //          ^ ptr
-    ptr: NonNull<ICode>,
+    ptr: NonNull<Slot>,
 }
 
+/// An instruction, which is rewritten in place by `write()` while the executable is shared.
+type Slot = UnsafeCell<ICode>;
+
 struct Header {
     // Fields should not require heap allocation
     // If you need to store a reference, fix `Clone` and `Drop` implementation.
     count: Cell<usize>,
     len: usize,
+    /// The inline caches of the instructions, which is dropped in `Drop`.
+    caches: Box<[InlineCache]>,
+    /// The start indices of the functions whose code is specialized. (See `mark_specialized()`)
+    specialized: RefCell<Vec<usize>>,
 }
 
 impl Executable {
@@ -39,20 +46,27 @@ impl Executable {
             alloc::handle_alloc_error(layout);
         }
 
-        let ptr = unsafe {
+        let mut caches = Vec::with_capacity(len);
+        let ptr: NonNull<Slot> = unsafe {
             // SAFETY: `allocation` is non-null.
-            *(allocation as *mut Header) = Header {
-                count: Cell::new(1),
-                len,
-            };
             NonNull::new_unchecked(allocation.add(Self::header_offset())).cast()
         };
         for (i, icode) in code_iter.enumerate() {
+            caches.push(InlineCache::for_icode(&icode));
             unsafe {
                 // SAFETY: Since `i` is less than `len`, it is in bounds.
-                ptr.add(i).write(icode);
+                ptr.add(i).write(UnsafeCell::new(icode));
             }
         }
+        unsafe {
+            // SAFETY: `allocation` is non-null and uninitialized, so it is written without drop.
+            (allocation as *mut Header).write(Header {
+                count: Cell::new(1),
+                len,
+                caches: caches.into_boxed_slice(),
+                specialized: RefCell::new(Vec::new()),
+            });
+        }
         Executable { ptr }
     }
 
@@ -68,19 +82,53 @@ impl Executable {
 
     /// # Safety
     ///
-    /// `index` must be less than `self.len()`
+    /// - `index` must be less than `self.len()`
+    /// - The returned reference must not be used after the instruction is rewritten by `write()`.
     pub unsafe fn fetch(&self, index: usize) -> &ICode {
         debug_assert!(index < self.len());
-        unsafe { &*self.ptr.as_ptr().add(index) }
+        unsafe { &*(*self.ptr.as_ptr().add(index)).get() }
     }
 
+    /// Replaces the instruction at `index` with `icode`, and drops the old one.
+    ///
     /// # Safety
     ///
-    /// `index` must be less than `self.len()`
+    /// `index` must be less than `self.len()`, and no reference to the instruction at `index`
+    /// (returned by `fetch()`) may be used after this call.
     pub unsafe fn write(&self, index: usize, icode: ICode) {
         debug_assert!(index < self.len());
         unsafe {
-            self.ptr.as_ptr().add(index).write(icode);
+            drop((*self.ptr.as_ptr().add(index)).get().replace(icode));
+        }
+    }
+
+    /// Marks the function starting at `start` as specialized, and returns `true` if it was not
+    /// marked yet. The code shared by the clones is specialized only once.
+    pub fn mark_specialized(&self, start: usize) -> bool {
+        let mut specialized = self.header().specialized.borrow_mut();
+        if specialized.contains(&start) {
+            false
+        } else {
+            specialized.push(start);
+            true
+        }
+    }
+
+    /// Returns the inline cache of the field lookup by the instruction at `index`, or `None` if the
+    /// instruction does not look up a field.
+    pub fn field_cache(&self, index: usize) -> Option<&FieldCache> {
+        match self.header().caches.get(index)? {
+            InlineCache::Field(cache) => Some(cache),
+            _ => None,
+        }
+    }
+
+    /// Returns the inline cache of the method lookup by the instruction at `index`, or `None` if
+    /// the instruction does not look up a method.
+    pub fn method_cache(&self, index: usize) -> Option<&MethodCache> {
+        match self.header().caches.get(index)? {
+            InlineCache::Method(cache) => Some(cache),
+            _ => None,
         }
     }
 
@@ -115,10 +163,10 @@ impl Executable {
     /// - a power of two.
     const fn align() -> usize {
         const {
-            assert!(align_of::<ICode>() != 0);
-            assert!(align_of::<ICode>() % 2 == 0);
+            assert!(align_of::<Slot>() != 0);
+            assert!(align_of::<Slot>() % 2 == 0);
         }
-        max(align_of::<Header>(), align_of::<ICode>())
+        max(align_of::<Header>(), align_of::<Slot>())
     }
 
     /// The size of the header and data.
@@ -127,7 +175,7 @@ impl Executable {
     /// - non-zero.
     /// - When rounded up to the nearest multiple of `Self::align()`, does not overflow `isize::MAX`.
     const fn size(len: usize) -> usize {
-        let Some(data_size) = size_of::<ICode>().checked_mul(len) else {
+        let Some(data_size) = size_of::<Slot>().checked_mul(len) else {
             size_overflow_panic()
         };
         let Some(size) = Self::header_offset().checked_add(data_size) else {
@@ -170,8 +218,9 @@ impl Drop for Executable {
                 let data_slice = ptr::slice_from_raw_parts_mut(self.ptr.as_ptr(), len);
                 drop_in_place(data_slice);
 
-                // No `drop_in_place` for the header is needed.
-                // Because none of the fields in the header are references.
+                // destroy the header, which owns the inline caches
+                drop_in_place(self.allocation() as *mut Header);
+
                 // free
                 let allocation = self.allocation();
                 let layout = Self::layout(len);
@@ -186,25 +235,26 @@ impl Drop for Executable {
 impl fmt::Debug for Executable {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let data_len = self.len();
-        let data_slice = unsafe {
-            // SAFETY: `self.ptr` points to the beginning of the data, and `data_len` is the length of it.
-            slice::from_raw_parts(self.ptr.as_ptr(), data_len)
-        };
+        let data = (0..data_len)
+            .map(|i| unsafe {
+                // SAFETY: `i` is less than `data_len`, and the references are used only here.
+                self.fetch(i)
+            })
+            .collect::<Vec<_>>();
         f.debug_struct("Executable")
             .field("len", &data_len)
-            .field("data", &data_slice)
+            .field("data", &data)
             .finish()
     }
 }
 
 impl fmt::Display for Executable {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let data_len = self.len();
-        let data_slice = unsafe {
-            // SAFETY: `self.ptr` points to the beginning of the data, and `data_len` is the length of it.
-            slice::from_raw_parts(self.ptr.as_ptr(), data_len)
-        };
-        for icode in data_slice {
+        for i in 0..self.len() {
+            let icode = unsafe {
+                // SAFETY: `i` is less than `self.len()`, and the reference is used only here.
+                self.fetch(i)
+            };
             writeln!(f, "{}", icode)?;
         }
         Ok(())
@@ -251,22 +301,23 @@ mod tests {
 
     #[test]
     fn smoke() {
+        let r0 = Reg::new(0);
         let code = vec![
-            ICode::LoadIntObject(0),
-            ICode::LoadIntObject(1),
-            ICode::LoadIntObject(2),
-            ICode::LoadIntObject(3),
+            ICode::LoadIntObject(r0, 0),
+            ICode::LoadIntObject(r0, 1),
+            ICode::LoadIntObject(r0, 2),
+            ICode::LoadIntObject(r0, 3),
         ];
         unsafe {
             let exe = Executable::new(code);
-            assert_eq!(exe.fetch(1), &ICode::LoadIntObject(1));
+            assert_eq!(exe.fetch(1), &ICode::LoadIntObject(r0, 1));
 
             let exe2 = Executable::clone(&exe);
-            assert_eq!(exe.fetch(2), &ICode::LoadIntObject(2));
+            assert_eq!(exe.fetch(2), &ICode::LoadIntObject(r0, 2));
             assert_eq!(exe.header().count.get(), 2);
 
-            exe.write(2, ICode::Unload);
-            assert_eq!(exe2.fetch(2), &ICode::Unload);
+            exe.write(2, ICode::Nop);
+            assert_eq!(exe2.fetch(2), &ICode::Nop);
 
             drop(exe);
             assert_eq!(exe2.header().count.get(), 1);
@@ -1,4 +1,4 @@
-use super::LocalId;
+use super::Reg;
 use crate::object::*;
 
 use core::fmt;
@@ -10,64 +10,48 @@ fn _size_check() {
     }
 }
 
+/// The register-based intermediate code.
+///
+/// Each call frame has its own registers. (See [`Reg`])
+/// The source operands marked as `take` are moved out of the registers, and the others are copied.
+/// Unless otherwise noted, the result is written to the first operand.
 #[derive(Clone, Debug, PartialEq)]
 pub enum ICode {
-    /// Pushes a constant integer value as an object to the stack.
-    LoadIntObject(i64),
+    /// Stores a constant integer value as an object to `.0`.
+    LoadIntObject(Reg, i64),
 
-    /// Pushes a constant float value as an object to the stack.
-    LoadFloatObject(f64),
+    /// Stores a constant float value as an object to `.0`.
+    LoadFloatObject(Reg, f64),
 
-    /// Pushes a constant string value as an object to the stack.
-    LoadStringObject(UString),
+    /// Stores a constant string value as an object to `.0`.
+    LoadStringObject(Reg, UString),
 
-    /// Pushes a constant boolean value as an object to the stack.
-    LoadBoolObject(bool),
+    /// Stores a constant boolean value as an object to `.0`.
+    LoadBoolObject(Reg, bool),
 
-    /// Pushes a constant nil value as an object to the stack.
-    LoadNilObject,
+    /// Stores a constant nil value as an object to `.0`.
+    LoadNilObject(Reg),
 
-    /// Pushes the stored local object specified by `.0` onto the local variable table.
+    /// Stores the value of `.1` to `.0`.
     ///
     /// # Panic
     ///
-    /// Not found the specified local object.
-    LoadLocal(LocalId),
+    /// `.1` is out of the current frame.
+    Move(Reg, Reg),
 
-    /// Pushes the top value from the stack.
+    /// Makes an array from the specified number (`.1`) of values in the registers starting from
+    /// `.0`, and stores the array to `.0`.
+    /// The values are always taken.
     ///
     /// # Panic
     ///
-    /// Stack is empty.
-    Unload,
+    /// The registers are out of the current frame.
+    MakeArray(Reg, usize),
 
-    /// Writes the top value of the stack to the location specified by `.0` in the local variable
-    /// table.
-    /// The top value of the stack is popped.
+    /// Makes a table from the specified number (`.1`) of key-value pairs in the registers starting
+    /// from `.0`, and stores the table to `.0`.
     ///
-    /// # Panic
-    ///
-    /// Not found the specified local object.
-    StoreLocal(LocalId),
-
-    /// Writes the top value of the stack to the last location in the local variable table.
-    /// The top value of the stack is popped.
-    StoreNewLocal,
-
-    /// Pops the specified number (`.0`) of values from the stack, makes them into an array, and
-    /// pushes the array to the stack.
-    ///
-    /// # Panic
-    ///
-    /// Stack size is less than the specified number.
-    MakeArray(usize),
-
-    /// Pops the twice specified number (`.0`) of values from the stack, makes them into a table,
-    /// and pushes the table to the stack.
-    ///
-    /// The specified number is the number of key-value pairs in the table.
-    /// Table keys and values are alternately popped from the stack. The first one popped is used
-    /// as a value, and the second one popped is used as a key.
+    /// The keys and values are placed alternately, the key first. They are always taken.
     /// The type of the key must be a string.
     ///
     /// # Exeption
@@ -76,275 +60,279 @@ pub enum ICode {
     ///
     /// # Panic
     ///
-    /// - Stack size is less than the twice specified number.
-    MakeTable(usize),
+    /// The registers are out of the current frame.
+    MakeTable(Reg, usize),
 
-    /// Removes the last n (specified number, `.0`) added values from local variable table.
-    ///
-    /// # Panic
+    /// Removes the registers from `.0` to the end of the current frame.
+    DropLocal(Reg),
+
+    /// Closes the upvalues of the locals from `.0` to the end of the current frame, i.e. moves the
+    /// values of the locals into the upvalues. (See `Upvalue`)
     ///
-    /// Specified number is greater than the local variable table size.
-    DropLocal(usize),
+    /// This is placed before the locals captured by functions go out of scope.
+    CloseUpvalues(Reg),
 
     /// Adds the specified number (`.0`) to program counter.
     Jump(isize),
 
-    /// Pops the top value from the stack, and if it is truthy, adds the specified number (`.0`) to
-    /// program counter.
-    ///
-    /// # Panic
-    ///
-    /// Stack is empty.
-    JumpIfTrue(isize),
+    /// If the value of `.0` is truthy, adds the specified number (`.1`) to program counter.
+    JumpIfTrue(Reg, isize),
 
-    /// Pops the top value from the stack, and if it is falsy, adds the specified number (`.0`) to
-    /// program counter.
-    ///
-    /// # Panic
-    ///
-    /// Stack is empty.
-    JumpIfFalse(isize),
+    /// If the value of `.0` is falsy, adds the specified number (`.1`) to program counter.
+    JumpIfFalse(Reg, isize),
+
+    /// If the value of `.0` is nil, adds the specified number (`.1`) to program counter.
+    JumpIfNil(Reg, isize),
+
+    /// If the value of `.0` is not nil, adds the specified number (`.1`) to program counter.
+    JumpIfNotNil(Reg, isize),
 
-    /// Pops the specified number (`.0`) of values as arguments from the stack.
-    /// These argument are stored in reverse order, with the first argument being at the top of the
-    /// stack and the last argument being at the bottom.
+    /// If `.1 < .2` is false, adds the specified number (`.3`) to program counter, otherwise skips
+    /// the next instruction.
     ///
-    /// After collecting the arguments, pops next value from the stack, which is expected to be a
-    /// callable object.
+    /// This is the fused form of `Less(.0, .1, .2)` and the following `JumpIfFalse(.0*, _)`, which
+    /// must be kept. If the comparison is done by a method, this works as `Less`, and the following
+    /// `JumpIfFalse` jumps instead.
+    ///
+    /// # Exeption
     ///
-    /// Calls the callable object with the collected arguments.
+    /// Same as `Less`.
+    JumpIfNotLess(Reg, Reg, Reg, isize),
+
+    /// The fused form of `LessEq` and `JumpIfFalse`. (See `JumpIfNotLess`)
+    JumpIfNotLessEq(Reg, Reg, Reg, isize),
+
+    /// The fused form of `Greater` and `JumpIfFalse`. (See `JumpIfNotLess`)
+    JumpIfNotGreater(Reg, Reg, Reg, isize),
+
+    /// The fused form of `GreaterEq` and `JumpIfFalse`. (See `JumpIfNotLess`)
+    JumpIfNotGreaterEq(Reg, Reg, Reg, isize),
+
+    /// The fused form of `Eq` and `JumpIfFalse`. (See `JumpIfNotLess`)
+    JumpIfNotEq(Reg, Reg, Reg, isize),
+
+    /// Calls the callable object in `.0` with the specified number (`.1`) of arguments in the
+    /// following registers, and stores the result to `.0`.
+    /// The callee and the arguments are always taken.
     ///
     /// # Exeption
     ///
     /// - The callee is not type of Function or RustFunction or Table.
-    /// - No `__call` method defined for the popped table type value.
+    /// - No `__call` method defined for the table type callee.
     /// - The number of calee arguments is not equal to the specified argument count.
+    Call(Reg, u8),
+
+    /// Calls the callable object in `.0` as `Call` does, in the position of `return f(x)`.
+    /// If the callee is a function of the running executable, the current frame is reused for it,
+    /// and it leaves to the caller of the current frame. Otherwise, it works as `Call`, and the
+    /// following `Leave` returns the result.
     ///
-    /// # Panic
+    /// # Exeption
     ///
-    /// Stack size is less than (`.0` + 1).
-    Call(u8),
+    /// Same as `Call`.
+    TailCall(Reg, u8),
 
-    /// Pops the specified number (`.0`) of values as arguments from the stack.
-    /// These argument are stored in reverse order, with the first argument being at the top of the
-    /// stack and the last argument being at the bottom.
-    ///
-    /// After collecting the arguments, pops next value from the stack, which is expected to be a
-    /// table object which has the specified method name.
+    /// Calls the specified method (`.2`) of the receiver in `.0` with the specified number (`.1`)
+    /// of arguments in the following registers, and stores the result to `.0`.
+    /// The receiver and the arguments are always taken.
     ///
     /// # Exeption
     ///
-    /// - The receiver is not type of Table.
-    /// - The specified method name is not defined in the receiver table object.
+    /// - The specified method name is not defined in the receiver object.
     /// - The number of method arguments is not equal to the specified argument count.
-    ///
-    /// # Panic
-    ///
-    /// Stack size is less than (`.0` + 1).
-    CallMethod(u8, UString),
+    CallMethod(Reg, u8, MethodName),
 
-    /// TODO
+    /// Sets the value of `.2` to the container `.0` with the key `.1`.
     ///
     /// # Exeption
     ///
     /// - The container is not type of Table or Array.
     /// - The key is not type of Int if the container is Array.
     /// - The key is not type of String if the container is Table.
-    SetItem,
+    SetItem(Reg, Reg, Reg),
 
-    /// TODO
+    /// Gets the value from the container `.1` with the key `.2`.
     ///
     /// # Exeption
     ///
     /// - The container is not type of Table or Array.
     /// - The key is not type of Int if the container is Array.
     /// - The key is not type of String if the container is Table.
-    GetItem,
+    GetItem(Reg, Reg, Reg),
+
+    /// Stores `true` if the value of `.1` is a table, otherwise `false`.
+    TestTable(Reg, Reg),
+
+    /// Stores `true` if the value of `.1` is an array of the specified length (`.2`), otherwise
+    /// `false`.
+    TestArray(Reg, Reg, usize),
 
-    /// TODO
+    /// Sets the function in `.1` as the method named `.2` of the table in `.0`.
     ///
     /// # Exeption
     ///
     /// - The container is not type of Table.
-    SetMethod(UString),
+    SetMethod(Reg, Reg, MethodName),
 
-    /// Pops the top two values from the stack, and pushes the `+` operation result to the stack.
+    /// Stores the result of `.1 + .2`.
     ///
     /// # Exeption
     ///
-    /// - Popped values are not type of Int or Float or Table.
-    /// - No `__add` method defined for the popped table type value.
-    ///
-    /// # Panic
-    ///
-    /// Stack size is less than 2.
-    Add,
+    /// - The operands are not type of Int or Float or Table.
+    /// - No `__add` method defined for the table type operand.
+    Add(Reg, Reg, Reg),
 
-    /// Pops the top two values from the stack, and pushes the `-` operation result to the stack.
+    /// Stores the result of `.1 - .2`.
     ///
     /// # Exeption
     ///
-    /// - Popped values are not type of Int or Float or Table.
-    /// - No `__sub` method defined for the popped table type value.
+    /// - The operands are not type of Int or Float or Table.
+    /// - No `__sub` method defined for the table type operand.
+    Sub(Reg, Reg, Reg),
+
+    /// Stores the result of `.1 + .2`, where `.2` is a constant integer.
     ///
-    /// # Panic
+    /// # Exeption
     ///
-    /// Stack size is less than 2.
-    Sub,
+    /// Same as `Add`.
+    AddInt(Reg, Reg, i64),
 
-    /// Pops the top two values from the stack, and pushes the `*` operation result to the stack.
+    /// Stores the result of `.1 - .2`, where `.2` is a constant integer.
     ///
     /// # Exeption
     ///
-    /// - Popped values are not type of Int or Float or Table.
-    /// - No `__mul` method defined for the popped table type value.
+    /// Same as `Sub`.
+    SubInt(Reg, Reg, i64),
+
+    /// Stores the result of `.1 * .2`.
     ///
-    /// # Panic
+    /// # Exeption
     ///
-    /// Stack size is less than 2.
-    Mul,
+    /// - The operands are not type of Int or Float or Table.
+    /// - No `__mul` method defined for the table type operand.
+    Mul(Reg, Reg, Reg),
 
-    /// Pops the top two values from the stack, and pushes the `/` operation result to the stack.
+    /// Stores the result of `.1 / .2`.
     ///
     /// # Exeption
     ///
-    /// - Popped values are not type of Int or Float or Table.
-    /// - No `__div` method defined for the popped table type value.
+    /// - The operands are not type of Int or Float or Table.
+    /// - No `__div` method defined for the table type operand.
     /// - Division by zero.
-    ///
-    /// # Panic
-    ///
-    /// Stack size is less than 2.
-    Div,
+    Div(Reg, Reg, Reg),
 
-    /// Pops the top two values from the stack, and pushes the `%` operation result to the stack.
+    /// Stores the result of `.1 % .2`.
     ///
     /// # Exeption
     ///
-    /// - Popped values are not type of Int or Float or Table.
-    /// - No `__mod` method defined for the popped table type value.
+    /// - The operands are not type of Int or Float or Table.
+    /// - No `__mod` method defined for the table type operand.
     /// - Division by zero.
-    ///
-    /// # Panic
-    ///
-    /// Stack size is less than 2.
-    Mod,
+    Mod(Reg, Reg, Reg),
 
-    /// Pops the one value from the stack, and pushes the unary `-` operation result to the stack.
+    /// Stores the result of `-.1`.
     ///
     /// # Exeption
     ///
-    /// - Popped value is not type of Int or Float or Table.
-    /// - No `__unm` method defined for the popped table type value.
-    ///
-    /// # Panic
-    ///
-    /// Stack is empty.
-    Unm,
+    /// - The operand is not type of Int or Float or Table.
+    /// - No `__unm` method defined for the table type operand.
+    Unm(Reg, Reg),
 
-    /// Pops the one value from the stack, and pushes the unary `+` operation result to the stack.
-    ///
+    /// Stores the result of `+.1`.
     ///
     /// # Exeption
     ///
-    /// - Popped value is not type of Int or Float or Table.
-    /// - No `__unp` method defined for the popped table type value.
-    ///
-    /// # Panic
-    ///
-    /// Stack is empty.
-    Unp,
+    /// - The operand is not type of Int or Float or Table.
+    /// - No `__unp` method defined for the table type operand.
+    Unp(Reg, Reg),
 
-    /// Pops the one value from the stack, and pushes the `not` operation result to the stack.
-    ///
-    /// # Panic
-    ///
-    /// Stack is empty.
-    Not,
+    /// Stores the result of `not .1`.
+    Not(Reg, Reg),
 
-    /// Pops the top two values from the stack, and pushes the result of the equivalence comparison
-    /// to the stack as a boolean value.
-    ///
-    /// # Panic
-    ///
-    /// Stack size is less than 2.
-    Eq,
+    /// Stores the result of the equivalence comparison of `.1` and `.2` as a boolean value.
+    Eq(Reg, Reg, Reg),
 
-    /// Pops the top two values from the stack, and pushes the result of the non-equivalence
-    /// comparison to the stack as a boolean value.
-    ///
-    /// # Panic
-    ///
-    /// Stack size is less than 2.
-    NotEq,
+    /// Stores the result of the non-equivalence comparison of `.1` and `.2` as a boolean value.
+    NotEq(Reg, Reg, Reg),
 
     /// TODO: <
-    Less,
+    Less(Reg, Reg, Reg),
 
     /// TODO: <=
-    LessEq,
+    LessEq(Reg, Reg, Reg),
 
     /// TODO: >
-    Greater,
+    Greater(Reg, Reg, Reg),
 
     /// TODO: >=
-    GreaterEq,
+    GreaterEq(Reg, Reg, Reg),
 
     /// TODO: ..
-    Concat,
+    Concat(Reg, Reg, Reg),
+
+    /// Formats the value of `.1` with the specified format spec (`.2`), and stores the formatted
+    /// string.
+    ///
+    /// # Exeption
+    ///
+    /// The type of the format spec cannot be applied to the value.
+    Format(Reg, Reg, Box<FormatSpec>),
 
     /// TODO: &
-    BitAnd,
+    BitAnd(Reg, Reg, Reg),
 
     /// TODO: |
-    BitOr,
+    BitOr(Reg, Reg, Reg),
 
     /// TODO: ^
-    BitXor,
+    BitXor(Reg, Reg, Reg),
 
     /// TODO: ~
-    BitNot,
+    BitNot(Reg, Reg),
 
     /// TODO: <<
-    ShiftL,
+    ShiftL(Reg, Reg, Reg),
 
     /// TODO: >>
-    ShiftR,
+    ShiftR(Reg, Reg, Reg),
 
     /// TODO: `__get_iter` method
-    GetIter,
+    GetIter(Reg, Reg),
 
     /// TODO: `__move_next` method
-    IterMoveNext,
+    IterMoveNext(Reg, Reg),
 
     /// TODO: `__current` method
-    IterCurrent,
+    IterCurrent(Reg, Reg),
 
-    /// Enters the "Create Function Object" section.
+    /// Enters the "Create Function Object" section. The function object is stored to `.0`.
     ///
     /// In this section, only `FuncSetProperty`, `FuncAddCapture`, and `EndFuncSection` are
     /// allowed.
     /// This section is exited by `EndFuncSection`.
-    BeginFuncSection,
+    BeginFuncSection(Reg),
 
     /// Sets the property of the function.
     ///
     /// - `.0` is the parameter count of the function.
     /// - `.1` is the start program counter of the function.
+    /// - `.2` is the end program counter of the function, i.e. the start of the next one.
+    ///
+    /// The program counters are `u32` to keep `ICode` small.
     ///
     /// # Panic
     ///
     /// - Used outside of the "Create Function Object" section.
     /// - Used twice or more.
-    FuncSetProperty(u8, usize),
+    FuncSetProperty(u8, u32, u32),
 
-    /// Adds the specified local object to the function's capture list.
+    /// Adds the specified local (`.0`) to the function's capture list.
     ///
     /// # Panic
     ///
     /// - Used outside of the "Create Function Object" section.
-    /// - The specified local object is not found.
-    FuncAddCapture(LocalId),
+    /// - The specified local is out of the current frame.
+    FuncAddCapture(Reg),
 
     /// Exits the "Create Function Object" section.
     ///
@@ -354,72 +342,132 @@ pub enum ICode {
     /// - `FuncSetProperty` is not used.
     EndFuncSection,
 
+    /// Stores the result of `.1 + .2`, where the operands are expected to be ints.
+    ///
+    /// This and the following `Int*` codes are the specialized forms, which are written by the VM
+    /// over the generic codes of a hot function. If an operand is not an int, the VM writes the
+    /// generic code back (deoptimization) and runs it instead.
+    IntAdd(Reg, Reg, Reg),
+
+    /// The specialized form of `Sub`. (See `IntAdd`)
+    IntSub(Reg, Reg, Reg),
+
+    /// The specialized form of `Mul`. (See `IntAdd`)
+    IntMul(Reg, Reg, Reg),
+
+    /// The specialized form of `Less`. (See `IntAdd`)
+    IntLess(Reg, Reg, Reg),
+
+    /// The specialized form of `LessEq`. (See `IntAdd`)
+    IntLessEq(Reg, Reg, Reg),
+
+    /// The specialized form of `Greater`. (See `IntAdd`)
+    IntGreater(Reg, Reg, Reg),
+
+    /// The specialized form of `GreaterEq`. (See `IntAdd`)
+    IntGreaterEq(Reg, Reg, Reg),
+
+    /// The specialized form of `JumpIfNotLess`. (See `IntAdd`)
+    JumpIfNotIntLess(Reg, Reg, Reg, isize),
+
+    /// The specialized form of `JumpIfNotLessEq`. (See `IntAdd`)
+    JumpIfNotIntLessEq(Reg, Reg, Reg, isize),
+
+    /// The specialized form of `JumpIfNotGreater`. (See `IntAdd`)
+    JumpIfNotIntGreater(Reg, Reg, Reg, isize),
+
+    /// The specialized form of `JumpIfNotGreaterEq`. (See `IntAdd`)
+    JumpIfNotIntGreaterEq(Reg, Reg, Reg, isize),
+
+    /// The specialized form of `CallMethod` for a builtin method of int and float, where `.2` is
+    /// the index of the method in the VM. The receiver is expected to be an int or a float.
+    /// (See `IntAdd`)
+    CallNumberMethod(Reg, u8, u16),
+
     /// Do nothing. Only increment the program counter.
     Nop,
 
-    /// Returns the top value of the stack and exits current function.
-    /// The top value of the stack is popped.
-    ///
-    /// # Panic
-    ///
-    /// Stack is empty.
-    Leave,
+    /// Returns the value of `.0` and exits current function.
+    Leave(Reg),
 }
 
 impl fmt::Display for ICode {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         #[rustfmt::skip]
         let res = match self {
-            ICode::LoadIntObject(a0)       => write!(f, "LoadIntObject    {}", a0),
-            ICode::LoadFloatObject(a0)     => write!(f, "LoadFloatObject  {}", a0),
-            ICode::LoadStringObject(a0)    => write!(f, "LoadStringObject {}", a0),
-            ICode::LoadBoolObject(a0)      => write!(f, "LoadBoolObject   {}", a0),
-            ICode::LoadNilObject           => write!(f, "LoadNilObject    "),
-            ICode::LoadLocal(a0)           => write!(f, "LoadLocal        {}", a0.as_usize()),
-            ICode::Unload                  => write!(f, "Unload           "),
-            ICode::StoreLocal(a0)          => write!(f, "StoreLocal       {}", a0.as_usize()),
-            ICode::StoreNewLocal           => write!(f, "StoreNewLocal    "),
-            ICode::MakeArray(a0)           => write!(f, "MakeArray        {}", a0),
-            ICode::MakeTable(a0)           => write!(f, "MakeTable        {}", a0),
-            ICode::DropLocal(a0)           => write!(f, "DropLocal        {}", a0),
-            ICode::Jump(a0)                => write!(f, "Jump             {}", a0),
-            ICode::JumpIfTrue(a0)          => write!(f, "JumpIfTrue       {}", a0),
-            ICode::JumpIfFalse(a0)         => write!(f, "JumpIfFalse      {}", a0),
-            ICode::CallMethod(a0, a1)      => write!(f, "CallMethod       {} {}", a0, a1),
-            ICode::Call(a0)                => write!(f, "Call             {}", a0),
-            ICode::SetItem                 => write!(f, "SetItem          "),
-            ICode::GetItem                 => write!(f, "GetItem          "),
-            ICode::SetMethod(a0)           => write!(f, "SetMethod        {}", a0),
-            ICode::Add                     => write!(f, "Add              "),
-            ICode::Sub                     => write!(f, "Sub              "),
-            ICode::Mul                     => write!(f, "Mul              "),
-            ICode::Div                     => write!(f, "Div              "),
-            ICode::Mod                     => write!(f, "Mod              "),
-            ICode::Unm                     => write!(f, "Unm              "),
-            ICode::Unp                     => write!(f, "Unp              "),
-            ICode::Not                     => write!(f, "Not              "),
-            ICode::Eq                      => write!(f, "Eq               "),
-            ICode::NotEq                   => write!(f, "NotEq            "),
-            ICode::Less                    => write!(f, "Less             "),
-            ICode::LessEq                  => write!(f, "LessEq           "),
-            ICode::Greater                 => write!(f, "Greater          "),
-            ICode::GreaterEq               => write!(f, "GreaterEq        "),
-            ICode::Concat                  => write!(f, "Concat           "),
-            ICode::BitAnd                  => write!(f, "BitAnd           "),
-            ICode::BitOr                   => write!(f, "BitOr            "),
-            ICode::BitXor                  => write!(f, "BitXor           "),
-            ICode::BitNot                  => write!(f, "BitNot           "),
-            ICode::ShiftL                  => write!(f, "ShiftL           "),
-            ICode::ShiftR                  => write!(f, "ShiftR           "),
-            ICode::GetIter                 => write!(f, "GetIter          "),
-            ICode::IterMoveNext            => write!(f, "IterMoveNext     "),
-            ICode::IterCurrent             => write!(f, "IterCurrent      "),
-            ICode::BeginFuncSection        => write!(f, "BeginFuncSection "),
-            ICode::FuncSetProperty(a0, a1) => write!(f, "  SetProperty    param={} start={}", a0, a1),
-            ICode::FuncAddCapture(a0)      => write!(f, "  AddCapture     {}", a0.as_usize()),
-            ICode::EndFuncSection          => write!(f, "EndFuncSection   "),
-            ICode::Nop                     => write!(f, "Nop              "),
-            ICode::Leave                   => write!(f, "Leave            "),
+            ICode::LoadIntObject(a0, a1)      => write!(f, "LoadIntObject    {} {}", a0, a1),
+            ICode::LoadFloatObject(a0, a1)    => write!(f, "LoadFloatObject  {} {}", a0, a1),
+            ICode::LoadStringObject(a0, a1)   => write!(f, "LoadStringObject {} {}", a0, a1),
+            ICode::LoadBoolObject(a0, a1)     => write!(f, "LoadBoolObject   {} {}", a0, a1),
+            ICode::LoadNilObject(a0)          => write!(f, "LoadNilObject    {}", a0),
+            ICode::Move(a0, a1)               => write!(f, "Move             {} {}", a0, a1),
+            ICode::MakeArray(a0, a1)          => write!(f, "MakeArray        {} {}", a0, a1),
+            ICode::MakeTable(a0, a1)          => write!(f, "MakeTable        {} {}", a0, a1),
+            ICode::DropLocal(a0)              => write!(f, "DropLocal        {}", a0),
+            ICode::CloseUpvalues(a0)          => write!(f, "CloseUpvalues    {}", a0),
+            ICode::Jump(a0)                   => write!(f, "Jump             {}", a0),
+            ICode::JumpIfTrue(a0, a1)         => write!(f, "JumpIfTrue       {} {}", a0, a1),
+            ICode::JumpIfFalse(a0, a1)        => write!(f, "JumpIfFalse      {} {}", a0, a1),
+            ICode::JumpIfNil(a0, a1)          => write!(f, "JumpIfNil        {} {}", a0, a1),
+            ICode::JumpIfNotNil(a0, a1)       => write!(f, "JumpIfNotNil     {} {}", a0, a1),
+            ICode::JumpIfNotLess(a0, a1, a2, a3)      => write!(f, "JumpIfNotLess    {} {} {} {}", a0, a1, a2, a3),
+            ICode::JumpIfNotLessEq(a0, a1, a2, a3)    => write!(f, "JumpIfNotLessEq  {} {} {} {}", a0, a1, a2, a3),
+            ICode::JumpIfNotGreater(a0, a1, a2, a3)   => write!(f, "JumpIfNotGreater {} {} {} {}", a0, a1, a2, a3),
+            ICode::JumpIfNotGreaterEq(a0, a1, a2, a3) => write!(f, "JumpIfNotGreaterEq {} {} {} {}", a0, a1, a2, a3),
+            ICode::JumpIfNotEq(a0, a1, a2, a3)        => write!(f, "JumpIfNotEq      {} {} {} {}", a0, a1, a2, a3),
+            ICode::Call(a0, a1)               => write!(f, "Call             {} {}", a0, a1),
+            ICode::TailCall(a0, a1)           => write!(f, "TailCall         {} {}", a0, a1),
+            ICode::CallMethod(a0, a1, a2)     => write!(f, "CallMethod       {} {} {}", a0, a1, a2),
+            ICode::SetItem(a0, a1, a2)        => write!(f, "SetItem          {} {} {}", a0, a1, a2),
+            ICode::GetItem(a0, a1, a2)        => write!(f, "GetItem          {} {} {}", a0, a1, a2),
+            ICode::TestTable(a0, a1)          => write!(f, "TestTable        {} {}", a0, a1),
+            ICode::TestArray(a0, a1, a2)      => write!(f, "TestArray        {} {} {}", a0, a1, a2),
+            ICode::SetMethod(a0, a1, a2)      => write!(f, "SetMethod        {} {} {}", a0, a1, a2),
+            ICode::Add(a0, a1, a2)            => write!(f, "Add              {} {} {}", a0, a1, a2),
+            ICode::Sub(a0, a1, a2)            => write!(f, "Sub              {} {} {}", a0, a1, a2),
+            ICode::AddInt(a0, a1, a2)         => write!(f, "AddInt           {} {} {}", a0, a1, a2),
+            ICode::SubInt(a0, a1, a2)         => write!(f, "SubInt           {} {} {}", a0, a1, a2),
+            ICode::Mul(a0, a1, a2)            => write!(f, "Mul              {} {} {}", a0, a1, a2),
+            ICode::Div(a0, a1, a2)            => write!(f, "Div              {} {} {}", a0, a1, a2),
+            ICode::Mod(a0, a1, a2)            => write!(f, "Mod              {} {} {}", a0, a1, a2),
+            ICode::Unm(a0, a1)                => write!(f, "Unm              {} {}", a0, a1),
+            ICode::Unp(a0, a1)                => write!(f, "Unp              {} {}", a0, a1),
+            ICode::Not(a0, a1)                => write!(f, "Not              {} {}", a0, a1),
+            ICode::Eq(a0, a1, a2)             => write!(f, "Eq               {} {} {}", a0, a1, a2),
+            ICode::NotEq(a0, a1, a2)          => write!(f, "NotEq            {} {} {}", a0, a1, a2),
+            ICode::Less(a0, a1, a2)           => write!(f, "Less             {} {} {}", a0, a1, a2),
+            ICode::LessEq(a0, a1, a2)         => write!(f, "LessEq           {} {} {}", a0, a1, a2),
+            ICode::Greater(a0, a1, a2)        => write!(f, "Greater          {} {} {}", a0, a1, a2),
+            ICode::GreaterEq(a0, a1, a2)      => write!(f, "GreaterEq        {} {} {}", a0, a1, a2),
+            ICode::Concat(a0, a1, a2)         => write!(f, "Concat           {} {} {}", a0, a1, a2),
+            ICode::Format(a0, a1, a2)         => write!(f, "Format           {} {} {:?}", a0, a1, a2),
+            ICode::BitAnd(a0, a1, a2)         => write!(f, "BitAnd           {} {} {}", a0, a1, a2),
+            ICode::BitOr(a0, a1, a2)          => write!(f, "BitOr            {} {} {}", a0, a1, a2),
+            ICode::BitXor(a0, a1, a2)         => write!(f, "BitXor           {} {} {}", a0, a1, a2),
+            ICode::BitNot(a0, a1)             => write!(f, "BitNot           {} {}", a0, a1),
+            ICode::ShiftL(a0, a1, a2)         => write!(f, "ShiftL           {} {} {}", a0, a1, a2),
+            ICode::ShiftR(a0, a1, a2)         => write!(f, "ShiftR           {} {} {}", a0, a1, a2),
+            ICode::GetIter(a0, a1)            => write!(f, "GetIter          {} {}", a0, a1),
+            ICode::IterMoveNext(a0, a1)       => write!(f, "IterMoveNext     {} {}", a0, a1),
+            ICode::IterCurrent(a0, a1)        => write!(f, "IterCurrent      {} {}", a0, a1),
+            ICode::BeginFuncSection(a0)       => write!(f, "BeginFuncSection {}", a0),
+            ICode::FuncSetProperty(a0, a1, a2) => write!(f, "  SetProperty    param={} start={} end={}", a0, a1, a2),
+            ICode::FuncAddCapture(a0)         => write!(f, "  AddCapture     {}", a0),
+            ICode::EndFuncSection             => write!(f, "EndFuncSection   "),
+            ICode::IntAdd(a0, a1, a2)         => write!(f, "IntAdd           {} {} {}", a0, a1, a2),
+            ICode::IntSub(a0, a1, a2)         => write!(f, "IntSub           {} {} {}", a0, a1, a2),
+            ICode::IntMul(a0, a1, a2)         => write!(f, "IntMul           {} {} {}", a0, a1, a2),
+            ICode::IntLess(a0, a1, a2)        => write!(f, "IntLess          {} {} {}", a0, a1, a2),
+            ICode::IntLessEq(a0, a1, a2)      => write!(f, "IntLessEq        {} {} {}", a0, a1, a2),
+            ICode::IntGreater(a0, a1, a2)     => write!(f, "IntGreater       {} {} {}", a0, a1, a2),
+            ICode::IntGreaterEq(a0, a1, a2)   => write!(f, "IntGreaterEq     {} {} {}", a0, a1, a2),
+            ICode::JumpIfNotIntLess(a0, a1, a2, a3)       => write!(f, "JumpIfNotIntLess {} {} {} {}", a0, a1, a2, a3),
+            ICode::JumpIfNotIntLessEq(a0, a1, a2, a3)     => write!(f, "JumpIfNotIntLessEq {} {} {} {}", a0, a1, a2, a3),
+            ICode::JumpIfNotIntGreater(a0, a1, a2, a3)    => write!(f, "JumpIfNotIntGreater {} {} {} {}", a0, a1, a2, a3),
+            ICode::JumpIfNotIntGreaterEq(a0, a1, a2, a3)  => write!(f, "JumpIfNotIntGreaterEq {} {} {} {}", a0, a1, a2, a3),
+            ICode::CallNumberMethod(a0, a1, a2) => write!(f, "CallNumberMethod {} {} {}", a0, a1, a2),
+            ICode::Nop                        => write!(f, "Nop              "),
+            ICode::Leave(a0)                  => write!(f, "Leave            {}", a0),
         };
         res
     }
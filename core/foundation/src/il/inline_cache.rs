@@ -0,0 +1,49 @@
+use super::ICode;
+use crate::object::{FieldCache, MethodCache};
+
+/// The inline cache of an instruction, which looks up a field or a method of tables.
+/// (See `Executable::field_cache()` and `Executable::method_cache()`)
+#[derive(Debug)]
+pub(super) enum InlineCache {
+    None,
+    Field(FieldCache),
+    Method(MethodCache),
+}
+
+impl InlineCache {
+    pub(super) fn for_icode(icode: &ICode) -> Self {
+        use ICode::*;
+        match icode {
+            GetItem(..) => InlineCache::Field(FieldCache::new()),
+            // The binary operators look up the method of the operand.
+            Call(..)
+            | TailCall(..)
+            | CallMethod(..)
+            | Add(..)
+            | Sub(..)
+            | AddInt(..)
+            | SubInt(..)
+            | Mul(..)
+            | Div(..)
+            | Mod(..)
+            | Eq(..)
+            | NotEq(..)
+            | Less(..)
+            | LessEq(..)
+            | Greater(..)
+            | GreaterEq(..)
+            | JumpIfNotLess(..)
+            | JumpIfNotLessEq(..)
+            | JumpIfNotGreater(..)
+            | JumpIfNotGreaterEq(..)
+            | JumpIfNotEq(..)
+            | Concat(..)
+            | BitAnd(..)
+            | BitOr(..)
+            | BitXor(..)
+            | ShiftL(..)
+            | ShiftR(..) => InlineCache::Method(MethodCache::new()),
+            _ => InlineCache::None,
+        }
+    }
+}
@@ -1,27 +1,28 @@
 use super::*;
-use crate::object::RustFunction;
 
 pub struct Module {
     executable: Executable,
-    default_rfuns: Box<[(&'static str, RustFunction)]>,
+    default_globals: Box<[&'static str]>,
     source_info: SourceInfo,
 }
 
 impl Module {
     pub const fn new(
         executable: Executable,
-        default_rfuns: Box<[(&'static str, RustFunction)]>,
+        default_globals: Box<[&'static str]>,
         source_info: SourceInfo,
     ) -> Self {
         Self {
             executable,
-            default_rfuns,
+            default_globals,
             source_info,
         }
     }
 
-    pub fn default_rfuncs(&self) -> &[(&'static str, RustFunction)] {
-        &self.default_rfuns
+    /// The names of the default globals (e.g. `print`, `io`) captured by the module, in the order
+    /// the executable expects them as locals. The values are provided by the runtime.
+    pub fn default_globals(&self) -> &[&'static str] {
+        &self.default_globals
     }
 
     pub fn executable(&self) -> &Executable {
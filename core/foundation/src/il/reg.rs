@@ -0,0 +1,58 @@
+use core::fmt;
+
+/// A register of a call frame.
+///
+/// The locals of a function occupy the registers in order of declaration, and the temporaries are
+/// allocated above them.
+///
+/// A register used as an operand can be marked as `take`. Then the value is moved out of the
+/// register instead of being copied, so that a temporary does not keep an object alive after its
+/// last use.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Reg(u16);
+
+impl Reg {
+    const TAKE: u16 = 1 << 15;
+
+    /// The maximum index of a register.
+    pub const MAX: usize = (Self::TAKE - 1) as usize;
+
+    pub const fn new(index: usize) -> Self {
+        assert!(index <= Self::MAX, "Too many registers in a function.");
+        Reg(index as u16)
+    }
+
+    pub const fn as_usize(&self) -> usize {
+        (self.0 & !Self::TAKE) as usize
+    }
+
+    /// Returns the same register marked as `take`.
+    pub const fn take(self) -> Self {
+        Reg(self.0 | Self::TAKE)
+    }
+
+    pub const fn is_take(&self) -> bool {
+        self.0 & Self::TAKE != 0
+    }
+
+    /// Returns the same register without the `take` mark.
+    pub const fn plain(self) -> Self {
+        Reg(self.0 & !Self::TAKE)
+    }
+
+    /// Returns the register `offset` registers after this one, without the `take` mark.
+    pub const fn offset(self, offset: usize) -> Self {
+        Reg::new(self.as_usize() + offset)
+    }
+}
+
+impl fmt::Display for Reg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // `r3*` is the register 3 marked as `take`.
+        if self.is_take() {
+            write!(f, "r{}*", self.as_usize())
+        } else {
+            write!(f, "r{}", self.as_usize())
+        }
+    }
+}
@@ -1,5 +1,8 @@
 use super::*;
-use crate::{object::UString, syntax::SyntaxToken};
+use crate::{
+    object::{FormatSpec, UString},
+    syntax::SyntaxToken,
+};
 use core::num::NonZero;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -106,6 +109,7 @@ pub enum Effect {
         table: ValueKey,
         name: StringKey,
         args: ValueSliceKey,
+        optional: bool,
     },
     Return {
         value: ValueKey,
@@ -126,6 +130,10 @@ pub enum Value {
         else_: EffectsKey,
         else_tail: ValueKey,
     },
+    Match {
+        value: ValueKey,
+        arms: Box<[MatchArm]>,
+    },
     Prefix {
         op: PrefixOp,
         value: ValueKey,
@@ -146,11 +154,13 @@ pub enum Value {
     Field {
         value: ValueKey,
         name: StringKey,
+        optional: bool,
     },
     MethodCall {
         value: ValueKey,
         name: StringKey,
         args: ValueSliceKey,
+        optional: bool,
     },
     Block {
         effects: EffectsKey,
@@ -171,6 +181,38 @@ pub enum Value {
     Table {
         fields: Box<[(TableKeyName, ValueKey)]>,
     },
+    Interpolation {
+        parts: Box<[InterpolationPart]>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub body: EffectsKey,
+    pub tail: ValueKey,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Pattern {
+    Wildcard,
+    Binding(SymbolKey),
+    Literal(ValueKey),
+    Table(Box<[(StringKey, Pattern)]>),
+    Array(Box<[Pattern]>),
+}
+
+impl Pattern {
+    /// Returns `true` if the pattern matches any value.
+    pub fn is_irrefutable(&self) -> bool {
+        matches!(self, Pattern::Wildcard | Pattern::Binding(_))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum InterpolationPart {
+    String(SyntaxToken, UString),
+    Value(ValueKey, Option<(SyntaxToken, FormatSpec)>),
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -209,6 +251,7 @@ pub enum BinaryOp {
     BitAnd(SyntaxToken),
     BitOr(SyntaxToken),
     BitXor(SyntaxToken),
+    NilCoalesce(SyntaxToken),
     Assign(SyntaxToken),
     Missing,
 }
@@ -29,6 +29,10 @@ impl Module {
     pub fn strage(&self) -> &Strage {
         &self.strage
     }
+
+    pub fn into_parts(self) -> (EffectsKey, Vec<FunctionKey>, Strage) {
+        (self.effects, self.functions, self.strage)
+    }
 }
 
 // p (prefix): [p]ut, first line has not indent
@@ -177,10 +181,11 @@ fn wl_effects<'s>(
                 wl!(f, indent + 1, "]")?;
                 wl!(f, indent, "}}")?;
             }
-            Effect::MethodCall { table, name, args } => {
+            Effect::MethodCall { table, name, args, optional } => {
                 wl!(f, indent, "MethodCall@{:?} {{", syntax.text_range())?;
                 w!(f, indent + 1, "table: ")?; pl_value(f, indent + 1, strage.get(table), strage)?;
                 w!(f, indent + 1, "name: ")?; pl_string(f, strage.get(name))?;
+                wl!(f, indent + 1, "optional: {}", optional)?;
                 wl!(f, indent + 1, "args: [")?;
                 wl_values(f, indent + 2, strage.get(args), strage)?;
                 wl!(f, indent + 1, "]")?;
@@ -242,6 +247,21 @@ fn pl_value(
             w!(f, indent + 1, "else_tail: ")?; pl_value(f, indent + 1, strage.get(else_tail), strage)?;
             wl!(f, indent,"}}")?;
         }
+        Value::Match { value, arms } => {
+            pl!(f, "Match@{:?} {{", syntax.text_range())?;
+            w!(f, indent + 1, "value: ")?; pl_value(f, indent + 1, strage.get(value), strage)?;
+            wl!(f, indent + 1, "arms: [")?;
+            for MatchArm { pattern, body, tail } in arms.iter() {
+                wl!(f, indent + 2, "{{")?;
+                w!(f, indent + 3, "pattern: ")?; pl_pattern(f, indent + 3, pattern, strage)?;
+                w!(f, indent + 3, "body:")?;
+                wl_effects(f, indent + 4, strage.get(body), strage)?;
+                w!(f, indent + 3, "tail: ")?; pl_value(f, indent + 3, strage.get(tail), strage)?;
+                wl!(f, indent + 2, "}}")?;
+            }
+            wl!(f, indent + 1, "]")?;
+            wl!(f, indent,"}}")?;
+        }
         Value::Prefix { op, value } => {
             pl!(f, "Prefix@{:?} {{", syntax.text_range())?;
             w!(f, indent + 1, "op: ")?; match op {
@@ -276,6 +296,7 @@ fn pl_value(
                 BinaryOp::BitAnd(syntax)   => pl!(f, "'&'@{:?}",   syntax.text_range())?,
                 BinaryOp::BitOr(syntax)    => pl!(f, "'|'@{:?}",   syntax.text_range())?,
                 BinaryOp::BitXor(syntax)   => pl!(f, "'^'@{:?}",   syntax.text_range())?,
+                BinaryOp::NilCoalesce(syntax) => pl!(f, "'??'@{:?}", syntax.text_range())?,
                 BinaryOp::Assign(syntax)   => pl!(f, "'='@{:?}",   syntax.text_range())?,
                 BinaryOp::Missing => pl!(f, "None")?,
             }
@@ -297,16 +318,18 @@ fn pl_value(
             w!(f, indent + 1, "index: ")?; pl_value(f, indent + 1, strage.get(index), strage)?;
             wl!(f, indent,"}}")?;
         }
-        Value::Field { value, name } => {
+        Value::Field { value, name, optional } => {
             pl!(f, "Field@{:?} {{", syntax.text_range())?;
             w!(f, indent + 1, "value: ")?; pl_value(f, indent + 1, strage.get(value), strage)?;
             w!(f, indent + 1, "name: ")?; pl_string(f, strage.get(name))?;
+            wl!(f, indent + 1, "optional: {}", optional)?;
             wl!(f, indent,"}}")?;
         }
-        Value::MethodCall { value, name, args } => {
+        Value::MethodCall { value, name, args, optional } => {
             pl!(f, "MethodCall@{:?} {{", syntax.text_range())?;
             w!(f, indent + 1, "value: ")?; pl_value(f, indent + 1, strage.get(value), strage)?;
             w!(f, indent + 1, "name: ")?; pl_string(f, strage.get(name))?;
+            wl!(f, indent + 1, "optional: {}", optional)?;
             wl!(f, indent + 1, "args: [")?;
             wl_values(f, indent + 2, strage.get(args), strage)?;
             wl!(f, indent + 1, "]")?;
@@ -358,10 +381,60 @@ fn pl_value(
             }
             wl!(f, indent,"}}")?;
         }
+        Value::Interpolation { parts } => {
+            pl!(f, "Interpolation@{:?} [", syntax.text_range())?;
+            for part in parts {
+                match part {
+                    InterpolationPart::String(syntax, string) => {
+                        wl!(f, indent + 1, "\"{}\"@{:?}", string, syntax.text_range())?;
+                    }
+                    InterpolationPart::Value(value, format_spec) => {
+                        w!(f, indent + 1, "")?; pl_value(f, indent + 1, strage.get(value), strage)?;
+                        if let Some((syntax, _)) = format_spec {
+                            wl!(f, indent + 2, "format_spec: {:?}@{:?}", syntax.text(), syntax.text_range())?;
+                        }
+                    }
+                }
+            }
+            wl!(f, indent,"]")?;
+        }
     };
     Ok(())
 }
 
+#[rustfmt::skip]
+fn pl_pattern(
+    f: &mut fmt::Formatter<'_>,
+    indent: u32,
+    pattern: &Pattern,
+    strage: &Strage,
+) -> fmt::Result {
+    match pattern {
+        Pattern::Wildcard => pl!(f, "Wildcard"),
+        Pattern::Binding(symbol) => {
+            p!(f, "Binding ")?; pl_symbol(f, strage.get(symbol))
+        }
+        Pattern::Literal(value) => {
+            p!(f, "Literal ")?; pl_value(f, indent, strage.get(value), strage)
+        }
+        Pattern::Table(fields) => {
+            pl!(f, "Table {{")?;
+            for (key, pattern) in fields.iter() {
+                w!(f, indent + 1, "key: ")?; pl_string(f, strage.get(key))?;
+                w!(f, indent + 1, "pattern: ")?; pl_pattern(f, indent + 1, pattern, strage)?;
+            }
+            wl!(f, indent, "}}")
+        }
+        Pattern::Array(elements) => {
+            pl!(f, "Array [")?;
+            for pattern in elements.iter() {
+                w!(f, indent + 1, "")?; pl_pattern(f, indent + 1, pattern, strage)?;
+            }
+            wl!(f, indent, "]")
+        }
+    }
+}
+
 fn pl_symbol(f: &mut fmt::Formatter<'_>, symbol: Option<(&SyntaxToken, &Symbol)>) -> fmt::Result {
     if let Some((syntax, symbol)) = symbol {
         let range = syntax.text_range();
@@ -23,6 +23,25 @@ impl<'s> Strage {
     }
 }
 
+impl Strage {
+    /// Replaces the value at `key`, keeping its syntax node.
+    pub fn set_value(&mut self, key: &ValueKey, value: Value) {
+        let index = key.0.expect("Cannot set a value to the missing value key");
+        match self.arena.get_mut(index) {
+            RawData::Value(_, dst) => *dst = value,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Replaces the `index`-th effect of `key`, keeping its syntax node.
+    pub fn set_effect(&mut self, key: &EffectsKey, index: usize, effect: Effect) {
+        match &mut self.arena.get_slice_mut(key.0)[index] {
+            RawData::Effect(_, dst) => *dst = effect,
+            _ => unreachable!(),
+        }
+    }
+}
+
 impl fmt::Debug for Strage {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Strage")
@@ -88,6 +107,11 @@ impl ValueSliceKey {
         self.0.as_u64()
     }
 
+    /// Returns the keys of the values in the slice.
+    pub fn keys(&self) -> impl ExactSizeIterator<Item = ValueKey> {
+        self.0.indices().map(|index| ValueKey(Some(index)))
+    }
+
     pub fn len(&self) -> usize {
         self.0.len()
     }
@@ -116,6 +140,11 @@ impl EffectsKey {
         self.0.as_u64()
     }
 
+    /// Returns the key of the first `len` effects.
+    pub fn truncated(&self, len: usize) -> Self {
+        Self(self.0.truncated(len))
+    }
+
     pub fn len(&self) -> usize {
         self.0.len()
     }
@@ -211,6 +240,18 @@ impl FunctionKey {
     pub fn as_u64(&self) -> u64 {
         self.0.as_u64()
     }
+
+    /// Returns the key of the effects in the function body.
+    pub fn body(&self, strage: &Strage) -> EffectsKey {
+        let (params, _) = self.get(strage);
+        EffectsKey(self.0.split_at(params.count()).1)
+    }
+
+    /// Returns the key of the function whose body is the first `len` effects of this one.
+    pub fn truncated(&self, len: usize, strage: &Strage) -> Self {
+        let params_len = self.0.len() - self.body(strage).len();
+        Self(self.0.truncated(params_len + len))
+    }
 }
 
 impl<'s> StrageKey<'s> for FunctionKey {
@@ -4,21 +4,40 @@ use core::fmt;
 mod pms_gc;
 use pms_gc::*;
 
+/// Control and statistics of the cycle collector.
+pub mod gc {
+    pub use super::pms_gc::{
+        collect, has_finalizable, mode, set_mode, stats, take_finalizable, Mode, Stats,
+    };
+}
+
 mod ustring;
 pub use ustring::UString;
 
+mod method_name;
+pub use method_name::MethodName;
+
 mod array;
 pub use array::Array;
 
 mod table;
-pub use table::{Table, TableMethod};
+pub use table::{FieldCache, MethodCache, Table, TableCache, TableMethod};
 
 mod function;
 pub use function::Function;
 
+mod upvalue;
+pub use upvalue::{Upvalue, UpvalueState};
+
 mod rust_function;
 pub use rust_function::RustFunction;
 
+mod weak_ref;
+pub use weak_ref::WeakRef;
+
+mod format_spec;
+pub use format_spec::*;
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum Object {
     Int(i64),
@@ -30,6 +49,7 @@ pub enum Object {
     Table(Table),
     Function(Function),
     RustFunction(RustFunction),
+    WeakRef(WeakRef),
 }
 
 fn _size_check() {
@@ -63,6 +83,7 @@ impl Object {
             Object::Table(_) => "table",
             Object::Function(_) => "function",
             Object::RustFunction(_) => "function",
+            Object::WeakRef(_) => "weakref",
         }
     }
 }
@@ -77,6 +98,7 @@ impl_from_variant! {
         Table: Table,
         Function: Function,
         RustFunction: RustFunction,
+        WeakRef: WeakRef,
     }
 }
 
@@ -93,6 +115,7 @@ impl fmt::Display for Object {
             Object::Table(x) => write!(f, "{:?}", x),
             Object::Function(x) => write!(f, "{:?}", x),
             Object::RustFunction(x) => write!(f, "{:?}", x),
+            Object::WeakRef(x) => write!(f, "{:?}", x),
         }
     }
 }
@@ -20,9 +20,13 @@ pub(crate) struct Inner {
     version: u64,
     ref_count: Cell<usize>,
     color: Cell<Color>,
+    buffered: Cell<bool>,
+    weak_count: Cell<usize>,
 }
 
 unsafe impl PmsInner for Inner {
+    const KIND: Kind = Kind::Array;
+
     fn ref_count_ref(&self) -> &Cell<usize> {
         &self.ref_count
     }
@@ -31,6 +35,14 @@ unsafe impl PmsInner for Inner {
         &self.color
     }
 
+    fn buffered_ref(&self) -> &Cell<bool> {
+        &self.buffered
+    }
+
+    fn weak_count_ref(&self) -> &Cell<usize> {
+        &self.weak_count
+    }
+
     unsafe fn iter_children_mut(&mut self) -> impl Iterator<Item = &mut Object> {
         self.data.iter_mut()
     }
@@ -57,6 +69,11 @@ impl Array {
         self.len() == 0
     }
 
+    /// Returns `true` if the two arrays are the same object, not only equal.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        self.ptr == other.ptr
+    }
+
     pub fn version(&self) -> u64 {
         self.inner().version
     }
@@ -121,11 +138,14 @@ impl<const N: usize> From<[Object; N]> for Array {
 
 impl From<Vec<Object>> for Array {
     fn from(value: Vec<Object>) -> Self {
+        register_allocation(Kind::Array);
         let ptr = Box::leak(Box::new(Inner {
             data: value,
             version: 0,
             ref_count: Cell::new(1),
             color: Cell::new(Color::Black),
+            buffered: Cell::new(false),
+            weak_count: Cell::new(0),
         }));
         Array {
             ptr: NonNull::from(ptr),
@@ -169,6 +189,7 @@ impl fmt::Debug for Array {
                 Object::Array(_x) => &"Array", // TODO: なんかいい感じにする
                 Object::Table(_x) => &"Table",
                 Object::RustFunction(x) => x,
+                Object::WeakRef(x) => x,
             });
         }
         dbg.finish()
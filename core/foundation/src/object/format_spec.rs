@@ -0,0 +1,282 @@
+use super::Object;
+use core::fmt;
+
+/// The format specification used in the string interpolation, e.g. `"{x:>8.2}"`.
+///
+/// ```text
+/// format_spec := [[fill] align] ['+'] ['0'] [width] ['.' precision] [type]
+/// fill        := <any character>
+/// align       := '<' | '>' | '^'
+/// width       := <integer>
+/// precision   := <integer>
+/// type        := 'x' | 'X' | 'o' | 'b' | 'e'
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FormatSpec {
+    fill: char,
+    align: Option<FormatAlign>,
+    sign_plus: bool,
+    zero_pad: bool,
+    width: usize,
+    precision: Option<usize>,
+    type_: Option<FormatType>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FormatAlign {
+    Left,
+    Right,
+    Center,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FormatType {
+    /// 'x'
+    LowerHex,
+    /// 'X'
+    UpperHex,
+    /// 'o'
+    Octal,
+    /// 'b'
+    Binary,
+    /// 'e'
+    Exponent,
+}
+
+impl FormatType {
+    pub fn as_char(&self) -> char {
+        match self {
+            FormatType::LowerHex => 'x',
+            FormatType::UpperHex => 'X',
+            FormatType::Octal => 'o',
+            FormatType::Binary => 'b',
+            FormatType::Exponent => 'e',
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FormatSpecParseError {
+    /// The width or the precision is too large.
+    TooLargeNumber,
+    /// The '.' is not followed by the precision.
+    MissingPrecision,
+    /// The character at the byte offset `.0` is not expected.
+    UnexpectedChar(usize),
+}
+
+impl fmt::Display for FormatSpecParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormatSpecParseError::TooLargeNumber => write!(f, "Too large number in format spec"),
+            FormatSpecParseError::MissingPrecision => {
+                write!(f, "Missing precision after '.' in format spec")
+            }
+            FormatSpecParseError::UnexpectedChar(_) => {
+                write!(f, "Unexpected character in format spec")
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FormatError {
+    type_: FormatType,
+    value_type_name: &'static str,
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Format type '{}' cannot be applied to the value of type '{}'.",
+            self.type_.as_char(),
+            self.value_type_name
+        )
+    }
+}
+
+impl FormatSpec {
+    /// Parses the format spec text that does not include the leading ':'.
+    pub fn parse(spec: &str) -> Result<Self, FormatSpecParseError> {
+        const MAX_NUMBER: usize = u16::MAX as usize;
+
+        fn to_align(c: char) -> Option<FormatAlign> {
+            match c {
+                '<' => Some(FormatAlign::Left),
+                '>' => Some(FormatAlign::Right),
+                '^' => Some(FormatAlign::Center),
+                _ => None,
+            }
+        }
+        fn eat_number(rest: &mut &str) -> Result<Option<usize>, FormatSpecParseError> {
+            let len = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            if len == 0 {
+                return Ok(None);
+            }
+            let number = match rest[..len].parse::<usize>() {
+                Ok(number) if number <= MAX_NUMBER => number,
+                _ => return Err(FormatSpecParseError::TooLargeNumber),
+            };
+            *rest = &rest[len..];
+            Ok(Some(number))
+        }
+
+        let mut format_spec = FormatSpec::default();
+        let mut rest = spec;
+
+        let mut chars = rest.chars();
+        match (chars.next(), chars.next().and_then(to_align)) {
+            (Some(fill), Some(align)) => {
+                format_spec.fill = fill;
+                format_spec.align = Some(align);
+                rest = chars.as_str();
+            }
+            (Some(c), None) if to_align(c).is_some() => {
+                format_spec.align = to_align(c);
+                rest = &rest[1..];
+            }
+            _ => {}
+        }
+        if let Some(r) = rest.strip_prefix('+') {
+            format_spec.sign_plus = true;
+            rest = r;
+        }
+        if let Some(r) = rest.strip_prefix('0') {
+            format_spec.zero_pad = true;
+            rest = r;
+        }
+        if let Some(width) = eat_number(&mut rest)? {
+            format_spec.width = width;
+        }
+        if let Some(r) = rest.strip_prefix('.') {
+            rest = r;
+            match eat_number(&mut rest)? {
+                Some(precision) => format_spec.precision = Some(precision),
+                None => return Err(FormatSpecParseError::MissingPrecision),
+            }
+        }
+        let mut chars = rest.chars();
+        format_spec.type_ = match chars.next() {
+            Some('x') => Some(FormatType::LowerHex),
+            Some('X') => Some(FormatType::UpperHex),
+            Some('o') => Some(FormatType::Octal),
+            Some('b') => Some(FormatType::Binary),
+            Some('e') => Some(FormatType::Exponent),
+            _ => None,
+        };
+        if format_spec.type_.is_some() {
+            rest = chars.as_str();
+        }
+
+        if !rest.is_empty() {
+            return Err(FormatSpecParseError::UnexpectedChar(
+                spec.len() - rest.len(),
+            ));
+        }
+        Ok(format_spec)
+    }
+
+    pub fn format(&self, object: &Object) -> Result<String, FormatError> {
+        let (sign, body) = match object {
+            Object::Int(x) => {
+                let sign = if *x < 0 { "-" } else { self.plus_sign() };
+                let abs = x.unsigned_abs();
+                let body = match (self.type_, self.precision) {
+                    (Some(FormatType::LowerHex), _) => format!("{:x}", abs),
+                    (Some(FormatType::UpperHex), _) => format!("{:X}", abs),
+                    (Some(FormatType::Octal), _) => format!("{:o}", abs),
+                    (Some(FormatType::Binary), _) => format!("{:b}", abs),
+                    (Some(FormatType::Exponent), Some(p)) => format!("{:.*e}", p, abs as f64),
+                    (Some(FormatType::Exponent), None) => format!("{:e}", abs as f64),
+                    (None, Some(p)) => format!("{:.*}", p, abs as f64),
+                    (None, None) => abs.to_string(),
+                };
+                (sign, body)
+            }
+            Object::Float(x) => {
+                let sign = if x.is_sign_negative() && !x.is_nan() {
+                    "-"
+                } else {
+                    self.plus_sign()
+                };
+                let abs = x.abs();
+                let body = match (self.type_, self.precision) {
+                    (Some(FormatType::Exponent), Some(p)) => format!("{:.*e}", p, abs),
+                    (Some(FormatType::Exponent), None) => format!("{:e}", abs),
+                    (Some(type_), _) => return Err(self.type_error(type_, object)),
+                    (None, Some(p)) => format!("{:.*}", p, abs),
+                    (None, None) => abs.to_string(),
+                };
+                (sign, body)
+            }
+            _ => {
+                if let Some(type_) = self.type_ {
+                    return Err(self.type_error(type_, object));
+                }
+                let string = object.to_string();
+                let body = match self.precision {
+                    Some(p) => string.chars().take(p).collect(),
+                    None => string,
+                };
+                return Ok(self.pad(body, FormatAlign::Left));
+            }
+        };
+
+        let len = sign.chars().count() + body.chars().count();
+        if self.zero_pad && self.align.is_none() && len < self.width {
+            let zeros = "0".repeat(self.width - len);
+            return Ok(format!("{}{}{}", sign, zeros, body));
+        }
+        Ok(self.pad(format!("{}{}", sign, body), FormatAlign::Right))
+    }
+
+    fn plus_sign(&self) -> &'static str {
+        if self.sign_plus {
+            "+"
+        } else {
+            ""
+        }
+    }
+
+    fn type_error(&self, type_: FormatType, object: &Object) -> FormatError {
+        FormatError {
+            type_,
+            value_type_name: object.type_name(),
+        }
+    }
+
+    fn pad(&self, string: String, default_align: FormatAlign) -> String {
+        let len = string.chars().count();
+        if len >= self.width {
+            return string;
+        }
+        let padding = self.width - len;
+        let (left, right) = match self.align.unwrap_or(default_align) {
+            FormatAlign::Left => (0, padding),
+            FormatAlign::Right => (padding, 0),
+            FormatAlign::Center => (padding / 2, padding - padding / 2),
+        };
+        let mut result = String::with_capacity(string.len() + padding * self.fill.len_utf8());
+        result.extend(core::iter::repeat_n(self.fill, left));
+        result.push_str(&string);
+        result.extend(core::iter::repeat_n(self.fill, right));
+        result
+    }
+}
+
+impl Default for FormatSpec {
+    fn default() -> Self {
+        Self {
+            fill: ' ',
+            align: None,
+            sign_plus: false,
+            zero_pad: false,
+            width: 0,
+            precision: None,
+            type_: None,
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use super::*;
 use crate::il::Executable;
-use core::cell::{Cell, RefCell};
+use core::cell::Cell;
 use std::rc::Rc;
 
 #[derive(Clone, Debug)]
@@ -9,22 +9,31 @@ pub struct Function(Rc<Inner>);
 #[derive(Clone, Debug)]
 struct Inner {
     exe: Executable,
-    env: Box<[Rc<RefCell<Object>>]>,
+    env: Box<[Upvalue]>,
     param_len: u8,
     start_index: usize,
+    end_index: usize,
     call_count: Cell<u32>,
 }
 
 impl Function {
-    pub fn new<I>(exe: Executable, param_len: u8, start_index: usize, env: I) -> Self
+    /// Creates a function whose code is `start_index..end_index` of `exe`.
+    pub fn new<I>(
+        exe: Executable,
+        param_len: u8,
+        start_index: usize,
+        end_index: usize,
+        env: I,
+    ) -> Self
     where
-        I: IntoIterator<Item = Rc<RefCell<Object>>>,
+        I: IntoIterator<Item = Upvalue>,
     {
         Function(Rc::new(Inner {
             exe,
             env: env.into_iter().collect(),
             param_len,
             start_index,
+            end_index,
             call_count: Cell::new(0),
         }))
     }
@@ -33,7 +42,8 @@ impl Function {
         &self.0.exe
     }
 
-    pub fn environment(&self) -> &[Rc<RefCell<Object>>] {
+    /// The locals captured by the function.
+    pub fn environment(&self) -> &[Upvalue] {
         &self.0.env
     }
 
@@ -45,6 +55,11 @@ impl Function {
         self.0.start_index
     }
 
+    /// The index next to the last instruction of the function.
+    pub fn end_index(&self) -> usize {
+        self.0.end_index
+    }
+
     pub fn call_count(&self) -> u32 {
         self.0.call_count.get()
     }
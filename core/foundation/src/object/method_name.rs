@@ -0,0 +1,61 @@
+use super::UString;
+use core::{borrow::Borrow, fmt};
+
+/// The name of a method, which is an interned string, i.e. a symbol. (See `UString::intern()`)
+///
+/// The names interned by the same thread are compared by their identity, so a method is found
+/// without comparing the contents of the names. (See `Table::get_method_by_name()`)
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MethodName(UString);
+
+impl MethodName {
+    pub fn new(name: &str) -> Self {
+        MethodName(UString::intern(name))
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    pub fn as_ustring(&self) -> &UString {
+        &self.0
+    }
+}
+
+impl From<&str> for MethodName {
+    fn from(name: &str) -> Self {
+        MethodName::new(name)
+    }
+}
+
+impl From<UString> for MethodName {
+    fn from(name: UString) -> Self {
+        MethodName(name.into_interned())
+    }
+}
+
+impl AsRef<str> for MethodName {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+// `Ord for UString` compares the contents unless the strings are the same, so the names can be
+// looked up by `str` in a sorted map.
+impl Borrow<str> for MethodName {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Display for MethodName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl fmt::Debug for MethodName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
@@ -1,6 +1,13 @@
-use super::{array::Inner as ArrayInner, table::Inner as TableInner, Array, Object, Table};
-use core::{alloc::Layout, cell::Cell, fmt::Debug, mem, ptr, ptr::NonNull};
-use std::alloc;
+use super::{Array, Object, Table};
+use core::{
+    alloc::Layout,
+    cell::{Cell, RefCell},
+    fmt::Debug,
+    mem, ptr,
+    ptr::NonNull,
+    time::Duration,
+};
+use std::{alloc, collections::VecDeque, time::Instant};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum Color {
@@ -22,8 +29,12 @@ macro_rules! debug_assert_ptr_is_not_freed {
 /// # Safety
 /// TODO
 pub(crate) unsafe trait PmsInner {
+    const KIND: Kind;
+
     fn ref_count_ref(&self) -> &Cell<usize>;
     fn color_ref(&self) -> &Cell<Color>;
+    fn buffered_ref(&self) -> &Cell<bool>;
+    fn weak_count_ref(&self) -> &Cell<usize>;
 
     unsafe fn iter_children_mut(&mut self) -> impl Iterator<Item = &mut Object>;
     unsafe fn drain_children(&mut self) -> impl Iterator<Item = Object>;
@@ -35,6 +46,57 @@ pub(crate) unsafe trait PmsInner {
         self.color_ref().set(color);
     }
 
+    /// Whether this object is in the root buffer of the batched mode. (See `Mode::Batched`)
+    ///
+    /// A buffered object is not deallocated even if its reference count becomes zero, since the
+    /// root buffer has a pointer to it. Its children are released, and the remaining empty object
+    /// (painted black, with zero reference count) is deallocated by `collect()`.
+    fn is_buffered(&self) -> bool {
+        self.buffered_ref().get()
+    }
+    fn set_buffered(&self, buffered: bool) {
+        self.buffered_ref().set(buffered);
+    }
+
+    /// The number of `WeakRef`s to this object. Like the root buffer, they keep the empty object
+    /// alive after its reference count becomes zero.
+    fn weak_count(&self) -> usize {
+        self.weak_count_ref().get()
+    }
+    fn inc_weak_count(&self) {
+        let weak_count = self.weak_count().wrapping_add(1);
+        self.weak_count_ref().set(weak_count);
+        if weak_count == 0 {
+            // Reference count overflow
+            std::process::abort()
+        }
+    }
+    fn dec_weak_count(&self) {
+        let weak_count = self.weak_count().wrapping_sub(1);
+        self.weak_count_ref().set(weak_count);
+        if weak_count == usize::MAX {
+            // Reference count underflow
+            std::process::abort()
+        }
+    }
+
+    /// Whether this object is released, that is, its reference count became zero but it is kept
+    /// by the root buffer or `WeakRef`s.
+    fn is_released(&self) -> bool {
+        self.ref_count() == 0 && self.color() == Color::Black
+    }
+
+    /// Whether this object has a finalizer which is not run yet. Only `Table`s can have it.
+    /// (See `finalize()`)
+    fn needs_finalization(&self) -> bool {
+        false
+    }
+    fn set_finalized(&self) {}
+    /// Whether this object is queued for finalization or already finalized.
+    fn is_finalized(&self) -> bool {
+        false
+    }
+
     fn ref_count(&self) -> usize {
         self.ref_count_ref().get()
     }
@@ -76,7 +138,9 @@ pub(crate) unsafe trait PmsObject<I: PmsInner> {
     fn custom_drop(this: &mut Self) {
         RecursiveDropGuard::begin_drop();
 
-        if this.inner().color() != Color::Black {
+        // Purple objects are the candidates buffered by the batched mode. (See `Mode::Batched`)
+        let color = this.inner().color();
+        if color != Color::Black && color != Color::Purple {
             unreachable!("drop() is called during mark and sweep");
         }
 
@@ -86,6 +150,14 @@ pub(crate) unsafe trait PmsObject<I: PmsInner> {
         // If the reference count is not zero, we can't drop this object.
         // But there is a possibility that this object is a part of a cycle, so we need to do `mark_and_sweep` from this object.
         if this.inner().ref_count() > 0 {
+            if is_batching() {
+                // Buffer this object instead of running `mark_and_sweep` now.
+                this.inner().paint(Color::Purple);
+                buffer_root(this.ptr());
+                RecursiveDropGuard::end_drop();
+                collect_if_threshold_reached();
+                return;
+            }
             unsafe {
                 this.inner().paint(Color::Purple); // Mark as suspicious of cycle reference
                 mark_and_sweep::run(this);
@@ -96,6 +168,11 @@ pub(crate) unsafe trait PmsObject<I: PmsInner> {
 
         // If the reference count is zero, we can drop this object.
         unsafe {
+            if queue_finalizer(this.ptr()) {
+                RecursiveDropGuard::end_drop();
+                return;
+            }
+
             // Mark this object as white as it is a candidate for collection.
             this.inner().paint(Color::White);
 
@@ -113,17 +190,14 @@ pub(crate) unsafe trait PmsObject<I: PmsInner> {
             // To collect objects for which circular references are suspected, we use `PurpleCollector`.
             // `PurpleCollector` is a struct that collects objects for which circular references are suspected and marks them as purple.
             struct PurpleCollector {
-                // In the `Object` enum, only `Array` and `Table` are `PmsObject`.
-                // If you add other `PmsObject` variants in the future, you will need to add them here as well.
-                array: Vec<NonNull<ArrayInner>>,
-                table: Vec<NonNull<TableInner>>,
+                // The purple objects, which are buffered in the same way as the root buffer of the batched mode.
+                // (See `Mode::Batched`)
+                // They must be buffered, since one of them may be freed while tracing or collecting the others.
+                roots: Vec<(Kind, NonNull<()>)>,
             }
             impl PurpleCollector {
                 fn new() -> Self {
-                    Self {
-                        array: Vec::new(),
-                        table: Vec::new(),
-                    }
+                    Self { roots: Vec::new() }
                 }
                 /// Collect purple objects (suspected of circular references) that can be traced from the object pointed to by `ptr`.
                 /// White objects found during tracing are applied `PmsObject::deallocate_inner()` recursively.
@@ -138,25 +212,14 @@ pub(crate) unsafe trait PmsObject<I: PmsInner> {
                         match next {
                             // If the child is `PmsObject`...
                             // (`_check_suspicious()` is just a function to cut out common processes, and I think it is not a good name.)
-                            Object::Array(array) => {
-                                if let Some(array) = self._check_suspicious(array) {
-                                    self.array.push(array.ptr());
-                                }
-                            }
-                            Object::Table(table) => {
-                                if let Some(table) = self._check_suspicious(table) {
-                                    self.table.push(table.ptr());
-                                }
-                            }
+                            Object::Array(array) => self._check_suspicious(array),
+                            Object::Table(table) => self._check_suspicious(table),
                             _ => {}
                         }
                     }
                 }
                 /// This function must be called only from `collect()`.
-                unsafe fn _check_suspicious<'a, I: PmsInner + 'a, T: PmsObject<I>>(
-                    &mut self,
-                    item: &'a mut T,
-                ) -> Option<&'a mut T> {
+                unsafe fn _check_suspicious<I: PmsInner, T: PmsObject<I>>(&mut self, item: &mut T) {
                     if cfg!(debug_assertions) {
                         let color = item.inner().color();
                         assert!(
@@ -169,46 +232,242 @@ pub(crate) unsafe trait PmsObject<I: PmsInner> {
                     item.inner().dec_ref_count();
 
                     if item.inner().ref_count() == 0 {
+                        if queue_finalizer(item.ptr()) {
+                            return;
+                        }
                         item.inner().paint(Color::White);
                         self.collect(item.ptr());
-                        deallocate_inner(item);
-                        None
+                        deallocate_or_release_inner(item);
                     } else {
                         // To avoid double collection, we need to check whether its color is purple.
                         if item.inner().color() == Color::Purple {
-                            return None;
+                            return;
                         }
                         item.inner().paint(Color::Purple);
-                        Some(item)
+                        if is_batching() {
+                            buffer_root(item.ptr());
+                        } else {
+                            item.inner().set_buffered(true);
+                            self.roots.push((I::KIND, item.ptr().cast()));
+                        }
                     }
                 }
-                unsafe fn finish(self) -> (Vec<NonNull<ArrayInner>>, Vec<NonNull<TableInner>>) {
-                    let Self {
-                        mut array,
-                        mut table,
-                    } = self;
-                    array.retain_mut(|x| x.as_ref().color() == Color::Purple);
-                    table.retain_mut(|x| x.as_ref().color() == Color::Purple);
-                    (array, table)
-                }
             }
 
-            // Collect purple objects and apply `mark_and_sweep::run()` for them.
+            // Collect purple objects and apply mark and sweep for them.
             let mut purple_collector = PurpleCollector::new();
             purple_collector.collect(this.ptr());
-            let (purple_array_ptrs, purple_table_ptrs) = purple_collector.finish();
 
-            deallocate_inner(this);
+            deallocate_or_release_inner(this);
 
-            for array_ptr in purple_array_ptrs {
-                mark_and_sweep::run_with_inner_ptr::<_, Array>(array_ptr);
-            }
-            for table_ptr in purple_table_ptrs {
-                mark_and_sweep::run_with_inner_ptr::<_, Table>(table_ptr);
+            if !purple_collector.roots.is_empty() {
+                mark_and_sweep::run_roots(purple_collector.roots);
             }
         }
         RecursiveDropGuard::end_drop();
+        collect_if_threshold_reached();
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Kind {
+    Array,
+    Table,
+}
+
+/// How the cycle collector handles the objects suspected of being a part of a cycle, that is, the
+/// `Array`/`Table` whose reference count is decremented but not zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Runs mark and sweep from the suspected object on every such drop. This is the default.
+    Immediate,
+    /// Buffers the suspected objects (roots) and runs mark and sweep for all of them at once when
+    /// `threshold` roots are buffered or [`collect()`] is called. Garbage cycles live until then,
+    /// while the other objects are freed as soon as their reference count becomes zero.
+    ///
+    /// This is the synchronous cycle collection of Bacon and Rajan. Since a root is traced once
+    /// per collection instead of once per drop, dropping shared references repeatedly (e.g. in a
+    /// loop) costs much less than [`Mode::Immediate`].
+    Batched { threshold: usize },
+}
+
+impl Mode {
+    /// The threshold recommended for [`Mode::Batched`].
+    pub const DEFAULT_THRESHOLD: usize = 1000;
+}
+
+/// The statistics of the objects and the cycle collector of the current thread.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// The number of `Array`s not yet freed.
+    pub live_arrays: usize,
+    /// The number of `Table`s not yet freed.
+    pub live_tables: usize,
+    /// The number of mark and sweep runs. A collection of [`Mode::Batched`] counts as one run.
+    pub runs: usize,
+    /// The number of objects freed by mark and sweep, i.e. the objects which were in cycles.
+    pub collected: usize,
+    /// The time spent in mark and sweep.
+    pub time: Duration,
+    /// The number of roots buffered by [`Mode::Batched`].
+    pub pending: usize,
+}
+
+struct State {
+    mode: Cell<Mode>,
+    stats: Cell<Stats>,
+    roots: RefCell<Vec<(Kind, NonNull<()>)>>,
+    finalizable: RefCell<VecDeque<Table>>,
+}
+
+// All the objects are confined to the thread which created them, so is the state of the collector.
+thread_local! {
+    static STATE: State = const {
+        State {
+            mode: Cell::new(Mode::Immediate),
+            stats: Cell::new(Stats {
+                live_arrays: 0,
+                live_tables: 0,
+                runs: 0,
+                collected: 0,
+                time: Duration::ZERO,
+                pending: 0,
+            }),
+            roots: RefCell::new(Vec::new()),
+            finalizable: RefCell::new(VecDeque::new()),
+        }
+    };
+
+    /// Whether `State::finalizable` is not empty. This is apart from `STATE`, so that
+    /// [`has_finalizable()`], which the VM polls for each instruction, is a plain load: a
+    /// thread-local without a destructor needs no check of its initialization.
+    static HAS_FINALIZABLE: Cell<bool> = const { Cell::new(false) };
+}
+
+fn update_stats(f: impl FnOnce(&mut Stats)) {
+    STATE.with(|state| {
+        let mut stats = state.stats.get();
+        f(&mut stats);
+        state.stats.set(stats);
+    });
+}
+
+pub(crate) fn register_allocation(kind: Kind) {
+    update_stats(|stats| match kind {
+        Kind::Array => stats.live_arrays += 1,
+        Kind::Table => stats.live_tables += 1,
+    });
+}
+
+fn is_batching() -> bool {
+    STATE.with(|state| matches!(state.mode.get(), Mode::Batched { .. }))
+}
+
+/// Adds the object pointed to by `ptr` to the root buffer unless it is already buffered.
+fn buffer_root<I: PmsInner>(ptr: NonNull<I>) {
+    let inner = unsafe { ptr.as_ref() };
+    if inner.is_buffered() {
+        return;
+    }
+    inner.set_buffered(true);
+    STATE.with(|state| state.roots.borrow_mut().push((I::KIND, ptr.cast())));
+}
+
+fn collect_if_threshold_reached() {
+    let reached = STATE.with(|state| match state.mode.get() {
+        Mode::Batched { threshold } => state.roots.borrow().len() >= threshold.max(1),
+        Mode::Immediate => false,
+    });
+    if reached {
+        collect();
+    }
+}
+
+/// Returns the current mode of the cycle collector.
+pub fn mode() -> Mode {
+    STATE.with(|state| state.mode.get())
+}
+
+/// Changes the mode of the cycle collector. The buffered roots are collected when switching to
+/// [`Mode::Immediate`].
+pub fn set_mode(mode: Mode) {
+    STATE.with(|state| state.mode.set(mode));
+    if mode == Mode::Immediate {
+        collect();
+    }
+}
+
+/// Returns the statistics of the current thread.
+pub fn stats() -> Stats {
+    STATE.with(|state| {
+        let mut stats = state.stats.get();
+        stats.pending = state.roots.borrow().len();
+        stats
+    })
+}
+
+/// Resurrects the object pointed to by `ptr`, whose reference count became zero or which is found
+/// to be garbage, and queues it for [`take_finalizable()`] if it needs finalization. Returns whether
+/// it is queued.
+unsafe fn queue_finalizer<I: PmsInner>(ptr: NonNull<I>) -> bool {
+    let inner = ptr.as_ref();
+    if !inner.needs_finalization() {
+        return false;
+    }
+    debug_assert_eq!(I::KIND, Kind::Table);
+    inner.set_finalized();
+    inner.paint(Color::Black);
+    // The queue holds a reference.
+    inner.inc_ref_count();
+    let table = Table::from_inner(ptr.cast());
+    STATE.with(|state| state.finalizable.borrow_mut().push_back(table));
+    HAS_FINALIZABLE.set(true);
+    true
+}
+
+/// Takes a table whose finalizer (`__gc` method) should be run now.
+///
+/// Instead of freeing a table with `__gc` method, the collector keeps it alive (with everything
+/// reachable from it) and queues it here. The host calls its `__gc` method with the table, and
+/// drops it after that. The rules are:
+///
+/// - The tables are queued in the order they become garbage. A table is queued before the
+///   objects it refers to, since they are kept alive by the queued table. The tables in the same
+///   garbage cycle are queued at once, in an unspecified order.
+/// - The finalizer of a table is run at most once. If the finalizer stores the table somewhere
+///   (resurrection), the table lives on, and it is freed without the finalizer next time.
+/// - A garbage cycle with a finalizer is freed by the collection after its finalizers are run.
+///
+/// The queued tables are never freed unless they are taken.
+pub fn take_finalizable() -> Option<Table> {
+    STATE.with(|state| {
+        let mut finalizable = state.finalizable.borrow_mut();
+        let table = finalizable.pop_front();
+        if finalizable.is_empty() {
+            // Release the buffer as finalizers are rare.
+            *finalizable = VecDeque::new();
+            HAS_FINALIZABLE.set(false);
+        }
+        table
+    })
+}
+
+/// Returns `true` if there is a table taken by [`take_finalizable()`].
+#[inline]
+pub fn has_finalizable() -> bool {
+    HAS_FINALIZABLE.get()
+}
+
+/// Runs mark and sweep for the roots buffered by [`Mode::Batched`], and returns the number of the
+/// objects freed by it. Does nothing in [`Mode::Immediate`], as nothing is buffered.
+pub fn collect() -> usize {
+    let roots = STATE.with(|state| mem::take(&mut *state.roots.borrow_mut()));
+    if roots.is_empty() {
+        return 0;
     }
+    let before = stats().collected;
+    unsafe { mark_and_sweep::run_roots(roots) };
+    stats().collected - before
 }
 
 thread_local! {
@@ -230,6 +489,25 @@ impl RecursiveDropGuard {
     }
 }
 
+/// Calls `$body` with `$object` bound to `&mut Array` or `&mut Table` pointed to by `$ptr`,
+/// without touching its reference count.
+macro_rules! with_pms_object {
+    ($kind:expr, $ptr:expr, |$object:ident| $body:expr) => {
+        match $kind {
+            Kind::Array => {
+                let mut object = mem::ManuallyDrop::new(Array::from_inner($ptr.cast()));
+                let $object: &mut Array = &mut object;
+                $body
+            }
+            Kind::Table => {
+                let mut object = mem::ManuallyDrop::new(Table::from_inner($ptr.cast()));
+                let $object: &mut Table = &mut object;
+                $body
+            }
+        }
+    };
+}
+
 unsafe fn deallocate_inner<I: PmsInner, T: PmsObject<I> + ?Sized>(this: &mut T) {
     debug_assert_ptr_is_not_freed!(this.ptr());
     debug_assert_eq!(this.inner().ref_count(), 0);
@@ -242,7 +520,19 @@ unsafe fn deallocate_inner<I: PmsInner, T: PmsObject<I> + ?Sized>(this: &mut T)
     } else {
         this.ptr()
     };
+    update_stats(|stats| match I::KIND {
+        Kind::Array => stats.live_arrays -= 1,
+        Kind::Table => stats.live_tables -= 1,
+    });
     let mut inner = ptr::read(ptr.as_ptr());
+    release_children(&mut inner);
+    drop(inner);
+    alloc::dealloc(ptr.as_ptr().cast(), Layout::for_value(ptr.as_ref()));
+}
+
+/// Removes the children of `inner`. The reference counts of `PmsObject` children must be already
+/// decremented, so they are just forgotten.
+unsafe fn release_children<I: PmsInner>(inner: &mut I) {
     for next in inner.drain_children() {
         match next {
             Object::Int(_) => {}          // No need to drop for `i64`  (Copy type)
@@ -256,6 +546,9 @@ unsafe fn deallocate_inner<I: PmsInner, T: PmsObject<I> + ?Sized>(this: &mut T)
             Object::Function(next) => {
                 drop(next);
             }
+            Object::WeakRef(next) => {
+                drop(next);
+            }
             Object::Array(next) => {
                 mem::forget(next);
             }
@@ -264,26 +557,101 @@ unsafe fn deallocate_inner<I: PmsInner, T: PmsObject<I> + ?Sized>(this: &mut T)
             }
         }
     }
-    drop(inner);
-    alloc::dealloc(ptr.as_ptr().cast(), Layout::for_value(ptr.as_ref()));
 }
 
-mod mark_and_sweep {
-    use super::*;
+/// Deallocates `this` whose reference count is zero. If `this` is buffered or has `WeakRef`s, only
+/// its children are released, and the empty object is deallocated later.
+///
+/// `this` must not be painted black, so that `drop_weak()` called while releasing the children
+/// does not deallocate it.
+unsafe fn deallocate_or_release_inner<I: PmsInner, T: PmsObject<I> + ?Sized>(this: &mut T) {
+    debug_assert_ne!(this.inner().color(), Color::Black);
+    release_children(this.inner_mut());
+    this.inner().paint(Color::Black);
+    if !this.inner().is_buffered() && this.inner().weak_count() == 0 {
+        deallocate_inner(this);
+    }
+}
 
-    pub(super) unsafe fn run_with_inner_ptr<I: PmsInner, T: PmsObject<I>>(ptr: NonNull<I>) {
-        let mut object: T = PmsObject::from_inner(ptr);
-        run(&mut object);
-        mem::forget(object);
+/// Decrements the weak count of the object pointed to by `ptr`, and deallocates it if it is
+/// released and nothing keeps it anymore.
+pub(crate) unsafe fn drop_weak<I: PmsInner, T: PmsObject<I>>(ptr: NonNull<I>) {
+    let inner = ptr.as_ref();
+    inner.dec_weak_count();
+    if inner.weak_count() == 0 && inner.is_released() && !inner.is_buffered() {
+        let mut object = mem::ManuallyDrop::new(T::from_inner(ptr));
+        deallocate_inner(&mut *object);
     }
+}
+
+mod mark_and_sweep {
+    use super::*;
 
     pub(super) unsafe fn run<I: PmsInner, T: PmsObject<I> + ?Sized>(item: &mut T) {
         if item.inner().color() != Color::Purple {
             return;
         }
+        let start = Instant::now();
         paint_gray(item);
         scan_gray(item);
-        collect_white(item);
+        let mut white = Vec::new();
+        collect_white(item, &mut white);
+        sweep_white(white);
+        update_stats(|stats| {
+            stats.runs += 1;
+            stats.time += start.elapsed();
+        });
+    }
+
+    /// Runs mark and sweep for all the `roots` at once. The roots must be taken from the root
+    /// buffer, i.e. buffered.
+    pub(super) unsafe fn run_roots(roots: Vec<(Kind, NonNull<()>)>) {
+        let start = Instant::now();
+
+        // Tentatively remove the internal references of the subgraphs traced from the roots.
+        // The roots traced from another root are already gray, and are not needed to be scanned
+        // separately.
+        let mut marked = Vec::with_capacity(roots.len());
+        for (kind, ptr) in roots {
+            let keep = with_pms_object!(kind, ptr, |object| mark_root(object));
+            if keep {
+                marked.push((kind, ptr));
+            }
+        }
+        for &(kind, ptr) in marked.iter() {
+            with_pms_object!(kind, ptr, |object| scan_gray(object));
+        }
+        let mut white = Vec::new();
+        for &(kind, ptr) in marked.iter() {
+            with_pms_object!(kind, ptr, |object| collect_white(object, &mut white));
+        }
+        sweep_white(white);
+
+        update_stats(|stats| {
+            stats.runs += 1;
+            stats.time += start.elapsed();
+        });
+    }
+
+    /// Removes `item` from the root buffer, and paints it gray if it may be a part of a garbage
+    /// cycle. Returns whether `item` is painted gray by this call.
+    unsafe fn mark_root<I: PmsInner, T: PmsObject<I> + ?Sized>(item: &mut T) -> bool {
+        item.inner().set_buffered(false);
+        match item.inner().color() {
+            Color::Purple if item.inner().ref_count() > 0 => {
+                paint_gray(item);
+                true
+            }
+            // The reference count became zero while it was buffered, and its children are
+            // already released. (See `deallocate_or_release_inner()`)
+            Color::Black if item.inner().ref_count() == 0 => {
+                if item.inner().weak_count() == 0 {
+                    deallocate_inner(item);
+                }
+                false
+            }
+            _ => false,
+        }
     }
 
     /// Tentatively removing. (試験削除)
@@ -365,7 +733,14 @@ mod mark_and_sweep {
         }
     }
 
-    unsafe fn collect_white<I: PmsInner, T: PmsObject<I> + ?Sized>(item: &mut T) {
+    /// Paints the white objects traced from `item` black, and pushes them to `white`.
+    ///
+    /// They are deallocated after all of them are found by `deallocate_white()`, since a white
+    /// object can be traced from more than one white object.
+    unsafe fn collect_white<I: PmsInner, T: PmsObject<I> + ?Sized>(
+        item: &mut T,
+        white: &mut Vec<(Kind, NonNull<()>)>,
+    ) {
         // We want to collect only white objects.
         if item.inner().color() != Color::White {
             return;
@@ -376,11 +751,53 @@ mod mark_and_sweep {
         item.inner().paint(Color::Black);
         for next in item.inner_mut().iter_children_mut() {
             match next {
-                Object::Array(array) => collect_white(array),
-                Object::Table(table) => collect_white(table),
+                Object::Array(array) => collect_white(array, white),
+                Object::Table(table) => collect_white(table, white),
                 _ => {}
             }
         }
-        deallocate_inner(item);
+        white.push((I::KIND, item.ptr().cast()));
+    }
+
+    /// Deallocates the white objects found by `collect_white()`, except the ones reachable from the
+    /// objects which need finalization. They are restored and the latter are queued for
+    /// finalization. (See `queue_finalizer()`)
+    unsafe fn sweep_white(mut white: Vec<(Kind, NonNull<()>)>) {
+        let needs_finalization = |&(kind, ptr): &(Kind, NonNull<()>)| {
+            with_pms_object!(kind, ptr, |object| object.inner().needs_finalization())
+        };
+        let finalizable = white
+            .iter()
+            .copied()
+            .filter(needs_finalization)
+            .collect::<Vec<_>>();
+        if !finalizable.is_empty() {
+            for &(kind, ptr) in white.iter() {
+                with_pms_object!(kind, ptr, |object| object.inner().paint(Color::White));
+            }
+            // Restore the reference counts decremented by `paint_gray()`, and paint them black.
+            for &(kind, ptr) in finalizable.iter() {
+                with_pms_object!(kind, ptr, |object| paint_black(object));
+            }
+            for (kind, ptr) in finalizable {
+                with_pms_object!(kind, ptr, |object| queue_finalizer(object.ptr()));
+            }
+            white.retain(|&(kind, ptr)| {
+                with_pms_object!(kind, ptr, |object| object.inner().color() == Color::White)
+            });
+        }
+        deallocate_white(white);
+    }
+
+    unsafe fn deallocate_white(white: Vec<(Kind, NonNull<()>)>) {
+        update_stats(|stats| stats.collected += white.len());
+        // Repaint them white, so that dropping a `WeakRef` to one of them while releasing another
+        // one does not deallocate it. (See `deallocate_or_release_inner()`)
+        for &(kind, ptr) in white.iter() {
+            with_pms_object!(kind, ptr, |object| object.inner().paint(Color::White));
+        }
+        for (kind, ptr) in white {
+            with_pms_object!(kind, ptr, |object| deallocate_or_release_inner(object));
+        }
     }
 }
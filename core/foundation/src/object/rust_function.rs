@@ -18,6 +18,7 @@ enum Inner {
     #[allow(unused, non_camel_case_types)]
     __dummy,
     Data(
+        u8,
         u8,
         fn(Box<dyn ExactSizeIterator<Item = Object>>) -> Result<Object>,
     ),
@@ -28,21 +29,53 @@ impl RustFunction {
         param_len: u8,
         func: fn(Box<dyn ExactSizeIterator<Item = Object>>) -> Result<Object>,
     ) -> Self {
-        RustFunction(Inner::Data(param_len, func))
+        RustFunction(Inner::Data(param_len, 0, func))
+    }
+
+    /// Creates a function that takes `param_len` required parameters followed by `optional_len`
+    /// optional ones. The omitted arguments are not passed to `func`, so it can tell them by the
+    /// length of the arguments.
+    pub const fn with_optional(
+        param_len: u8,
+        optional_len: u8,
+        func: fn(Box<dyn ExactSizeIterator<Item = Object>>) -> Result<Object>,
+    ) -> Self {
+        RustFunction(Inner::Data(param_len, optional_len, func))
     }
 
+    /// The number of the required parameters.
     pub fn param_len(&self) -> u8 {
         self.data().0
     }
 
+    /// The number of the optional parameters, which follow the required ones.
+    pub fn optional_param_len(&self) -> u8 {
+        self.data().1
+    }
+
+    /// Returns `true` if the function can be called with `arg_len` arguments.
+    pub fn accepts(&self, arg_len: usize) -> bool {
+        let (param_len, optional_len, _) = self.data();
+        (param_len as usize..=param_len as usize + optional_len as usize).contains(&arg_len)
+    }
+
     pub fn call(&self, args: Box<dyn ExactSizeIterator<Item = Object>>) -> Result<Object> {
-        let (param_len, func) = self.data();
-        if args.len() != param_len as usize {
-            Err(anyhow!(
-                "Invalid argument length: expected {}, got {}",
-                param_len,
-                args.len()
-            ))
+        let (param_len, optional_len, func) = self.data();
+        if !self.accepts(args.len()) {
+            if optional_len == 0 {
+                Err(anyhow!(
+                    "Invalid argument length: expected {}, got {}",
+                    param_len,
+                    args.len()
+                ))
+            } else {
+                Err(anyhow!(
+                    "Invalid argument length: expected {} to {}, got {}",
+                    param_len,
+                    param_len + optional_len,
+                    args.len()
+                ))
+            }
         } else {
             func(args)
         }
@@ -52,12 +85,13 @@ impl RustFunction {
     fn data(
         &self,
     ) -> (
+        u8,
         u8,
         fn(Box<dyn ExactSizeIterator<Item = Object>>) -> Result<Object>,
     ) {
         unsafe {
             match self.0 {
-                Inner::Data(param_len, func) => (param_len, func),
+                Inner::Data(param_len, optional_len, func) => (param_len, optional_len, func),
                 _ => core::hint::unreachable_unchecked(),
             }
         }
@@ -66,9 +100,10 @@ impl RustFunction {
 
 impl fmt::Debug for RustFunction {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let (param_len, func) = self.data();
+        let (param_len, optional_len, func) = self.data();
         f.debug_struct("RustFunction")
             .field("param_len", &param_len)
+            .field("optional_len", &optional_len)
             .field("func", &func)
             .finish()
     }
@@ -1,7 +1,11 @@
 use super::*;
 use crate::collections::*;
-use compact_str::CompactString;
-use core::{borrow::Borrow, cell::Cell, hash::Hash, ptr::NonNull};
+use core::{
+    borrow::Borrow,
+    cell::{Cell, RefCell},
+    hash::Hash,
+    ptr::NonNull,
+};
 
 pub struct Table {
     ptr: NonNull<Inner>,
@@ -19,12 +23,23 @@ unsafe impl PmsObject<Inner> for Table {
 
 pub struct Inner {
     map: LazyHashMap<UString, Object>,
-    methods: SortedLinearMap<CompactString, TableMethod>,
+    methods: SortedLinearMap<MethodName, TableMethod>,
+    /// (See `Table::version()`)
+    version: u64,
     ref_count: Cell<usize>,
     color: Cell<Color>,
+    buffered: Cell<bool>,
+    weak_count: Cell<usize>,
+    weak_values: bool,
+    /// The length of `map` at which the freed values are removed from a table with weak values.
+    /// (See `Table::insert()`)
+    prune_len: usize,
+    finalized: Cell<bool>,
 }
 
 unsafe impl PmsInner for Inner {
+    const KIND: Kind = Kind::Table;
+
     fn ref_count_ref(&self) -> &Cell<usize> {
         &self.ref_count
     }
@@ -33,6 +48,26 @@ unsafe impl PmsInner for Inner {
         &self.color
     }
 
+    fn buffered_ref(&self) -> &Cell<bool> {
+        &self.buffered
+    }
+
+    fn weak_count_ref(&self) -> &Cell<usize> {
+        &self.weak_count
+    }
+
+    fn needs_finalization(&self) -> bool {
+        !self.finalized.get() && self.methods.contains_key("__gc")
+    }
+
+    fn set_finalized(&self) {
+        self.finalized.set(true);
+    }
+
+    fn is_finalized(&self) -> bool {
+        self.finalized.get()
+    }
+
     unsafe fn iter_children_mut(&mut self) -> impl Iterator<Item = &mut Object> {
         self.map.iter_mut().map(|(_, v)| v)
     }
@@ -66,12 +101,50 @@ impl Table {
         Table::with_map(map)
     }
 
+    /// Creates a table with weak values, which holds the `Array`/`Table` values by `WeakRef`s so
+    /// that they do not keep the values alive.
+    pub fn with_weak_values() -> Self {
+        let mut table = Table::new();
+        unsafe { table.inner_mut().weak_values = true };
+        table
+    }
+
+    pub fn has_weak_values(&self) -> bool {
+        self.inner().weak_values
+    }
+
+    /// Returns the version of the table, which changes whenever a field or a method is added or
+    /// removed, but not when the value of an existing one is replaced.
+    ///
+    /// The versions are unique among all the tables, so a version identifies the table as well as
+    /// its set of keys.
+    pub fn version(&self) -> u64 {
+        self.inner().version
+    }
+
+    /// Returns the number of the fields. The freed values of a table with weak values are not
+    /// counted.
     pub fn len(&self) -> usize {
-        self.inner().map.len()
+        let map = &self.inner().map;
+        if self.has_weak_values() {
+            map.iter().filter(|(_, value)| is_present(value)).count()
+        } else {
+            map.len()
+        }
     }
 
     pub fn is_empty(&self) -> bool {
-        self.inner().map.is_empty()
+        let map = &self.inner().map;
+        if self.has_weak_values() {
+            !map.iter().any(|(_, value)| is_present(value))
+        } else {
+            map.is_empty()
+        }
+    }
+
+    /// Returns `true` if the two tables are the same object, not only equal.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        self.ptr == other.ptr
     }
 
     pub fn get<Q>(&self, key: &Q) -> Option<&Object>
@@ -82,8 +155,39 @@ impl Table {
         self.inner().map.get(key)
     }
 
+    /// Returns the value for `key` like `get()`, but the `WeakRef` values of a table with weak
+    /// values are upgraded. `None` is returned if the value is already freed.
+    pub fn get_upgraded<Q>(&self, key: &Q) -> Option<Object>
+    where
+        UString: Borrow<Q>,
+        Q: Hash + Ord + ?Sized,
+    {
+        match self.get(key)? {
+            Object::WeakRef(weak) if self.has_weak_values() => weak.upgrade(),
+            value => Some(value.clone()),
+        }
+    }
+
+    /// Inserts `value` for `key`. If this table has weak values, `Array`/`Table` values are
+    /// stored as `WeakRef`s, and the freed values are removed as the table grows.
     pub fn insert<T: Into<Object>>(&mut self, key: UString, value: T) -> Option<Object> {
-        unsafe { self.inner_mut().map.insert(key, value.into()) }
+        let value = match value.into() {
+            Object::Array(array) if self.has_weak_values() => Object::WeakRef((&array).into()),
+            Object::Table(table) if self.has_weak_values() => Object::WeakRef((&table).into()),
+            value => value,
+        };
+        if self.has_weak_values() && self.inner().map.len() >= self.inner().prune_len {
+            self.prune();
+        }
+        let inner = unsafe { self.inner_mut() };
+        let old = match key.interned_hash() {
+            Some(hash) => inner.map.insert_hashed(hash, key, value),
+            None => inner.map.insert(key, value),
+        };
+        if old.is_none() {
+            inner.version = next_version();
+        }
+        old
     }
 
     pub fn remove<Q>(&mut self, key: &Q) -> Option<Object>
@@ -91,47 +195,143 @@ impl Table {
         UString: Borrow<Q>,
         Q: Hash + Ord + ?Sized,
     {
-        unsafe { self.inner_mut().map.remove(key) }
+        let inner = unsafe { self.inner_mut() };
+        let old = inner.map.remove(key);
+        if old.is_some() {
+            inner.version = next_version();
+        }
+        old
     }
 
     pub fn clear(&mut self) {
-        unsafe {
-            self.inner_mut().map.clear();
-        }
+        let inner = unsafe { self.inner_mut() };
+        inner.map.clear();
+        inner.version = next_version();
     }
 
+    /// Returns `true` if the table has `key`. The freed values of a table with weak values are
+    /// regarded as absent.
     pub fn contains_key<Q>(&self, key: &Q) -> bool
     where
         UString: Borrow<Q>,
         Q: Hash + Ord + ?Sized,
     {
-        self.inner().map.contains_key(key)
+        self.get(key)
+            .is_some_and(|value| !self.has_weak_values() || is_present(value))
     }
 
     /// # Safety
     /// TODO
+    ///
+    /// The freed values of a table with weak values are included. (See `get_upgraded()`)
     pub unsafe fn iter(&self) -> lazy_hash_map::Iter<UString, Object> {
         self.inner().map.iter()
     }
 
     pub fn get_method<Q>(&self, key: &Q) -> Option<&TableMethod>
     where
-        CompactString: Borrow<Q>,
+        MethodName: Borrow<Q>,
         Q: Hash + Ord + ?Sized,
     {
         self.inner().methods.get(key)
     }
 
-    pub fn set_method<T: Into<TableMethod>>(&mut self, key: CompactString, value: T) {
-        unsafe { self.inner_mut().methods.insert(key, value.into()) };
+    /// Returns the method for `key` like `get_method()`, but compares the names by their identity.
+    /// (See `MethodName`)
+    pub fn get_method_by_name(&self, key: &MethodName) -> Option<&TableMethod> {
+        self.inner().methods.find(key)
+    }
+
+    pub fn set_method<T: Into<TableMethod>>(&mut self, key: MethodName, value: T) {
+        let inner = unsafe { self.inner_mut() };
+        if inner.methods.insert(key, value.into()).is_none() {
+            inner.version = next_version();
+        }
+    }
+
+    /// Returns the value for `key` like `get_upgraded()`, but the lookup is skipped if `cache`
+    /// remembers the same key of this table of the current version.
+    pub fn get_cached(&self, key: &UString, cache: &FieldCache) -> Option<Object> {
+        let value = match cache.lookup(self.version(), key.as_str()) {
+            // SAFETY: The values are not moved while the version is not changed.
+            Some(slot) => unsafe { slot.map(|slot| slot.as_ref()) },
+            None => {
+                let value = match key.interned_hash() {
+                    Some(hash) => self.inner().map.get_hashed(hash, key),
+                    None => self.get(key),
+                };
+                cache.store(self.version(), key.clone(), value);
+                value
+            }
+        };
+        match value? {
+            Object::WeakRef(weak) if self.has_weak_values() => weak.upgrade(),
+            value => Some(value.clone()),
+        }
+    }
+
+    /// Returns the method for `key` like `get_method()`, using `cache`. (See `get_cached()`)
+    pub fn get_method_cached(&self, key: &str, cache: &MethodCache) -> Option<&TableMethod> {
+        match cache.lookup(self.version(), key) {
+            // SAFETY: The methods are not moved while the version is not changed.
+            Some(slot) => unsafe { slot.map(|slot| slot.as_ref()) },
+            None => {
+                let method = self.get_method(key);
+                cache.store(self.version(), key, method);
+                method
+            }
+        }
+    }
+
+    /// Returns the method for `key` like `get_method_by_name()`, using `cache`.
+    pub fn get_method_by_name_cached(
+        &self,
+        key: &MethodName,
+        cache: &MethodCache,
+    ) -> Option<&TableMethod> {
+        match cache.lookup(self.version(), key.as_str()) {
+            // SAFETY: The methods are not moved while the version is not changed.
+            Some(slot) => unsafe { slot.map(|slot| slot.as_ref()) },
+            None => {
+                let method = self.get_method_by_name(key);
+                cache.store(self.version(), key.as_ustring().clone(), method);
+                method
+            }
+        }
+    }
+
+    /// Removes the freed values of a table with weak values. Since the `WeakRef`s of the values
+    /// keep their allocations, they are removed once the table doubles, not only when read.
+    fn prune(&mut self) {
+        let inner = unsafe { self.inner_mut() };
+        let dead = inner
+            .map
+            .iter()
+            .filter(|(_, value)| !is_present(value))
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+        for key in dead.iter() {
+            inner.map.remove(key);
+        }
+        if !dead.is_empty() {
+            inner.version = next_version();
+        }
+        inner.prune_len = (inner.map.len() * 2).max(MIN_PRUNE_LEN);
     }
 
     fn with_map(map: LazyHashMap<UString, Object>) -> Self {
+        register_allocation(Kind::Table);
         let ptr = Box::leak(Box::new(Inner {
             map,
             methods: SortedLinearMap::new(),
+            version: next_version(),
             ref_count: Cell::new(1),
             color: Cell::new(Color::Black),
+            buffered: Cell::new(false),
+            weak_count: Cell::new(0),
+            weak_values: false,
+            prune_len: MIN_PRUNE_LEN,
+            finalized: Cell::new(false),
         }));
         Table {
             ptr: NonNull::from(ptr),
@@ -139,6 +339,89 @@ impl Table {
     }
 }
 
+/// The smallest `Inner::prune_len`.
+const MIN_PRUNE_LEN: usize = 8;
+
+/// Returns `false` if `value` of a table with weak values is already freed, that is, it is regarded
+/// as absent. (A `WeakRef` stored in a table without weak values is a value by itself.)
+fn is_present(value: &Object) -> bool {
+    !matches!(value, Object::WeakRef(weak) if !weak.is_alive())
+}
+
+thread_local! {
+    static NEXT_VERSION: Cell<u64> = const { Cell::new(1) };
+}
+
+/// Returns a new version of tables. (See `Table::version()`)
+fn next_version() -> u64 {
+    NEXT_VERSION.with(|next| {
+        let version = next.get();
+        next.set(version + 1);
+        version
+    })
+}
+
+/// The inline cache for looking up a key of tables, which remembers where the value of the key
+/// is in a table of a version. (See `Table::get_cached()`)
+pub struct TableCache<T> {
+    /// The version of the table, or 0 if nothing is cached.
+    version: Cell<u64>,
+    key: RefCell<UString>,
+    /// The value of `key` in the table, or `None` if the table does not have `key`.
+    slot: Cell<Option<NonNull<T>>>,
+}
+
+pub type FieldCache = TableCache<Object>;
+pub type MethodCache = TableCache<TableMethod>;
+
+impl<T> TableCache<T> {
+    pub const fn new() -> Self {
+        Self {
+            version: Cell::new(0),
+            key: RefCell::new(UString::new()),
+            slot: Cell::new(None),
+        }
+    }
+
+    /// Returns the cached slot if `key` of the table of `version` is cached.
+    fn lookup(&self, version: u64, key: &str) -> Option<Option<NonNull<T>>> {
+        if self.version.get() != version {
+            return None;
+        }
+        let cached = self.key.borrow();
+        // The same string, e.g. an interned one, is found without comparing the contents.
+        if core::ptr::eq(cached.as_str(), key) || cached.as_str() == key {
+            Some(self.slot.get())
+        } else {
+            None
+        }
+    }
+
+    fn store(&self, version: u64, key: impl AsRef<str> + Into<UString>, value: Option<&T>) {
+        self.version.set(version);
+        if self.key.borrow().as_str() != key.as_ref() {
+            *self.key.borrow_mut() = key.into();
+        }
+        self.slot.set(value.map(NonNull::from));
+    }
+}
+
+impl<T> Default for TableCache<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for TableCache<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TableCache")
+            .field("version", &self.version.get())
+            .field("key", &self.key.borrow())
+            .field("slot", &self.slot.get())
+            .finish()
+    }
+}
+
 impl Default for Table {
     fn default() -> Self {
         Self::new()
@@ -180,17 +463,28 @@ impl fmt::Debug for Table {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut dbg = f.debug_map();
         for (key, value) in self.inner().map.iter() {
-            dbg.key(key).value(match value {
-                Object::Int(x) => x,
-                Object::Float(x) => x,
-                Object::String(x) => x,
-                Object::Bool(x) => x,
-                Object::Nil => &"nil",
-                Object::Function(_x) => todo!(),
-                Object::Array(_x) => &"Array", // TODO: なんかいい感じにする
-                Object::Table(_x) => &"Table",
-                Object::RustFunction(x) => x,
-            });
+            // The values of a table with weak values are shown as the referents, and the freed
+            // ones are omitted.
+            let upgraded = match value {
+                Object::WeakRef(weak) if self.has_weak_values() => match weak.upgrade() {
+                    Some(upgraded) => Some(upgraded),
+                    None => continue,
+                },
+                _ => None,
+            };
+            dbg.key(key)
+                .value(match upgraded.as_ref().unwrap_or(value) {
+                    Object::Int(x) => x,
+                    Object::Float(x) => x,
+                    Object::String(x) => x,
+                    Object::Bool(x) => x,
+                    Object::Nil => &"nil",
+                    Object::Function(_x) => todo!(),
+                    Object::Array(_x) => &"Array", // TODO: なんかいい感じにする
+                    Object::Table(_x) => &"Table",
+                    Object::RustFunction(x) => x,
+                    Object::WeakRef(x) => x,
+                });
         }
         dbg.finish()
     }
@@ -0,0 +1,43 @@
+use super::*;
+use core::cell::{Ref, RefCell};
+use std::rc::Rc;
+
+/// A local variable captured by functions.
+///
+/// While the scope of the local is alive, the upvalue is *open*: the value stays in the slot of the
+/// value stack, and the upvalue only refers to it. When the scope ends, the VM *closes* the
+/// upvalue by moving the value into it. The clones share the same variable.
+#[derive(Clone, Debug)]
+pub struct Upvalue(Rc<RefCell<UpvalueState>>);
+
+#[derive(Debug)]
+pub enum UpvalueState {
+    /// The local is in the slot `slot` of the value stack of the VM.
+    Open {
+        slot: usize,
+    },
+    Closed(Object),
+}
+
+impl Upvalue {
+    pub fn new_open(slot: usize) -> Self {
+        Upvalue(Rc::new(RefCell::new(UpvalueState::Open { slot })))
+    }
+
+    pub fn state(&self) -> Ref<'_, UpvalueState> {
+        self.0.borrow()
+    }
+
+    /// Returns the slot of the local if the upvalue is open.
+    pub fn open_slot(&self) -> Option<usize> {
+        match *self.0.borrow() {
+            UpvalueState::Open { slot } => Some(slot),
+            UpvalueState::Closed(_) => None,
+        }
+    }
+
+    /// Sets the value of the closed upvalue, or closes the open upvalue with `value`.
+    pub fn set_closed(&self, value: Object) {
+        *self.0.borrow_mut() = UpvalueState::Closed(value);
+    }
+}
@@ -4,7 +4,14 @@ use core::{
     hash::{Hash, Hasher},
     ops::{Add, AddAssign},
 };
-use std::sync::Arc;
+use std::{
+    cell::RefCell,
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
 
 #[derive(Clone)]
 pub struct UString(Variant);
@@ -23,11 +30,141 @@ fn _send_sync() {
     assert_sync::<UString>();
 }
 
+// Each thread interns the strings by itself, the same as the state of the collector, so that no
+// lock is taken. The strings interned by different threads are told apart by the ID of the
+// interner. (See `PartialEq for UString`)
+thread_local! {
+    static INTERNER: RefCell<Interner> = RefCell::new(Interner::new());
+}
+
+static NEXT_INTERNER_ID: AtomicU64 = AtomicU64::new(1);
+
+/// The smallest `Interner::prune_len`.
+const MIN_PRUNE_LEN: usize = 256;
+
+struct Interner {
+    id: u64,
+    strings: HashSet<UString>,
+    /// The number of the strings at which the ones used only by the interner are released.
+    prune_len: usize,
+}
+
+impl Interner {
+    fn new() -> Self {
+        Self {
+            id: NEXT_INTERNER_ID.fetch_add(1, Ordering::Relaxed),
+            strings: HashSet::new(),
+            prune_len: MIN_PRUNE_LEN,
+        }
+    }
+
+    /// Returns the interned string of the contents of `value`. `value` itself is interned if
+    /// nothing else refers to it, instead of copying it.
+    fn intern(&mut self, mut value: UString) -> UString {
+        debug_assert!(!value.is_empty());
+        if let Some(interned) = self.strings.get(value.as_str()) {
+            return interned.clone();
+        }
+        if self.strings.len() >= self.prune_len {
+            self.prune();
+        }
+        let Variant::Occupied(mut string) = core::mem::replace(&mut value.0, Variant::Empty) else {
+            unreachable!();
+        };
+        let interned = internal::Interned {
+            hash: crate::collections::lazy_hash_map::hash_one(string.as_str()),
+            interner: self.id,
+        };
+        match Arc::get_mut(&mut string) {
+            Some(unique) => unique.set_interned(interned),
+            None => {
+                let mut copy = internal::UnicodeBasedString::from(string.as_str());
+                copy.set_interned(interned);
+                string = Arc::new(copy);
+            }
+        }
+        let interned = UString(Variant::Occupied(string));
+        self.strings.insert(interned.clone());
+        interned
+    }
+
+    /// Releases `string`, which is about to be used only by the interner.
+    fn release(&mut self, string: &Arc<internal::UnicodeBasedString>) {
+        let is_interned = |interned: &UString| match &interned.0 {
+            Variant::Empty => false,
+            Variant::Occupied(interned) => Arc::ptr_eq(interned, string),
+        };
+        if !self.strings.get(string.as_str()).is_some_and(is_interned) {
+            return;
+        }
+        self.strings.remove(string.as_str());
+        if self.strings.is_empty() {
+            self.strings.shrink_to_fit();
+        }
+    }
+
+    /// Releases the strings used only by the interner, which are left by the other threads. (See
+    /// `Drop for UString`) It runs once the interner doubles, so that the cost is amortized over
+    /// the interning.
+    fn prune(&mut self) {
+        self.strings.retain(|string| match &string.0 {
+            Variant::Empty => false,
+            Variant::Occupied(string) => Arc::strong_count(string) > 1,
+        });
+        self.prune_len = (self.strings.len() * 2).max(MIN_PRUNE_LEN);
+    }
+}
+
 impl UString {
     pub const fn new() -> Self {
         UString(Variant::Empty)
     }
 
+    /// Returns the interned string of `value`, which is shared by the current thread. It is
+    /// released once nothing but the interner refers to it.
+    ///
+    /// The strings interned by the same thread are compared by their identity, and have the hash
+    /// computed in advance for the table lookups.
+    pub fn intern(value: &str) -> Self {
+        UString::from(value).into_interned()
+    }
+
+    /// Returns the interned string of the same contents, like `intern()`. `self` is returned as
+    /// is if it is already interned by the current thread.
+    pub fn into_interned(self) -> Self {
+        let Variant::Occupied(string) = &self.0 else {
+            return self;
+        };
+        let current = string.interned().map(|interned| interned.interner);
+        let mut value = Some(self);
+        // The interner is not available while the thread is exiting.
+        let _ = INTERNER.try_with(|interner| {
+            let mut interner = interner.borrow_mut();
+            if current != Some(interner.id) {
+                value = Some(interner.intern(value.take().unwrap()));
+            }
+        });
+        value.unwrap()
+    }
+
+    pub fn is_interned(&self) -> bool {
+        self.interned_hash().is_some()
+    }
+
+    /// Returns the number of the strings interned by the current thread, including the ones which
+    /// are not released yet. (See `intern()`)
+    pub fn interned_len() -> usize {
+        INTERNER.with_borrow(|interner| interner.strings.len())
+    }
+
+    /// Returns the hash in `LazyHashMap` if the string is interned. (See `intern()`)
+    pub(crate) fn interned_hash(&self) -> Option<u64> {
+        match &self.0 {
+            Variant::Empty => None,
+            Variant::Occupied(x) => x.interned().map(|interned| interned.hash),
+        }
+    }
+
     pub fn len(&self) -> usize {
         match &self.0 {
             Variant::Empty => 0,
@@ -80,6 +217,30 @@ impl UString {
     }
 }
 
+impl Drop for UString {
+    fn drop(&mut self) {
+        let Variant::Occupied(string) = &self.0 else {
+            return;
+        };
+        // Only this and the interner refer to the string.
+        let Some(interned) = string.interned() else {
+            return;
+        };
+        if Arc::strong_count(string) != 2 {
+            return;
+        }
+        // The interner of another thread releases the string by itself (See `Interner::prune()`),
+        // and the interner is not available while the thread is exiting.
+        let _ = INTERNER.try_with(|interner| {
+            if let Ok(mut interner) = interner.try_borrow_mut() {
+                if interner.id == interned.interner {
+                    interner.release(string);
+                }
+            }
+        });
+    }
+}
+
 impl Default for UString {
     fn default() -> Self {
         UString::new()
@@ -179,7 +340,16 @@ impl PartialEq for UString {
     fn eq(&self, other: &Self) -> bool {
         match (&self.0, &other.0) {
             (Variant::Empty, Variant::Empty) => true,
-            (Variant::Occupied(lhs), Variant::Occupied(rhs)) => lhs.eq(rhs),
+            (Variant::Occupied(lhs), Variant::Occupied(rhs)) => {
+                if Arc::ptr_eq(lhs, rhs) {
+                    return true;
+                }
+                match (lhs.interned(), rhs.interned()) {
+                    // The strings interned by the same thread are equal only if they are the same.
+                    (Some(lhs), Some(rhs)) if lhs.interner == rhs.interner => false,
+                    _ => lhs.eq(rhs),
+                }
+            }
             _ => false,
         }
     }
@@ -204,7 +374,12 @@ impl_symmetric_partial_eq!(str, &str, String, &String);
 
 impl Ord for UString {
     fn cmp(&self, other: &Self) -> cmp::Ordering {
-        self.as_str().cmp(other.as_str())
+        match (&self.0, &other.0) {
+            (Variant::Occupied(lhs), Variant::Occupied(rhs)) if Arc::ptr_eq(lhs, rhs) => {
+                cmp::Ordering::Equal
+            }
+            _ => self.as_str().cmp(other.as_str()),
+        }
     }
 }
 
@@ -240,7 +415,16 @@ mod internal {
     use core::iter;
 
     #[derive(Clone)]
-    pub(super) struct UnicodeBasedString(Variant);
+    pub(super) struct UnicodeBasedString(Variant, Option<Interned>);
+
+    /// How a string is interned. (See `UString::intern()`)
+    #[derive(Clone, Copy)]
+    pub(super) struct Interned {
+        /// The hash in `LazyHashMap`.
+        pub(super) hash: u64,
+        /// The ID of the interner, i.e. of the thread, which interned the string.
+        pub(super) interner: u64,
+    }
 
     #[derive(Clone)]
     enum Variant {
@@ -250,6 +434,14 @@ mod internal {
     use Variant::*;
 
     impl UnicodeBasedString {
+        pub(super) fn interned(&self) -> Option<Interned> {
+            self.1
+        }
+
+        pub(super) fn set_interned(&mut self, interned: Interned) {
+            self.1 = Some(interned);
+        }
+
         pub(super) fn len(&self) -> usize {
             match &self.0 {
                 Ascii(s) => s.len(),
@@ -295,7 +487,7 @@ mod internal {
             match &self.0 {
                 Ascii(s) => {
                     let str = s.get(start..end)?;
-                    Some(UnicodeBasedString(Ascii(CompactString::from(str))))
+                    Some(UnicodeBasedString(Ascii(CompactString::from(str)), None))
                 }
                 NonAscii(s, pos) => {
                     let start = *pos.get(start)?;
@@ -307,6 +499,8 @@ mod internal {
         }
 
         pub(super) fn push_str(&mut self, string: &str) {
+            // The string is a copy of the interned one if it was interned. (See `Arc::make_mut()`)
+            self.1 = None;
             match (&mut self.0, string.is_ascii()) {
                 (Ascii(s), true) => {
                     s.push_str(string);
@@ -345,14 +539,14 @@ mod internal {
         fn from(str: &str) -> Self {
             let string = CompactString::from(str);
             if str.is_ascii() {
-                UnicodeBasedString(Ascii(string))
+                UnicodeBasedString(Ascii(string), None)
             } else {
                 let pos = str
                     .char_indices()
                     .map(|(i, _)| i)
                     .chain(iter::once(str.len()))
                     .collect();
-                UnicodeBasedString(NonAscii(string, pos))
+                UnicodeBasedString(NonAscii(string, pos), None)
             }
         }
     }
@@ -360,14 +554,14 @@ mod internal {
     impl From<CompactString> for UnicodeBasedString {
         fn from(string: CompactString) -> Self {
             if string.is_ascii() {
-                UnicodeBasedString(Ascii(string))
+                UnicodeBasedString(Ascii(string), None)
             } else {
                 let pos = string
                     .char_indices()
                     .map(|(i, _)| i)
                     .chain(iter::once(string.len()))
                     .collect();
-                UnicodeBasedString(NonAscii(string, pos))
+                UnicodeBasedString(NonAscii(string, pos), None)
             }
         }
     }
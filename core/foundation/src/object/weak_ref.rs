@@ -0,0 +1,131 @@
+use super::{array::Inner as ArrayInner, table::Inner as TableInner, *};
+use core::{fmt, ptr::NonNull};
+
+/// A reference to an `Array` or a `Table` which does not keep it alive.
+///
+/// The target is freed when its reference count becomes zero or it is collected as a part of a
+/// garbage cycle, regardless of the `WeakRef`s to it. Then [`WeakRef::upgrade()`] returns `None`.
+/// So it does once the target is queued for finalization (`__gc`), even if the finalizer keeps
+/// the target alive, since a finalized object must not be reached again through a weak reference.
+pub struct WeakRef {
+    // The pointer to the inner of the target. Its lowest bit is set if the target is a `Table`,
+    // so that `Object` stays 16 bytes.
+    tagged_ptr: NonNull<()>,
+}
+
+enum Target {
+    Array(NonNull<ArrayInner>),
+    Table(NonNull<TableInner>),
+}
+
+const TABLE_TAG: usize = 1;
+
+impl WeakRef {
+    fn target(&self) -> Target {
+        let addr = self.tagged_ptr.addr().get();
+        if addr & TABLE_TAG == 0 {
+            Target::Array(self.tagged_ptr.cast())
+        } else {
+            let ptr = self.tagged_ptr.map_addr(|addr| unsafe {
+                core::num::NonZero::new_unchecked(addr.get() & !TABLE_TAG)
+            });
+            Target::Table(ptr.cast())
+        }
+    }
+
+    /// Returns the target if it is neither freed nor finalized yet.
+    pub fn upgrade(&self) -> Option<Object> {
+        match self.target() {
+            Target::Array(ptr) => unsafe {
+                let inner = ptr.as_ref();
+                is_reachable(inner).then(|| {
+                    inner.inc_ref_count();
+                    Object::Array(Array::from_inner(ptr))
+                })
+            },
+            Target::Table(ptr) => unsafe {
+                let inner = ptr.as_ref();
+                is_reachable(inner).then(|| {
+                    inner.inc_ref_count();
+                    Object::Table(Table::from_inner(ptr))
+                })
+            },
+        }
+    }
+
+    /// Returns `true` if the target is neither freed nor finalized yet.
+    pub fn is_alive(&self) -> bool {
+        match self.target() {
+            Target::Array(ptr) => unsafe { is_reachable(ptr.as_ref()) },
+            Target::Table(ptr) => unsafe { is_reachable(ptr.as_ref()) },
+        }
+    }
+
+    /// Returns `true` if the two weak references refer to the same object.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        self.tagged_ptr == other.tagged_ptr
+    }
+}
+
+// The reference count of a freed (released) target is zero. (See `PmsInner::is_released()`)
+fn is_reachable(inner: &impl PmsInner) -> bool {
+    inner.ref_count() > 0 && !inner.is_finalized()
+}
+
+impl From<&Array> for WeakRef {
+    fn from(array: &Array) -> Self {
+        array.inner().inc_weak_count();
+        WeakRef {
+            tagged_ptr: array.ptr().cast(),
+        }
+    }
+}
+
+impl From<&Table> for WeakRef {
+    fn from(table: &Table) -> Self {
+        table.inner().inc_weak_count();
+        let ptr = table.ptr().cast::<()>();
+        debug_assert_eq!(ptr.addr().get() & TABLE_TAG, 0);
+        WeakRef {
+            tagged_ptr: ptr.map_addr(|addr| addr | TABLE_TAG),
+        }
+    }
+}
+
+impl Clone for WeakRef {
+    fn clone(&self) -> Self {
+        match self.target() {
+            Target::Array(ptr) => unsafe { ptr.as_ref().inc_weak_count() },
+            Target::Table(ptr) => unsafe { ptr.as_ref().inc_weak_count() },
+        }
+        WeakRef {
+            tagged_ptr: self.tagged_ptr,
+        }
+    }
+}
+
+impl Drop for WeakRef {
+    fn drop(&mut self) {
+        match self.target() {
+            Target::Array(ptr) => unsafe { drop_weak::<_, Array>(ptr) },
+            Target::Table(ptr) => unsafe { drop_weak::<_, Table>(ptr) },
+        }
+    }
+}
+
+impl PartialEq for WeakRef {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr_eq(other)
+    }
+}
+
+impl fmt::Debug for WeakRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.target() {
+            Target::Array(_) => "Array",
+            Target::Table(_) => "Table",
+        };
+        let state = if self.is_alive() { "alive" } else { "dead" };
+        write!(f, "WeakRef({kind}, {state})")
+    }
+}
@@ -54,6 +54,32 @@ impl fmt::Display for SyntaxError {
 
 impl Error for SyntaxError {}
 
+/// A diagnostic that does not prevent the program from being compiled.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SyntaxWarning {
+    message: Cow<'static, str>,
+    range: rowan::TextRange,
+}
+
+impl SyntaxWarning {
+    pub fn new(message: Cow<'static, str>, range: rowan::TextRange) -> Self {
+        Self { message, range }
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+    pub fn range(&self) -> rowan::TextRange {
+        self.range
+    }
+}
+
+impl fmt::Display for SyntaxWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
 macro_rules! syntax_kind {
     ($($variant:ident $(= [$($tt:tt)*])? $(@ $anchor:ident)?),* $(,)?) => {
         #[allow(bad_style)]
@@ -105,10 +131,16 @@ syntax_kind! {
     INT = [int] @START_LITERAL,
     FLOAT = [float],
     STRING = [string],
+    RAW_STRING = [raw_string],
     TRUE = [true],
     FALSE = [false],
     NIL = [nil] @END_LITERAL,
 
+    STRING_START = [string_start],
+    STRING_MID = [string_mid],
+    STRING_END = [string_end],
+    FORMAT_SPEC = [format_spec],
+
     VAR_KW = [var] @START_KEYWORD,
     FUNC_KW = [func],
     IF_KW = [if],
@@ -123,6 +155,8 @@ syntax_kind! {
     RETURN_KW = [return],
     BREAK_KW = [break],
     CONTINUE_KW = [continue],
+    MATCH_KW = [match],
+    WITH_KW = [with],
     AND_KW = [and],
     OR_KW = [or],
     NOT_KW = [not] @END_KEYWORD,
@@ -157,7 +191,21 @@ syntax_kind! {
     LTEQ = [<=],
     GT2 = [>>],
     GTEQ = [>=],
-    DOT2 = [..] @END_PUNCT,
+    DOT2 = [..],
+    QUESTION2 = [??],
+    QUESTIONDOT = [?.],
+    PLUSEQ = [+=],
+    MINUSEQ = [-=],
+    STAREQ = [*=],
+    SLASHEQ = [/=],
+    PERCENTEQ = [%=],
+    AMPEQ = [&=],
+    PIPEEQ = [|=],
+    CARETEQ = [^=],
+    LT2EQ = [<<=],
+    GT2EQ = [>>=],
+    DOT2EQ = [..=],
+    QUESTIONARROW = [?->] @END_PUNCT,
 
     IDENT = [ident],
 
@@ -176,6 +224,7 @@ syntax_kind! {
     ATTR_STMT,
 
     IF_EXPR,
+    MATCH_EXPR,
     DO_EXPR,
     CALL_EXPR,
     BINARY_EXPR,
@@ -184,6 +233,7 @@ syntax_kind! {
     FIELD_EXPR,
     METHOD_CALL_EXPR,
     PAREN_EXPR,
+    INTERPOLATED_STRING,
 
     LOCAL_VAR,
     LITERAL,
@@ -193,6 +243,13 @@ syntax_kind! {
 
     ELSE_BRANCH,
     ELIF_BRANCH,
+    MATCH_ARM,
+
+    LITERAL_PAT,
+    BINDING_PAT,
+    TABLE_PAT,
+    TABLE_PAT_FIELD,
+    ARRAY_PAT,
 
     PARAM_LIST,
     ARG_LIST,
@@ -202,6 +259,7 @@ syntax_kind! {
     TABLE_FIELD,
     TABLE_FIELD_NAME_IDENT,
     TABLE_FIELD_NAME_EXPR,
+    INTERPOLATION,
 }
 
 const fn _static_assert_size() {
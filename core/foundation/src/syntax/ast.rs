@@ -173,6 +173,7 @@ ast_node!(struct AttrStmt for ATTR_STMT {
 
 ast_node!(enum Expression for {
     If(IfExpr),
+    Match(MatchExpr),
     Do(DoExpr),
     Call(CallExpr),
     Binary(BinaryExpr),
@@ -186,6 +187,7 @@ ast_node!(enum Expression for {
     ArrayConst(ArrayConst),
     TableConst(TableConst),
     FuncConst(FuncConst),
+    InterpolatedString(InterpolatedString),
 });
 
 // 'if' Expr then
@@ -203,6 +205,17 @@ ast_node!(struct IfExpr for IF_EXPR {
     end_token: token[end],
 });
 
+// 'match' Expr 'with'
+//  MatchArm*
+// 'end'
+ast_node!(struct MatchExpr for MATCH_EXPR {
+    match_token: token[match],
+    scrutinee: child[Expression],
+    with_token: token[with],
+    arms: children[MatchArm],
+    end_token: token[end],
+});
+
 // 'do'
 //   Program
 // 'end'
@@ -250,7 +263,19 @@ impl BinaryExpr {
                     T![&]   => BinaryOp::BitAnd,
                     T![|]   => BinaryOp::BitOr,
                     T![^]   => BinaryOp::BitXor,
+                    T![??]  => BinaryOp::NilCoalesce,
                     T![=]   => BinaryOp::Assign,
+                    T![+=]  => BinaryOp::AddAssign,
+                    T![-=]  => BinaryOp::SubAssign,
+                    T![*=]  => BinaryOp::MulAssign,
+                    T![/=]  => BinaryOp::DivAssign,
+                    T![%=]  => BinaryOp::ModAssign,
+                    T![<<=] => BinaryOp::ShlAssign,
+                    T![>>=] => BinaryOp::ShrAssign,
+                    T![..=] => BinaryOp::ConcatAssign,
+                    T![&=]  => BinaryOp::BitAndAssign,
+                    T![|=]  => BinaryOp::BitOrAssign,
+                    T![^=]  => BinaryOp::BitXorAssign,
                     _ => return None,
                 };
                 Some((token, op))
@@ -284,7 +309,19 @@ pub enum BinaryOp {
     BitAnd,
     BitOr,
     BitXor,
+    NilCoalesce,
     Assign,
+    AddAssign,
+    SubAssign,
+    MulAssign,
+    DivAssign,
+    ModAssign,
+    ShlAssign,
+    ShrAssign,
+    ConcatAssign,
+    BitAndAssign,
+    BitOrAssign,
+    BitXorAssign,
 }
 impl BinaryOp {
     pub fn sign_text(&self) -> &'static str {
@@ -308,9 +345,40 @@ impl BinaryOp {
             BinaryOp::BitAnd => "&",
             BinaryOp::BitOr => "|",
             BinaryOp::BitXor => "^",
+            BinaryOp::NilCoalesce => "??",
             BinaryOp::Assign => "=",
+            BinaryOp::AddAssign => "+=",
+            BinaryOp::SubAssign => "-=",
+            BinaryOp::MulAssign => "*=",
+            BinaryOp::DivAssign => "/=",
+            BinaryOp::ModAssign => "%=",
+            BinaryOp::ShlAssign => "<<=",
+            BinaryOp::ShrAssign => ">>=",
+            BinaryOp::ConcatAssign => "..=",
+            BinaryOp::BitAndAssign => "&=",
+            BinaryOp::BitOrAssign => "|=",
+            BinaryOp::BitXorAssign => "^=",
         }
     }
+
+    /// Returns the operator applied by the compound assignment, e.g. `Add` for `+=`.
+    pub fn compound_assign_op(&self) -> Option<BinaryOp> {
+        let op = match self {
+            BinaryOp::AddAssign => BinaryOp::Add,
+            BinaryOp::SubAssign => BinaryOp::Sub,
+            BinaryOp::MulAssign => BinaryOp::Mul,
+            BinaryOp::DivAssign => BinaryOp::Div,
+            BinaryOp::ModAssign => BinaryOp::Mod,
+            BinaryOp::ShlAssign => BinaryOp::Shl,
+            BinaryOp::ShrAssign => BinaryOp::Shr,
+            BinaryOp::ConcatAssign => BinaryOp::Concat,
+            BinaryOp::BitAndAssign => BinaryOp::BitAnd,
+            BinaryOp::BitOrAssign => BinaryOp::BitOr,
+            BinaryOp::BitXorAssign => BinaryOp::BitXor,
+            _ => return None,
+        };
+        Some(op)
+    }
 }
 
 ast_node!(struct PrefixExpr for PREFIX_EXPR {
@@ -372,6 +440,15 @@ ast_node!(struct FieldExpr for FIELD_EXPR {
     dot_token: token[.],
     field: child[Name],
 });
+impl FieldExpr {
+    pub fn question_dot_token(&self) -> Option<SyntaxToken> {
+        support::token(self.syntax(), T![?.])
+    }
+    /// Returns `true` if this is the optional chaining `?.`.
+    pub fn is_optional(&self) -> bool {
+        self.question_dot_token().is_some()
+    }
+}
 
 // Expr '->' Name ArgList
 ast_node!(struct MethodCallExpr for METHOD_CALL_EXPR {
@@ -380,6 +457,15 @@ ast_node!(struct MethodCallExpr for METHOD_CALL_EXPR {
     method_name: child[Name],
     arg_list: child[ArgList],
 });
+impl MethodCallExpr {
+    pub fn question_arrow_token(&self) -> Option<SyntaxToken> {
+        support::token(self.syntax(), T![?->])
+    }
+    /// Returns `true` if this is the optional chaining `?->`.
+    pub fn is_optional(&self) -> bool {
+        self.question_arrow_token().is_some()
+    }
+}
 
 // '(' Expr ')'
 ast_node!(struct ParenExpr for PAREN_EXPR {
@@ -406,12 +492,13 @@ impl Literal {
         let token = self.token()?;
         #[rustfmt::skip]
         let kind = match token.kind() {
-            T![int]    => LiteralKind::Int(token),
-            T![float]  => LiteralKind::Float(token),
-            T![string] => LiteralKind::String(token),
-            T![true]   => LiteralKind::Bool(true),
-            T![false]  => LiteralKind::Bool(false),
-            T![nil]    => LiteralKind::Nil,
+            T![int]        => LiteralKind::Int(token),
+            T![float]      => LiteralKind::Float(token),
+            T![string]     => LiteralKind::String(token),
+            T![raw_string] => LiteralKind::RawString(token),
+            T![true]       => LiteralKind::Bool(true),
+            T![false]      => LiteralKind::Bool(false),
+            T![nil]        => LiteralKind::Nil,
             _ => return None,
         };
         Some(kind)
@@ -421,6 +508,7 @@ pub enum LiteralKind {
     Int(SyntaxToken),
     Float(SyntaxToken),
     String(SyntaxToken),
+    RawString(SyntaxToken),
     Bool(bool),
     Nil,
 }
@@ -449,6 +537,39 @@ ast_node!(struct FuncConst for FUNC_CONST {
     end_token: token[end],
 });
 
+// string_start Interpolation (string_mid Interpolation)* string_end
+ast_node!(struct InterpolatedString for INTERPOLATED_STRING {
+    interpolations: children[Interpolation],
+});
+impl InterpolatedString {
+    /// Returns the string parts and the interpolations in the order of appearance.
+    pub fn parts(&self) -> impl Iterator<Item = InterpolatedStringPart> {
+        self.syntax()
+            .children_with_tokens()
+            .filter_map(|it| match it {
+                rowan::NodeOrToken::Node(node) => {
+                    Interpolation::cast(node).map(InterpolatedStringPart::Interpolation)
+                }
+                rowan::NodeOrToken::Token(token) => match token.kind() {
+                    T![string_start] | T![string_mid] | T![string_end] => {
+                        Some(InterpolatedStringPart::String(token))
+                    }
+                    _ => None,
+                },
+            })
+    }
+}
+pub enum InterpolatedStringPart {
+    String(SyntaxToken),
+    Interpolation(Interpolation),
+}
+
+// Expr format_spec?
+ast_node!(struct Interpolation for INTERPOLATION {
+    expr: child[Expression],
+    format_spec_token: token[format_spec],
+});
+
 // 'else'
 //   Program
 ast_node!(struct ElseBranch for ELSE_BRANCH {
@@ -465,6 +586,60 @@ ast_node!(struct ElifBranch for ELIF_BRANCH {
     body: child[Program],
 });
 
+// '|' Pattern 'then'
+//   Program
+ast_node!(struct MatchArm for MATCH_ARM {
+    pipe_token: token[|],
+    pattern: child[Pattern],
+    then_token: token[then],
+    body: child[Program],
+});
+
+ast_node!(enum Pattern for {
+    Literal(LiteralPat),
+    Binding(BindingPat),
+    Table(TablePat),
+    Array(ArrayPat),
+});
+
+// '-'? Literal
+ast_node!(struct LiteralPat for LITERAL_PAT {
+    minus_token: token[-],
+    literal: child[Literal],
+});
+
+// ident
+ast_node!(struct BindingPat for BINDING_PAT {
+    ident_token: token[ident],
+});
+impl BindingPat {
+    /// Returns `true` if this is the wildcard pattern `_`, which binds nothing.
+    pub fn is_wildcard(&self) -> bool {
+        self.ident_token().is_some_and(|token| token.text() == "_")
+    }
+}
+
+// '{' TablePatField* '}'
+ast_node!(struct TablePat for TABLE_PAT {
+    l_brace_token: token['{'],
+    fields: children[TablePatField],
+    r_brace_token: token['}'],
+});
+
+// Name ('=' Pattern)?
+ast_node!(struct TablePatField for TABLE_PAT_FIELD {
+    name: child[Name],
+    eq_token: token[=],
+    pattern: child[Pattern],
+});
+
+// '[' ( Pattern (',' Pattern)* ','? )? ']'
+ast_node!(struct ArrayPat for ARRAY_PAT {
+    l_bracket_token: token['['],
+    elements: children[Pattern],
+    r_bracket_token: token[']'],
+});
+
 // '(' Name* ')'
 ast_node!(struct ParamList for PARAM_LIST {
     l_paren_token: token['('],
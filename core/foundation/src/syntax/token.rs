@@ -26,6 +26,22 @@ pub enum TokenKind {
         terminated: bool,
         quote_kind: QuoteKind,
     },
+    /// Raw string literal, no escape sequences and interpolations. e.g. "r\"foo\\d\""
+    RawString {
+        terminated: bool,
+        quote_kind: QuoteKind,
+    },
+    /// Start part of interpolated string literal. e.g. "\"foo {"
+    StringStart { quote_kind: QuoteKind },
+    /// Middle part of interpolated string literal. e.g. "} bar {"
+    StringMid,
+    /// End part of interpolated string literal. e.g. "} baz\""
+    StringEnd {
+        terminated: bool,
+        quote_kind: QuoteKind,
+    },
+    /// Format spec of string interpolation. e.g. ":.2"
+    FormatSpec,
     /// "true"
     True,
     /// "false"
@@ -62,6 +78,10 @@ pub enum TokenKind {
     Break,
     /// "continue"
     Continue,
+    /// "match"
+    Match,
+    /// "with"
+    With,
     /// "and"
     And,
     /// "or"
@@ -134,6 +154,36 @@ pub enum TokenKind {
     GtEq,
     /// ".."
     Dot2,
+    /// "??"
+    Question2,
+    /// "?."
+    QuestionDot,
+    /// "+="
+    PlusEq,
+    /// "-="
+    MinusEq,
+    /// "*="
+    StarEq,
+    /// "/="
+    SlashEq,
+    /// "%="
+    PercentEq,
+    /// "&="
+    AmpEq,
+    /// "|="
+    PipeEq,
+    /// "^="
+    CaretEq,
+
+    // Three character symbol.
+    /// "<<="
+    Lt2Eq,
+    /// ">>="
+    Gt2Eq,
+    /// "..="
+    Dot2Eq,
+    /// "?->"
+    QuestionArrow,
 
     /// Identifier that is not classified as a keyword or literal. e.g. "foo"
     Ident,
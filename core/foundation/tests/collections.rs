@@ -186,6 +186,25 @@ fn lazy_hash_map_insert() {
     assert_eq!(map.get("key99"), Some(&99));
 }
 
+#[test]
+fn lazy_hash_map_hashed() {
+    use foundation::collections::lazy_hash_map::hash_one;
+    let mut map = LazyHashMap::new();
+    for i in 0..100 {
+        let key = format!("key{}", i);
+        assert_eq!(map.insert_hashed(hash_one(&key), key, i), None);
+    }
+    let key = "key42".to_string();
+    assert_eq!(map.get("key42"), Some(&42));
+    assert_eq!(map.get_hashed(hash_one(&key), &key), Some(&42));
+    assert_eq!(
+        map.insert_hashed(hash_one(&key), key.clone(), -42),
+        Some(42)
+    );
+    assert_eq!(map.get_hashed(hash_one(&key), &key), Some(&-42));
+    assert_eq!(map.len(), 100);
+}
+
 #[test]
 fn lazy_hash_map_remove() {
     let mut map = LazyHashMap::from([("foo".to_string(), -1), ("bar".to_string(), -2)]);
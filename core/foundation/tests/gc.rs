@@ -0,0 +1,251 @@
+use foundation::object::{gc, *};
+
+use mockalloc::Mockalloc;
+
+#[global_allocator]
+static ALLOCATOR: Mockalloc<std::alloc::System> = Mockalloc(std::alloc::System);
+
+fn make_cycle() {
+    let mut table1 = Table::new();
+    let mut table2 = Table::new();
+    table1.insert("table2".into(), Object::Table(table2.clone()));
+    table2.insert("table1".into(), Object::Table(table1.clone()));
+}
+
+#[mockalloc::test]
+fn immediate_stats() {
+    let before = gc::stats();
+    {
+        let mut array = Array::new();
+        array.push(Object::Array(array.clone()));
+        let _table = Table::new();
+        let stats = gc::stats();
+        assert_eq!(stats.live_arrays, before.live_arrays + 1);
+        assert_eq!(stats.live_tables, before.live_tables + 1);
+    }
+    make_cycle();
+    let after = gc::stats();
+    assert_eq!(after.live_arrays, before.live_arrays);
+    assert_eq!(after.live_tables, before.live_tables);
+    assert_eq!(after.collected, before.collected + 3);
+    assert!(after.runs > before.runs);
+    assert_eq!(after.pending, 0);
+}
+
+#[mockalloc::test]
+fn batched_collect() {
+    gc::set_mode(gc::Mode::Batched { threshold: 100 });
+    let before = gc::stats();
+    for _ in 0..10 {
+        make_cycle();
+    }
+    let stats = gc::stats();
+    assert_eq!(stats.live_tables, before.live_tables + 20);
+    // Both tables of each cycle are left with a reference, so both are candidates.
+    assert_eq!(stats.pending, 20);
+
+    assert_eq!(gc::collect(), 20);
+    let stats = gc::stats();
+    assert_eq!(stats.live_tables, before.live_tables);
+    assert_eq!(stats.pending, 0);
+    gc::set_mode(gc::Mode::Immediate);
+}
+
+#[mockalloc::test]
+fn batched_threshold() {
+    gc::set_mode(gc::Mode::Batched { threshold: 4 });
+    let before = gc::stats();
+    make_cycle();
+    make_cycle();
+    // The 4th candidate reaches the threshold.
+    let stats = gc::stats();
+    assert_eq!(stats.pending, 0);
+    assert_eq!(stats.live_tables, before.live_tables);
+
+    make_cycle();
+    assert_eq!(gc::stats().pending, 2);
+    // Switching to the immediate mode collects the queued objects.
+    gc::set_mode(gc::Mode::Immediate);
+    assert_eq!(gc::stats().pending, 0);
+    assert_eq!(gc::stats().live_tables, before.live_tables);
+}
+
+#[mockalloc::test]
+fn batched_keeps_reachable_objects() {
+    gc::set_mode(gc::Mode::Batched { threshold: 100 });
+    let mut root = Table::new();
+    {
+        let mut child = Table::new();
+        child.insert("value".into(), Object::Int(42));
+        child.insert("parent".into(), Object::Table(root.clone()));
+        root.insert("child".into(), Object::Table(child.clone()));
+    }
+    gc::collect();
+    assert_eq!(
+        match root.get("child").unwrap() {
+            Object::Table(child) => child.get("value").unwrap(),
+            _ => unreachable!(),
+        },
+        &Object::Int(42)
+    );
+    drop(root);
+    assert_eq!(gc::collect(), 2);
+    gc::set_mode(gc::Mode::Immediate);
+}
+
+#[mockalloc::test]
+fn batched_frees_acyclic_objects() {
+    gc::set_mode(gc::Mode::Batched { threshold: 100 });
+    let before = gc::stats();
+    {
+        let mut table = Table::new();
+        table.insert("array".into(), Object::Array(Array::new()));
+        let shared = table.clone();
+        drop(shared);
+        assert_eq!(gc::stats().pending, 1);
+    }
+    // The children are released as soon as the reference count becomes zero, while the buffered
+    // table itself is deallocated by the next collection.
+    let stats = gc::stats();
+    assert_eq!(stats.live_arrays, before.live_arrays);
+    assert_eq!(stats.live_tables, before.live_tables + 1);
+    assert_eq!(gc::collect(), 0);
+    assert_eq!(gc::stats().live_tables, before.live_tables);
+    gc::set_mode(gc::Mode::Immediate);
+}
+
+/// root <-> a, root -> b -> a
+fn make_diamond() {
+    let mut root = Table::new();
+    let mut a = Table::new();
+    let mut b = Table::new();
+    a.insert("root".into(), Object::Table(root.clone()));
+    b.insert("a".into(), Object::Table(a.clone()));
+    root.insert("a".into(), Object::Table(a));
+    root.insert("b".into(), Object::Table(b));
+}
+
+#[mockalloc::test]
+fn diamond() {
+    make_diamond();
+    gc::set_mode(gc::Mode::Batched { threshold: 100 });
+    make_diamond();
+    assert_eq!(gc::collect(), 3);
+    gc::set_mode(gc::Mode::Immediate);
+}
+
+#[mockalloc::test]
+fn batched_shared_subgraph() {
+    gc::set_mode(gc::Mode::Batched { threshold: 100 });
+    let mut shared = Array::new();
+    shared.push(Object::Array(shared.clone()));
+    let mut holders = Vec::new();
+    for _ in 0..10 {
+        let mut holder = Table::new();
+        holder.insert("shared".into(), Object::Array(shared.clone()));
+        holders.push(holder);
+    }
+    drop(shared);
+    // Dropping the holders makes the shared array a root repeatedly, but it is buffered once.
+    drop(holders);
+    assert_eq!(gc::stats().pending, 1);
+    assert_eq!(gc::collect(), 1);
+    gc::set_mode(gc::Mode::Immediate);
+}
+
+fn finalizable_table(name: &str) -> Table {
+    let mut table = Table::from([("name".into(), Object::String(name.into()))]);
+    table.set_method("__gc".into(), RustFunction::new(1, |_| Ok(Object::Nil)));
+    table
+}
+
+fn name_of(table: &Table) -> &str {
+    match table.get("name") {
+        Some(Object::String(name)) => name.as_str(),
+        _ => unreachable!(),
+    }
+}
+
+#[mockalloc::test]
+fn finalize_by_ref_count() {
+    let before = gc::stats();
+    let mut parent = finalizable_table("parent");
+    parent.insert("child".into(), Object::Table(finalizable_table("child")));
+    drop(parent);
+
+    // The parent is finalized first, while its child is alive.
+    let parent = gc::take_finalizable().unwrap();
+    assert_eq!(name_of(&parent), "parent");
+    assert!(matches!(parent.get("child"), Some(Object::Table(_))));
+    assert!(!gc::has_finalizable());
+    drop(parent);
+
+    let child = gc::take_finalizable().unwrap();
+    assert_eq!(name_of(&child), "child");
+    drop(child);
+    assert!(gc::take_finalizable().is_none());
+    assert_eq!(gc::stats().live_tables, before.live_tables);
+}
+
+#[mockalloc::test]
+fn finalize_cycle() {
+    let before = gc::stats();
+    {
+        let mut table1 = finalizable_table("table1");
+        let mut table2 = Table::new();
+        table1.insert("table2".into(), Object::Table(table2.clone()));
+        table2.insert("table1".into(), Object::Table(table1.clone()));
+    }
+    // The cycle is kept alive while the finalizer runs.
+    let table1 = gc::take_finalizable().unwrap();
+    assert_eq!(name_of(&table1), "table1");
+    let Some(Object::Table(table2)) = table1.get("table2") else {
+        unreachable!()
+    };
+    assert!(matches!(table2.get("table1"), Some(Object::Table(_))));
+    assert_eq!(gc::stats().collected, before.collected);
+
+    // The cycle is collected as garbage without finalizers after that.
+    drop(table1);
+    assert!(!gc::has_finalizable());
+    assert_eq!(gc::stats().collected, before.collected + 2);
+    assert_eq!(gc::stats().live_tables, before.live_tables);
+}
+
+#[mockalloc::test]
+fn finalize_resurrection() {
+    let before = gc::stats();
+    drop(finalizable_table("table"));
+    let table = gc::take_finalizable().unwrap();
+    // The finalizer stores the table somewhere.
+    let resurrected = table.clone();
+    drop(table);
+    assert_eq!(name_of(&resurrected), "table");
+
+    // The finalizer is not run again.
+    drop(resurrected);
+    assert!(!gc::has_finalizable());
+    assert_eq!(gc::stats().live_tables, before.live_tables);
+}
+
+#[mockalloc::test]
+fn finalize_batched() {
+    gc::set_mode(gc::Mode::Batched { threshold: 100 });
+    make_cycle();
+    {
+        let mut table1 = finalizable_table("table1");
+        let mut table2 = finalizable_table("table2");
+        table1.insert("table2".into(), Object::Table(table2.clone()));
+        table2.insert("table1".into(), Object::Table(table1.clone()));
+    }
+    // The cycle without finalizers is freed, and the other one is queued.
+    assert_eq!(gc::collect(), 2);
+    let mut names = Vec::new();
+    while let Some(table) = gc::take_finalizable() {
+        names.push(name_of(&table).to_string());
+    }
+    names.sort();
+    assert_eq!(names, ["table1", "table2"]);
+    assert_eq!(gc::collect(), 2);
+    gc::set_mode(gc::Mode::Immediate);
+}
@@ -178,6 +178,65 @@ fn u_string_clone_no_sync() {
     assert_eq!(s1, "abcdef");
 }
 
+#[test]
+fn u_string_intern() {
+    let interned = UString::intern("hello");
+    assert!(interned.is_interned());
+    assert_eq!(interned, UString::intern("hello"));
+    assert_eq!(interned, UString::from("hello"));
+    assert_ne!(interned, UString::intern("world"));
+    assert!(!UString::from("hello").is_interned());
+
+    let mut appended = interned.clone();
+    appended += " world";
+    assert!(!appended.is_interned());
+    assert_eq!(appended, UString::intern("hello world"));
+    assert_eq!(interned, "hello");
+}
+
+#[test]
+fn u_string_intern_release() {
+    let before = UString::interned_len();
+    let interned = UString::intern("released");
+    let cloned = interned.clone();
+    assert_eq!(UString::interned_len(), before + 1);
+    drop(interned);
+    assert_eq!(UString::interned_len(), before + 1);
+    drop(cloned);
+    assert_eq!(UString::interned_len(), before);
+}
+
+#[test]
+fn u_string_intern_threads() {
+    let here = UString::intern("shared");
+    let there = std::thread::spawn(|| UString::intern("shared"))
+        .join()
+        .unwrap();
+    // The strings interned by different threads are compared by their contents.
+    assert!(there.is_interned());
+    assert_eq!(here, there);
+    assert_ne!(there, UString::intern("other"));
+    assert_eq!(there.into_interned(), here);
+}
+
+#[test]
+fn table_method_by_name() {
+    let mut table = Table::new();
+    table.set_method("method".into(), RustFunction::new(0, |_| Ok(Object::Nil)));
+    assert!(table
+        .get_method_by_name(&MethodName::new("method"))
+        .is_some());
+    assert!(table
+        .get_method_by_name(&MethodName::new("other"))
+        .is_none());
+    assert!(table.get_method("method").is_some());
+
+    let name = std::thread::spawn(|| MethodName::new("method"))
+        .join()
+        .unwrap();
+    assert!(table.get_method_by_name(&name).is_some());
+}
+
 #[test]
 fn array_construct_empty() {
     let array = Array::new();
@@ -378,3 +437,117 @@ fn table_equal_for_nan() {
     let table2 = table1.clone();
     assert_ne!(table1, table2);
 }
+
+#[test]
+fn table_interned_key() {
+    let mut table = Table::new();
+    for n in 0..32 {
+        table.insert(UString::intern(&format!("key{}", n)), n);
+    }
+    table.insert("foo".into(), 100);
+    let cache = FieldCache::new();
+    assert_eq!(table.get("key3"), Some(&i(3)));
+    assert_eq!(
+        table.get_cached(&UString::intern("key3"), &cache),
+        Some(i(3))
+    );
+    assert_eq!(
+        table.get_cached(&UString::intern("foo"), &cache),
+        Some(i(100))
+    );
+    table.insert(UString::intern("foo"), 200);
+    assert_eq!(table.get("foo"), Some(&i(200)));
+    assert_eq!(table.len(), 33);
+}
+
+#[test]
+fn table_version() {
+    let mut table = Table::from([("key".into(), i(1))]);
+    let version = table.version();
+    table.insert("key".into(), i(2));
+    assert_eq!(table.version(), version);
+    table.insert("foo".into(), i(3));
+    let inserted = table.version();
+    assert_ne!(inserted, version);
+    assert_eq!(table.remove("bar"), None);
+    assert_eq!(table.version(), inserted);
+    table.remove("foo");
+    assert_ne!(table.version(), inserted);
+    assert_ne!(Table::new().version(), Table::new().version());
+}
+
+#[test]
+fn table_get_cached() {
+    let cache = FieldCache::new();
+    let mut table1 = Table::from([("key".into(), i(1))]);
+    let table2 = Table::from([("key".into(), i(2))]);
+    assert_eq!(table1.get_cached(&"key".into(), &cache), Some(i(1)));
+    assert_eq!(table1.get_cached(&"key".into(), &cache), Some(i(1)));
+    assert_eq!(table1.get_cached(&"foo".into(), &cache), None);
+    assert_eq!(table2.get_cached(&"key".into(), &cache), Some(i(2)));
+
+    assert_eq!(table1.get_cached(&"key".into(), &cache), Some(i(1)));
+    table1.insert("key".into(), i(10));
+    assert_eq!(table1.get_cached(&"key".into(), &cache), Some(i(10)));
+    for n in 0..32 {
+        table1.insert(n.to_string().as_str().into(), i(n));
+    }
+    assert_eq!(table1.get_cached(&"key".into(), &cache), Some(i(10)));
+    table1.remove("key");
+    assert_eq!(table1.get_cached(&"key".into(), &cache), None);
+}
+
+#[test]
+fn table_get_method_cached() {
+    let cache = MethodCache::new();
+    let func = RustFunction::new(2, |_| Ok(Object::Nil));
+    let mut table = Table::new();
+    assert_eq!(table.get_method_cached("__add", &cache), None);
+    table.set_method("__add".into(), func);
+    assert_eq!(
+        table.get_method_cached("__add", &cache),
+        Some(&TableMethod::Native(func))
+    );
+    assert_eq!(table.get_method_cached("__sub", &cache), None);
+}
+
+#[test]
+fn format_spec_parse() {
+    assert_eq!(FormatSpec::parse(""), Ok(FormatSpec::default()));
+    assert!(FormatSpec::parse("*^+08.3e").is_ok());
+    assert!(FormatSpec::parse("<").is_ok());
+    assert_eq!(
+        FormatSpec::parse("8."),
+        Err(FormatSpecParseError::MissingPrecision)
+    );
+    assert_eq!(
+        FormatSpec::parse("99999999"),
+        Err(FormatSpecParseError::TooLargeNumber)
+    );
+    assert_eq!(
+        FormatSpec::parse(">8z"),
+        Err(FormatSpecParseError::UnexpectedChar(2))
+    );
+}
+
+#[test]
+fn format_spec_format() {
+    let format = |spec: &str, object: Object| FormatSpec::parse(spec).unwrap().format(&object);
+    assert_eq!(format("", i(42)), Ok("42".to_string()));
+    assert_eq!(format(">5", i(42)), Ok("   42".to_string()));
+    assert_eq!(format("*^6", i(42)), Ok("**42**".to_string()));
+    assert_eq!(format("05", i(-42)), Ok("-0042".to_string()));
+    assert_eq!(format("+", i(42)), Ok("+42".to_string()));
+    assert_eq!(format("x", i(255)), Ok("ff".to_string()));
+    assert_eq!(format("08b", i(5)), Ok("00000101".to_string()));
+    assert_eq!(format(".2", f(1.23456)), Ok("1.23".to_string()));
+    assert_eq!(format(".1", i(3)), Ok("3.0".to_string()));
+    assert_eq!(format(".2e", f(1234.5)), Ok("1.23e3".to_string()));
+    assert_eq!(format("5", s("ab")), Ok("ab   ".to_string()));
+    assert_eq!(format(".2", s("abc")), Ok("ab".to_string()));
+    assert_eq!(format(">6", b(true)), Ok("  true".to_string()));
+    assert_eq!(
+        format("x", f(1.5)).map_err(|e| e.to_string()),
+        Err("Format type 'x' cannot be applied to the value of type 'float'.".to_string())
+    );
+}
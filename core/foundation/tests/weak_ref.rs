@@ -0,0 +1,167 @@
+use foundation::object::{gc, *};
+
+use mockalloc::Mockalloc;
+
+#[global_allocator]
+static ALLOCATOR: Mockalloc<std::alloc::System> = Mockalloc(std::alloc::System);
+
+#[mockalloc::test]
+fn upgrade() {
+    let table = Table::from([("key".into(), Object::Int(1))]);
+    let weak = WeakRef::from(&table);
+    assert!(weak.is_alive());
+    assert_eq!(weak.upgrade(), Some(Object::Table(table.clone())));
+
+    drop(table);
+    assert!(!weak.is_alive());
+    assert_eq!(weak.upgrade(), None);
+
+    let array = Array::from(vec![Object::Int(1)]);
+    let weak = WeakRef::from(&array);
+    let weak2 = weak.clone();
+    assert_eq!(weak, weak2);
+    drop(weak);
+    assert_eq!(weak2.upgrade(), Some(Object::Array(array.clone())));
+    drop(array);
+    assert_eq!(weak2.upgrade(), None);
+}
+
+#[mockalloc::test]
+fn does_not_keep_alive() {
+    let before = gc::stats();
+    let weak = {
+        let mut table = Table::new();
+        table.insert("array".into(), Object::Array(Array::new()));
+        WeakRef::from(&table)
+    };
+    // The children are freed, and only the empty table is kept until the weak reference is dropped.
+    let stats = gc::stats();
+    assert_eq!(stats.live_arrays, before.live_arrays);
+    assert_eq!(stats.live_tables, before.live_tables + 1);
+    drop(weak);
+    assert_eq!(gc::stats().live_tables, before.live_tables);
+}
+
+#[mockalloc::test]
+fn self_reference() {
+    let mut table = Table::new();
+    table.insert("self".into(), Object::WeakRef((&table).into()));
+    let mut array = Array::new();
+    array.push(Object::WeakRef((&array).into()));
+}
+
+#[mockalloc::test]
+fn garbage_cycle() {
+    // table1 <-> table2, and both of them have weak references to each other.
+    let weak = {
+        let mut table1 = Table::new();
+        let mut table2 = Table::new();
+        table1.insert("table2".into(), Object::Table(table2.clone()));
+        table2.insert("table1".into(), Object::Table(table1.clone()));
+        table1.insert("weak2".into(), Object::WeakRef((&table2).into()));
+        table2.insert("weak1".into(), Object::WeakRef((&table1).into()));
+        WeakRef::from(&table1)
+    };
+    assert_eq!(weak.upgrade(), None);
+
+    gc::set_mode(gc::Mode::Batched { threshold: 100 });
+    let weak = {
+        let mut table1 = Table::new();
+        let mut table2 = Table::new();
+        table1.insert("table2".into(), Object::Table(table2.clone()));
+        table2.insert("table1".into(), Object::Table(table1.clone()));
+        table1.insert("weak2".into(), Object::WeakRef((&table2).into()));
+        WeakRef::from(&table1)
+    };
+    // The cycle is alive until it is collected.
+    assert!(weak.is_alive());
+    assert_eq!(gc::collect(), 2);
+    assert!(!weak.is_alive());
+    gc::set_mode(gc::Mode::Immediate);
+}
+
+#[mockalloc::test]
+fn buffered() {
+    gc::set_mode(gc::Mode::Batched { threshold: 100 });
+    let table = Table::new();
+    drop(table.clone());
+    let weak = WeakRef::from(&table);
+    drop(table);
+    assert!(!weak.is_alive());
+    // The table is released while buffered, and kept by both the root buffer and `weak`.
+    gc::collect();
+    assert!(!weak.is_alive());
+    drop(weak);
+    gc::set_mode(gc::Mode::Immediate);
+}
+
+#[mockalloc::test]
+fn finalized() {
+    let mut table = Table::new();
+    table.set_method("__gc".into(), RustFunction::new(1, |_| Ok(Object::Nil)));
+    let weak = WeakRef::from(&table);
+    let mut cache = Table::with_weak_values();
+    cache.insert("table".into(), Object::Table(table.clone()));
+    drop(table);
+
+    // The queue of the finalizers keeps the table alive, but it is not reachable any more.
+    assert!(!weak.is_alive());
+    assert_eq!(weak.upgrade(), None);
+    assert_eq!(cache.get_upgraded("table"), None);
+
+    // Nor after the finalizer resurrects it.
+    let table = gc::take_finalizable().unwrap();
+    assert_eq!(weak.upgrade(), None);
+    assert!(cache.is_empty());
+    drop(table);
+    assert_eq!(weak.upgrade(), None);
+}
+
+#[mockalloc::test]
+fn weak_values() {
+    let mut cache = Table::with_weak_values();
+    assert!(cache.has_weak_values());
+
+    let value = Table::new();
+    cache.insert("value".into(), Object::Table(value.clone()));
+    cache.insert("int".into(), Object::Int(1));
+    assert!(matches!(cache.get("value"), Some(Object::WeakRef(_))));
+    assert_eq!(
+        cache.get_upgraded("value"),
+        Some(Object::Table(value.clone()))
+    );
+    assert_eq!(cache.get_upgraded("int"), Some(Object::Int(1)));
+
+    drop(value);
+    assert_eq!(cache.get_upgraded("value"), None);
+    assert!(!cache.contains_key("value"));
+    assert_eq!(cache.len(), 1);
+    assert_eq!(format!("{:?}", cache), "{int: 1}");
+
+    // The values referring to the table do not form a cycle.
+    let before = gc::stats();
+    let mut observer = Table::new();
+    observer.insert("cache".into(), Object::Table(cache.clone()));
+    cache.insert("observer".into(), Object::Table(observer));
+    assert_eq!(cache.get_upgraded("observer"), None);
+    assert_eq!(gc::stats().collected, before.collected);
+}
+
+#[mockalloc::test]
+fn weak_values_prune() {
+    let before = gc::stats();
+    let mut cache = Table::with_weak_values();
+    let value = Table::new();
+    cache.insert("value".into(), Object::Table(value.clone()));
+    drop(value);
+    assert!(cache.is_empty());
+    // The freed value is kept allocated by the `WeakRef` in the table until it is removed.
+    assert_eq!(gc::stats().live_tables, before.live_tables + 2);
+
+    for i in 0..16 {
+        cache.insert(format!("int{}", i).as_str().into(), Object::Int(i));
+    }
+    assert_eq!(cache.len(), 16);
+    assert_eq!(unsafe { cache.iter() }.count(), 16);
+    assert_eq!(gc::stats().live_tables, before.live_tables + 1);
+}
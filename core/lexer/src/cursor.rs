@@ -61,6 +61,13 @@ impl<'src> Cursor<'src> {
         self.chars.clone().next()
     }
 
+    /// Peeks the second next symbol from the input stream without consuming it.
+    pub(crate) fn peek2(&self) -> Option<char> {
+        let mut chars = self.chars.clone();
+        chars.next();
+        chars.next()
+    }
+
     pub(crate) fn bump(&mut self, kind: TokenKind) -> Token {
         let current_len = self.chars.as_str().len();
         let len = (self.remaining_len - current_len) as u32;
@@ -8,37 +8,85 @@ use foundation::syntax::token::{TokenKind::*, *};
 
 pub fn tokenize(source: &str) -> impl Iterator<Item = Token> + '_ {
     let mut cursor = Cursor::new(source);
-    core::iter::from_fn(move || advance_token(&mut cursor))
+    let mut interpolations = Vec::new();
+    core::iter::from_fn(move || advance_token(&mut cursor, &mut interpolations))
 }
 
-fn advance_token(cursor: &mut Cursor) -> Option<Token> {
+/// State of the string interpolation currently being lexed.
+struct Interpolation {
+    /// The quote character of the string literal that contains this interpolation.
+    quote: char,
+    /// The number of unclosed `{` in the interpolated expression.
+    brace_depth: u32,
+}
+
+fn advance_token(cursor: &mut Cursor, interpolations: &mut Vec<Interpolation>) -> Option<Token> {
     let kind = match cursor.next()? {
         c if is_whitespace_char(c) => whitespace(cursor),
+        'r' if matches!(cursor.peek(), Some('"' | '\'')) => raw_string(cursor),
         c if is_ident_start_char(c) => ident_or_keyword(cursor, c),
         c @ '0'..='9' => number(cursor, c),
-        c @ ('"' | '\'') => string(cursor, c),
+        c @ ('"' | '\'') => string(cursor, c, interpolations),
         '#' => comment(cursor),
-        '+' => Plus,
-        '-' => symbol2(Minus, cursor, [('>', Arrow)]),
-        '*' => Star,
-        '/' => Slash,
-        '%' => Percent,
-        '&' => Amp,
-        '|' => Pipe,
-        '^' => Caret,
+        '+' => symbol2(Plus, cursor, [('=', PlusEq)]),
+        '-' => symbol2(Minus, cursor, [('>', Arrow), ('=', MinusEq)]),
+        '*' => symbol2(Star, cursor, [('=', StarEq)]),
+        '/' => symbol2(Slash, cursor, [('=', SlashEq)]),
+        '%' => symbol2(Percent, cursor, [('=', PercentEq)]),
+        '&' => symbol2(Amp, cursor, [('=', AmpEq)]),
+        '|' => symbol2(Pipe, cursor, [('=', PipeEq)]),
+        '^' => symbol2(Caret, cursor, [('=', CaretEq)]),
         '~' => Tilde,
         '!' => symbol2(Bang, cursor, [('=', BangEq)]),
         '=' => symbol2(Eq, cursor, [('=', Eq2)]),
-        '<' => symbol2(Lt, cursor, [('=', LtEq), ('<', Lt2)]),
-        '>' => symbol2(Gt, cursor, [('=', GtEq), ('>', Gt2)]),
-        '.' => symbol2(Dot, cursor, [('.', Dot2)]),
+        '<' => match symbol2(Lt, cursor, [('=', LtEq), ('<', Lt2)]) {
+            Lt2 => symbol2(Lt2, cursor, [('=', Lt2Eq)]),
+            kind => kind,
+        },
+        '>' => match symbol2(Gt, cursor, [('=', GtEq), ('>', Gt2)]) {
+            Gt2 => symbol2(Gt2, cursor, [('=', Gt2Eq)]),
+            kind => kind,
+        },
+        '.' => match symbol2(Dot, cursor, [('.', Dot2)]) {
+            Dot2 => symbol2(Dot2, cursor, [('=', Dot2Eq)]),
+            kind => kind,
+        },
         '@' => At,
+        '?' => match (cursor.peek(), cursor.peek2()) {
+            (Some('-'), Some('>')) => {
+                cursor.next();
+                cursor.next();
+                QuestionArrow
+            }
+            _ => symbol2(Unknown, cursor, [('?', Question2), ('.', QuestionDot)]),
+        },
         ',' => Comma,
-        ':' => Colon,
+        ':' => match interpolations.last() {
+            Some(Interpolation { brace_depth: 0, .. }) => format_spec(cursor),
+            _ => Colon,
+        },
         '(' => OpenParen,
         ')' => CloseParen,
-        '{' => OpenBrace,
-        '}' => CloseBrace,
+        '{' => {
+            if let Some(interpolation) = interpolations.last_mut() {
+                interpolation.brace_depth += 1;
+            }
+            OpenBrace
+        }
+        '}' => match interpolations.last_mut() {
+            Some(Interpolation {
+                quote,
+                brace_depth: 0,
+            }) => {
+                let quote = *quote;
+                string_continue(cursor, quote, interpolations)
+            }
+            Some(interpolation) => {
+                interpolation.brace_depth -= 1;
+                CloseBrace
+            }
+            None => CloseBrace,
+        },
         '[' => OpenBracket,
         ']' => CloseBracket,
         c if is_emoji_char(c) => invalid_ident(cursor),
@@ -154,8 +202,19 @@ fn ident_or_keyword(cursor: &mut Cursor, first_char: char) -> TokenKind {
                 }
                 _ => None,
             },
+            'm' => next_if_s(cursor, &['a', 't', 'c', 'h'], Match),
             'v' => next_if_s(cursor, &['a', 'r'], Var),
-            'w' => next_if_s(cursor, &['h', 'i', 'l', 'e'], While),
+            'w' => match cursor.peek()? {
+                'h' => {
+                    cursor.next();
+                    next_if_s(cursor, &['i', 'l', 'e'], While)
+                }
+                'i' => {
+                    cursor.next();
+                    next_if_s(cursor, &['t', 'h'], With)
+                }
+                _ => None,
+            },
             _ => None,
         };
         if pre_match.is_some() {
@@ -299,34 +358,130 @@ fn number(cursor: &mut Cursor, first_digit: char) -> TokenKind {
     }
 }
 
-fn string(cursor: &mut Cursor, quote: char) -> TokenKind {
+fn string(cursor: &mut Cursor, quote: char, interpolations: &mut Vec<Interpolation>) -> TokenKind {
     debug_assert!((quote == '"' || quote == '\'') && cursor.prev() == quote);
-    let mut terminated = false;
+    let quote_kind = match quote {
+        '"' => QuoteKind::Double,
+        '\'' => QuoteKind::Single,
+        _ => unreachable!(),
+    };
+    match string_body(cursor, quote) {
+        StringBodyEnd::Quote => String {
+            terminated: true,
+            quote_kind,
+        },
+        StringBodyEnd::Eof => String {
+            terminated: false,
+            quote_kind,
+        },
+        StringBodyEnd::OpenBrace => {
+            interpolations.push(Interpolation {
+                quote,
+                brace_depth: 0,
+            });
+            StringStart { quote_kind }
+        }
+    }
+}
+
+/// Lexes the rest of the interpolated string literal after the `}` that closes an interpolation.
+fn string_continue(
+    cursor: &mut Cursor,
+    quote: char,
+    interpolations: &mut Vec<Interpolation>,
+) -> TokenKind {
+    debug_assert!(cursor.prev() == '}');
+    let quote_kind = match quote {
+        '"' => QuoteKind::Double,
+        '\'' => QuoteKind::Single,
+        _ => unreachable!(),
+    };
+    match string_body(cursor, quote) {
+        StringBodyEnd::Quote => {
+            interpolations.pop();
+            StringEnd {
+                terminated: true,
+                quote_kind,
+            }
+        }
+        StringBodyEnd::Eof => {
+            interpolations.pop();
+            StringEnd {
+                terminated: false,
+                quote_kind,
+            }
+        }
+        StringBodyEnd::OpenBrace => StringMid,
+    }
+}
+
+enum StringBodyEnd {
+    /// Reached the closing quote.
+    Quote,
+    /// Reached the `{` that starts an interpolation.
+    OpenBrace,
+    /// Reached the end of the input.
+    Eof,
+}
+
+fn string_body(cursor: &mut Cursor, quote: char) -> StringBodyEnd {
     while let Some(c) = cursor.next() {
         match c {
-            '\\' => {
-                let peek = cursor.peek();
-                if peek == Some(quote) || peek == Some('\\') {
+            // The escape sequences are validated in the lowering, here only skips the characters
+            // that must not be treated as the end of string or the start of interpolation.
+            '\\' => match cursor.peek() {
+                Some('\\') => {
                     cursor.next();
                 }
+                Some(q) if q == quote => {
+                    cursor.next();
+                }
+                Some('u') => {
+                    cursor.next();
+                    if cursor.peek() == Some('{') {
+                        cursor.next();
+                        cursor.eat_while(|c| c.is_ascii_hexdigit());
+                        if cursor.peek() == Some('}') {
+                            cursor.next();
+                        }
+                    }
+                }
+                _ => {}
+            },
+            // "{{" and "}}" are the escaped braces.
+            '{' | '}' if cursor.peek() == Some(c) => {
+                cursor.next();
             }
-            q if q == quote => {
-                terminated = true;
-                break;
-            }
+            '{' => return StringBodyEnd::OpenBrace,
+            q if q == quote => return StringBodyEnd::Quote,
             _ => {}
         }
     }
-    String {
+    StringBodyEnd::Eof
+}
+
+fn raw_string(cursor: &mut Cursor) -> TokenKind {
+    debug_assert!(cursor.prev() == 'r');
+    let quote = cursor.next().unwrap();
+    let quote_kind = match quote {
+        '"' => QuoteKind::Double,
+        '\'' => QuoteKind::Single,
+        _ => unreachable!(),
+    };
+    cursor.eat_while(|c| c != quote);
+    let terminated = cursor.next().is_some();
+    RawString {
         terminated,
-        quote_kind: match quote {
-            '"' => QuoteKind::Double,
-            '\'' => QuoteKind::Single,
-            _ => unreachable!(),
-        },
+        quote_kind,
     }
 }
 
+fn format_spec(cursor: &mut Cursor) -> TokenKind {
+    debug_assert!(cursor.prev() == ':');
+    cursor.eat_while(|c| !matches!(c, '}' | '"' | '\'' | '\n'));
+    FormatSpec
+}
+
 fn comment(cursor: &mut Cursor) -> TokenKind {
     debug_assert!(cursor.prev() == '#');
     cursor.eat_while(|c| c != '\n');
@@ -105,3 +105,73 @@ fn keyword_like() {
     assert_token!("no ", [Ident { 2 }, Whitespace { 1 }]);
     assert_token!("n@", [Ident { 1 }, At { 1 }]);
 }
+
+#[test]
+fn interpolated_string() {
+    assert_token!(
+        r#""a{x}b""#,
+        [
+            StringStart {
+                3,
+                quote_kind: QuoteKind::Double,
+            },
+            Ident { 1 },
+            StringEnd {
+                3,
+                terminated: true,
+                quote_kind: QuoteKind::Double,
+            }
+        ]
+    );
+    assert_token!(
+        r#"'{x:>8.2} {y}'"#,
+        [
+            StringStart {
+                2,
+                quote_kind: QuoteKind::Single,
+            },
+            Ident { 1 },
+            FormatSpec { 5 },
+            StringMid { 3 },
+            Ident { 1 },
+            StringEnd {
+                2,
+                terminated: true,
+                quote_kind: QuoteKind::Single,
+            }
+        ]
+    );
+    assert_token!(
+        r#""{{x}}""#,
+        [String {
+            7,
+            terminated: true,
+            quote_kind: QuoteKind::Double,
+        }]
+    );
+    assert_token!(
+        r#""{ {}["a"] }"#,
+        [
+            StringStart {
+                2,
+                quote_kind: QuoteKind::Double,
+            },
+            Whitespace { 1 },
+            OpenBrace { 1 },
+            CloseBrace { 1 },
+            OpenBracket { 1 },
+            String {
+                3,
+                terminated: true,
+                quote_kind: QuoteKind::Double,
+            },
+            CloseBracket { 1 },
+            Whitespace { 1 },
+            StringEnd {
+                1,
+                terminated: false,
+                quote_kind: QuoteKind::Double,
+            }
+        ]
+    );
+}
@@ -113,6 +113,55 @@ fn string_escaped() {
     );
 }
 
+#[test]
+fn string_escaped_unicode() {
+    assert_token!(
+        r#""\u{7B}\n""#,
+        [String {
+            10,
+            terminated: true,
+            quote_kind: QuoteKind::Double,
+        }]
+    );
+    assert_token!(
+        "'multi\nline'",
+        [String {
+            12,
+            terminated: true,
+            quote_kind: QuoteKind::Single,
+        }]
+    );
+}
+
+#[test]
+fn raw_string() {
+    assert_token!(
+        r#"r"\d{2}\""#,
+        [RawString {
+            9,
+            terminated: true,
+            quote_kind: QuoteKind::Double,
+        }]
+    );
+    assert_token!(
+        r#"r'say "hi"'"#,
+        [RawString {
+            11,
+            terminated: true,
+            quote_kind: QuoteKind::Single,
+        }]
+    );
+    assert_token!(
+        r#"r"foo"#,
+        [RawString {
+            5,
+            terminated: false,
+            quote_kind: QuoteKind::Double,
+        }]
+    );
+    assert_token!("r", [Ident { 1 }]);
+}
+
 #[test]
 fn bool() {
     assert_token!("true", [True { 4 }]);
@@ -140,6 +189,8 @@ fn keyword() {
     assert_token!("return", [Return { 6 }]);
     assert_token!("break", [Break { 5 }]);
     assert_token!("continue", [Continue { 8 }]);
+    assert_token!("match", [Match { 5 }]);
+    assert_token!("with", [With { 4 }]);
     assert_token!("and", [And { 3 }]);
     assert_token!("or", [Or { 2 }]);
     assert_token!("not", [Not { 3 }]);
@@ -178,6 +229,20 @@ fn symbol() {
     assert_token!(">>", [Gt2 { 2 }]);
     assert_token!(">=", [GtEq { 2 }]);
     assert_token!("..", [Dot2 { 2 }]);
+    assert_token!("+=", [PlusEq { 2 }]);
+    assert_token!("-=", [MinusEq { 2 }]);
+    assert_token!("*=", [StarEq { 2 }]);
+    assert_token!("/=", [SlashEq { 2 }]);
+    assert_token!("%=", [PercentEq { 2 }]);
+    assert_token!("&=", [AmpEq { 2 }]);
+    assert_token!("|=", [PipeEq { 2 }]);
+    assert_token!("^=", [CaretEq { 2 }]);
+    assert_token!("<<=", [Lt2Eq { 3 }]);
+    assert_token!(">>=", [Gt2Eq { 3 }]);
+    assert_token!("..=", [Dot2Eq { 3 }]);
+    assert_token!("??", [Question2 { 2 }]);
+    assert_token!("?.", [QuestionDot { 2 }]);
+    assert_token!("?->", [QuestionArrow { 3 }]);
 }
 
 #[test]
@@ -3,6 +3,7 @@ use foundation::syntax::{SyntaxKind, SyntaxKind::*, T};
 
 mod atom;
 mod expression;
+mod pattern;
 mod statement;
 mod util;
 
@@ -1,7 +1,7 @@
 use super::*;
 
 pub(super) const LITERA_FIRST: TokenSet =
-    TokenSet::new(&[INT, FLOAT, STRING, T![true], T![false], T![nil]]);
+    TokenSet::new(&[INT, FLOAT, STRING, RAW_STRING, T![true], T![false], T![nil]]);
 
 /// Precondition: `assert!(p.at_ts(LITERA_FIRST))`
 pub(super) fn literal(p: &mut Parser) -> CompletedMarker {
@@ -11,6 +11,53 @@ pub(super) fn literal(p: &mut Parser) -> CompletedMarker {
     m.complete(p, LITERAL)
 }
 
+/// Precondition: `assert!(p.at(T![string_start]))`
+pub(super) fn interpolated_string(p: &mut Parser) -> CompletedMarker {
+    let m = p.start();
+    p.bump(T![string_start]);
+    loop {
+        interpolation(p);
+        match p.current() {
+            Some(T![string_mid]) => p.bump(T![string_mid]),
+            Some(T![string_end]) => {
+                p.bump(T![string_end]);
+                break;
+            }
+            _ => {
+                p.error_with(|p| {
+                    const NEXT_FIRST: TokenSet = TokenSet::new(&[T![string_mid], T![string_end]]);
+                    let m = p.start();
+                    util::skip_while_st(p, NEXT_FIRST);
+                    m.complete(p, ERROR);
+                    "Expected closing '}' of the interpolation. Use `{{` for a literal '{'"
+                });
+                match p.current() {
+                    Some(T![string_mid]) => p.bump(T![string_mid]),
+                    Some(T![string_end]) => {
+                        p.bump(T![string_end]);
+                        break;
+                    }
+                    _ => break,
+                }
+            }
+        }
+    }
+    m.complete(p, INTERPOLATED_STRING)
+}
+
+fn interpolation(p: &mut Parser) {
+    let m = p.start();
+    p.eat_trivia();
+    if p.at_ts(expression::EXPR_FIRST) {
+        expression::expr(p);
+    } else {
+        p.error("Missing <expr>");
+    }
+    p.eat_trivia();
+    p.eat(T![format_spec]);
+    m.complete(p, INTERPOLATION);
+}
+
 /// Precondition: `assert!(p.at(T!['[']))`
 pub(super) fn array_const(p: &mut Parser) -> CompletedMarker {
     let m = p.start();
@@ -92,6 +139,19 @@ pub(super) fn table_const(p: &mut Parser) -> CompletedMarker {
 
     p.eat_trivia();
 
+    if !p.eat(T!['}']) {
+        p.error_with(|p| {
+            const NEXT_FIRST: TokenSet = statement::STMT_FIRST.unions(&[T!['}']]);
+            if p.at_ts(NEXT_FIRST) {
+                "Missing closing '}'"
+            } else {
+                let m = p.start();
+                util::skip_while_st(p, NEXT_FIRST);
+                m.complete(p, ERROR);
+                "Expected closing '}'"
+            }
+        });
+    }
     m.complete(p, TABLE_CONST)
 }
 
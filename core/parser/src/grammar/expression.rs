@@ -26,6 +26,9 @@ fn expr_bp(p: &mut Parser, min_bp: u8) -> Option<CompletedMarker> {
         let Some(current) = p.current() else {
             break;
         };
+        if p.at_match_arm_separator() {
+            break;
+        }
         let m;
         let r_bp = match infix_op_binding_power(current) {
             Some((l_bp, r_bp)) => {
@@ -81,10 +84,10 @@ fn lhs(p: &mut Parser) -> CompletedMarker {
             while let Some(current) = p.current() {
                 // postfix-op
                 lhs = match current {
-                    T![.] => dot_expr(p, lhs),
+                    T![.] | T![?.] => dot_expr(p, lhs),
                     T!['['] => index_expr(p, lhs),
                     T!['('] => call_expr(p, lhs),
-                    T![->] => method_call_expr(p, lhs),
+                    T![->] | T![?->] => method_call_expr(p, lhs),
                     _ => break,
                 };
             }
@@ -95,20 +98,22 @@ fn lhs(p: &mut Parser) -> CompletedMarker {
 
 /// |        Precedence        | Associativity |     Operators     |
 /// | -----------------------  | ------------- | ----------------- |
-/// | 13: Unary Postfix        |    postfix    | .x, [], (), ->x() |
-/// | 12: Unary Prefix         |    prefix     | +, -, not         |
-/// | 11: Multiplicative       |   left infix  | *, /, %           |
-/// | 10: Additive             |   left infix  | +, -              |
-/// |  9: String concatenation |  right infix  | ..                |
-/// |  8: Shift                |   left infix  | <<, >>            |
-/// |  7: Bitwise-AND          |   left infix  | &                 |
-/// |  6: Bitwise-XOR          |   left infix  | ^                 |
-/// |  5: Bitwise-OR           |   left infix  | |                 |
+/// | 14: Unary Postfix        |    postfix    | .x, [], (), ->x() |
+/// |                          |               | ?.x, ?->x()       |
+/// | 13: Unary Prefix         |    prefix     | +, -, not         |
+/// | 12: Multiplicative       |   left infix  | *, /, %           |
+/// | 11: Additive             |   left infix  | +, -              |
+/// | 10: String concatenation |  right infix  | ..                |
+/// |  9: Shift                |   left infix  | <<, >>            |
+/// |  8: Bitwise-AND          |   left infix  | &                 |
+/// |  7: Bitwise-XOR          |   left infix  | ^                 |
+/// |  6: Bitwise-OR           |   left infix  | |                 |
+/// |  5: Nil-coalescing       |  right infix  | ??                |
 /// |  4: Relational           |   left infix  | <, <=, >, >=      |
 /// |  3: Equality             |   left infix  | ==, !=            |
 /// |  2: Logical-AND          |   left infix  | and               |
 /// |  1: Logical-OR           |   left infix  | or                |
-/// |  0: Assignment           |   right infix | =                 |
+/// |  0: Assignment           |   right infix | =, +=, -=, ..=, … |
 const fn infix_op_binding_power(kind: SyntaxKind) -> Option<(u8, u8)> {
     const fn left(precedence: u8) -> (u8, u8) {
         (2 * precedence + 1, 2 * precedence + 2)
@@ -118,31 +123,35 @@ const fn infix_op_binding_power(kind: SyntaxKind) -> Option<(u8, u8)> {
     }
     #[rustfmt::skip]
     let bp = match kind {
-        T![*] | T![/] | T![%]           => left(11),
-        T![+] | T![-]                   => left(10),
-        T![..]                          => right(9),
-        T![<<] | T![>>]                 => left(8),
-        T![&]                           => left(7),
-        T![^]                           => left(6),
-        T![|]                           => left(5),
+        T![*] | T![/] | T![%]           => left(12),
+        T![+] | T![-]                   => left(11),
+        T![..]                          => right(10),
+        T![<<] | T![>>]                 => left(9),
+        T![&]                           => left(8),
+        T![^]                           => left(7),
+        T![|]                           => left(6),
+        T![??]                          => right(5),
         T![<] | T![<=] | T![>] | T![>=] => left(4),
         T![==] | T![!=]                 => left(3),
         T![and]                         => left(2),
         T![or]                          => left(1),
-        T![=]                           => right(0),
+        T![=] | T![+=] | T![-=] | T![*=] | T![/=] | T![%=] |
+        T![&=] | T![|=] | T![^=] | T![<<=] | T![>>=] | T![..=] => right(0),
         _ => return None,
     };
     Some(bp)
 }
 
 const ATOM_EXPR_FIRST: TokenSet = atom::LITERA_FIRST.unions(&[
-    T![do],   // do_expr
-    T![if],   // if_expr
-    T!['('],  // paren_expr
-    T!['{'],  // atom::table_const
-    T!['['],  // atom::array_const
-    T![func], // atom::func_const
-    IDENT,    // atom::local_var
+    T![string_start], // atom::interpolated_string
+    T![do],           // do_expr
+    T![if],           // if_expr
+    T![match],        // match_expr
+    T!['('],          // paren_expr
+    T!['{'],          // atom::table_const
+    T!['['],          // atom::array_const
+    T![func],         // atom::func_const
+    IDENT,            // atom::local_var
 ]);
 
 fn atom_expr(p: &mut Parser) -> CompletedMarker {
@@ -152,24 +161,28 @@ fn atom_expr(p: &mut Parser) -> CompletedMarker {
     match unsafe { p.current().unwrap_unchecked() } {
         T![do] => do_expr(p),
         T![if] => if_expr(p),
+        T![match] => match_expr(p),
         T!['('] => paren_expr(p),
         T!['{'] => atom::table_const(p),
         T!['['] => atom::array_const(p),
         T![func] => atom::func_const(p),
         IDENT => atom::local_var(p),
+        T![string_start] => atom::interpolated_string(p),
         c if atom::LITERA_FIRST.contains(c) => atom::literal(p),
         _ => unreachable!(),
     }
 }
 
+/// Precondition: `assert!(p.at(T![.]) || p.at(T![?.]))`
 fn dot_expr(p: &mut Parser, lhs: CompletedMarker) -> CompletedMarker {
     let m = lhs.precede(p);
-    p.bump(T![.]);
+    let dot = if p.at(T![?.]) { "?." } else { "." };
+    p.bump_any();
     p.eat_trivia();
     if p.at(IDENT) {
         name(p);
     } else {
-        p.error("Expected <name> after `.`")
+        p.error(format!("Expected <name> after `{}`", dot))
     }
     m.complete(p, FIELD_EXPR)
 }
@@ -180,7 +193,7 @@ fn index_expr(p: &mut Parser, lhs: CompletedMarker) -> CompletedMarker {
     p.eat_trivia();
     expr(p);
     p.eat_trivia();
-    if !p.at(T![']']) {
+    if !p.eat(T![']']) {
         p.error("Expected `]` to close index expression");
     }
     m.complete(p, INDEX_EXPR)
@@ -193,14 +206,16 @@ fn call_expr(p: &mut Parser, lhs: CompletedMarker) -> CompletedMarker {
     m.complete(p, CALL_EXPR)
 }
 
+/// Precondition: `assert!(p.at(T![->]) || p.at(T![?->]))`
 fn method_call_expr(p: &mut Parser, lhs: CompletedMarker) -> CompletedMarker {
     let m = lhs.precede(p);
-    p.bump(T![->]);
+    let arrow = if p.at(T![?->]) { "?->" } else { "->" };
+    p.bump_any();
     p.eat_trivia();
     if p.at(IDENT) {
         name(p);
     } else {
-        p.error("Expected <name> after `->`")
+        p.error(format!("Expected <name> after `{}`", arrow))
     }
     p.eat_trivia();
     if p.at(T!['(']) {
@@ -254,6 +269,35 @@ fn if_expr(p: &mut Parser) -> CompletedMarker {
     m.complete(p, IF_EXPR)
 }
 
+fn match_expr(p: &mut Parser) -> CompletedMarker {
+    let m = p.start();
+    p.bump(T![match]);
+    p.eat_trivia();
+    let outer = p.set_match_arm(false);
+    if p.at_ts(EXPR_FIRST) {
+        expr(p);
+    } else {
+        p.error("Expected <expr>");
+    }
+    p.eat_trivia();
+    if !p.eat(T![with]) {
+        p.error("Missing 'with' keyword");
+    }
+    p.set_match_arm(true);
+    loop {
+        p.eat_trivia();
+        if !p.at(T![|]) {
+            break;
+        }
+        match_arm(p);
+    }
+    p.restore_match_arm(outer);
+    if !p.eat(T![end]) {
+        p.error("Missing 'end' keyword");
+    }
+    m.complete(p, MATCH_EXPR)
+}
+
 fn paren_expr(p: &mut Parser) -> CompletedMarker {
     let m = p.start();
     p.bump(T!['(']);
@@ -270,6 +314,27 @@ fn paren_expr(p: &mut Parser) -> CompletedMarker {
     m.complete(p, PAREN_EXPR)
 }
 
+/// Precondition: `assert!(p.at(T![|]))`
+fn match_arm(p: &mut Parser) {
+    let m = p.start();
+    p.bump(T![|]);
+    p.eat_trivia();
+    if p.at_ts(pattern::PATTERN_FIRST) {
+        pattern::pattern(p);
+    } else {
+        p.error("Expected <pattern>");
+    }
+    p.eat_trivia();
+    if !p.eat(T![then]) {
+        p.error("Missing 'then' keyword");
+    }
+    let m_body = p.start();
+    util::loop_stmt_until_st(p, TokenSet::new(&[T![end], T![|]]));
+    p.eat_trivia();
+    m_body.complete(p, PROGRAM);
+    m.complete(p, MATCH_ARM);
+}
+
 fn elif_branch(p: &mut Parser) {
     let m = p.start();
     p.bump(T![elif]);
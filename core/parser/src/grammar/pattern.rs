@@ -0,0 +1,148 @@
+use super::*;
+
+pub(super) const PATTERN_FIRST: TokenSet = atom::LITERA_FIRST.unions(&[
+    T![-],   // literal_pat
+    IDENT,   // binding_pat
+    T!['{'], // table_pat
+    T!['['], // array_pat
+]);
+
+/// Precondition: `assert!(p.at_ts(PATTERN_FIRST))`
+pub(super) fn pattern(p: &mut Parser) -> CompletedMarker {
+    assert!(p.at_ts(PATTERN_FIRST));
+
+    // SAFETY: `p.at_ts(..)` is true, so `p.current()` is not None.
+    match unsafe { p.current().unwrap_unchecked() } {
+        IDENT => binding_pat(p),
+        T!['{'] => table_pat(p),
+        T!['['] => array_pat(p),
+        _ => literal_pat(p),
+    }
+}
+
+fn literal_pat(p: &mut Parser) -> CompletedMarker {
+    let m = p.start();
+    if p.eat(T![-]) {
+        p.eat_trivia();
+        if p.at(INT) || p.at(FLOAT) {
+            atom::literal(p);
+        } else {
+            p.error("Expected number literal after '-'");
+        }
+    } else {
+        atom::literal(p);
+    }
+    m.complete(p, LITERAL_PAT)
+}
+
+fn binding_pat(p: &mut Parser) -> CompletedMarker {
+    let m = p.start();
+    p.bump(IDENT);
+    m.complete(p, BINDING_PAT)
+}
+
+fn table_pat(p: &mut Parser) -> CompletedMarker {
+    let m = p.start();
+    p.bump(T!['{']);
+
+    p.eat_trivia();
+
+    while p.current().map(|t| t != T!['}']).unwrap_or(false) {
+        if p.at(T![,]) {
+            p.error("Missing <field>");
+            p.bump(T![,]);
+            p.eat_trivia();
+            continue;
+        }
+        if p.at(IDENT) {
+            table_pat_field(p);
+        } else {
+            break;
+        }
+        p.eat_trivia();
+        if !p.eat(T![,]) {
+            if p.at(IDENT) {
+                p.error("Missing ','");
+            } else {
+                break;
+            }
+        }
+        p.eat_trivia();
+    }
+
+    if !p.eat(T!['}']) {
+        p.error_with(|p| {
+            const NEXT_FIRST: TokenSet = TokenSet::new(&[T!['}'], T![then], T![|], T![end]]);
+            if p.at_ts(NEXT_FIRST) {
+                return "Missing closing '}'";
+            }
+            let m = p.start();
+            util::skip_while_st(p, NEXT_FIRST);
+            m.complete(p, ERROR);
+            p.eat(T!['}']);
+            "Expected closing '}'"
+        });
+    }
+    m.complete(p, TABLE_PAT)
+}
+
+/// Precondition: `assert!(p.at(IDENT))`
+fn table_pat_field(p: &mut Parser) {
+    let m = p.start();
+    name(p);
+    p.eat_trivia();
+    if p.eat(T![=]) {
+        p.eat_trivia();
+        if p.at_ts(PATTERN_FIRST) {
+            pattern(p);
+        } else {
+            p.error("Missing <pattern>");
+        }
+    }
+    m.complete(p, TABLE_PAT_FIELD);
+}
+
+fn array_pat(p: &mut Parser) -> CompletedMarker {
+    let m = p.start();
+    p.bump(T!['[']);
+
+    p.eat_trivia();
+
+    while p.current().map(|t| t != T![']']).unwrap_or(false) {
+        if p.at(T![,]) {
+            p.error("Missing <pattern>");
+            p.bump(T![,]);
+            p.eat_trivia();
+            continue;
+        }
+        if p.at_ts(PATTERN_FIRST) {
+            pattern(p);
+        } else {
+            break;
+        }
+        p.eat_trivia();
+        if !p.eat(T![,]) {
+            if p.at_ts(PATTERN_FIRST) {
+                p.error("Missing ','");
+            } else {
+                break;
+            }
+        }
+        p.eat_trivia();
+    }
+
+    if !p.eat(T![']']) {
+        p.error_with(|p| {
+            const NEXT_FIRST: TokenSet = TokenSet::new(&[T![']'], T![then], T![|], T![end]]);
+            if p.at_ts(NEXT_FIRST) {
+                return "Missing closing ']'";
+            }
+            let m = p.start();
+            util::skip_while_st(p, NEXT_FIRST);
+            m.complete(p, ERROR);
+            p.eat(T![']']);
+            "Expected closing ']'"
+        });
+    }
+    m.complete(p, ARRAY_PAT)
+}
@@ -54,6 +54,9 @@ fn build_tree(
             let end = self.offsets[self.index + 1];
             (start, end)
         }
+        fn position(&self) -> u32 {
+            self.offsets[self.index]
+        }
         fn next(&mut self) -> (u32, u32) {
             let (start, end) = self.current();
             self.index += 1;
@@ -110,19 +113,19 @@ fn build_tree(
             }
             Event::None => {}
             Event::EmptyError { message } => {
-                let (start, _) = offset_range.next();
+                let start = offset_range.position();
                 let range = TextRange::empty(start.into());
                 errors.push(SyntaxError::new(message, range));
             }
             Event::StartError => {
-                let (start, _) = offset_range.current();
+                let start = offset_range.position();
                 range_error_start = Some(start);
             }
             Event::FinishError { message } => {
                 let Some(start) = range_error_start.take() else {
                     unreachable!();
                 };
-                let (_, end) = offset_range.next();
+                let end = offset_range.position();
                 let range = TextRange::new(start.into(), end.into());
                 errors.push(SyntaxError::new(message, range));
             }
@@ -157,17 +160,31 @@ fn convert_kind(kind: token::TokenKind) -> (SyntaxKind, Option<&'static str>) {
             quote_kind,
         } => {
             if !terminated {
-                error = Some(match quote_kind {
-                    token::QuoteKind::Single => {
-                        "Missing trailing `'` symbol to terminate the string literal"
-                    }
-                    token::QuoteKind::Double => {
-                        "Missing trailing `\"` symbol to terminate the string literal"
-                    }
-                });
+                error = Some(unterminated_string_message(quote_kind));
             }
             T![string]
         }
+        token::TokenKind::RawString {
+            terminated,
+            quote_kind,
+        } => {
+            if !terminated {
+                error = Some(unterminated_string_message(quote_kind));
+            }
+            T![raw_string]
+        }
+        token::TokenKind::StringStart { quote_kind: _ } => T![string_start],
+        token::TokenKind::StringMid => T![string_mid],
+        token::TokenKind::StringEnd {
+            terminated,
+            quote_kind,
+        } => {
+            if !terminated {
+                error = Some(unterminated_string_message(quote_kind));
+            }
+            T![string_end]
+        }
+        token::TokenKind::FormatSpec => T![format_spec],
         token::TokenKind::True => T![true],
         token::TokenKind::False => T![false],
         token::TokenKind::Nil => T![nil],
@@ -185,6 +202,8 @@ fn convert_kind(kind: token::TokenKind) -> (SyntaxKind, Option<&'static str>) {
         token::TokenKind::Return => T![return],
         token::TokenKind::Break => T![break],
         token::TokenKind::Continue => T![continue],
+        token::TokenKind::Match => T![match],
+        token::TokenKind::With => T![with],
         token::TokenKind::And => T![and],
         token::TokenKind::Or => T![or],
         token::TokenKind::Not => T![not],
@@ -219,6 +238,20 @@ fn convert_kind(kind: token::TokenKind) -> (SyntaxKind, Option<&'static str>) {
         token::TokenKind::Gt2 => T![>>],
         token::TokenKind::GtEq => T![>=],
         token::TokenKind::Dot2 => T![..],
+        token::TokenKind::Question2 => T![??],
+        token::TokenKind::QuestionDot => T![?.],
+        token::TokenKind::QuestionArrow => T![?->],
+        token::TokenKind::PlusEq => T![+=],
+        token::TokenKind::MinusEq => T![-=],
+        token::TokenKind::StarEq => T![*=],
+        token::TokenKind::SlashEq => T![/=],
+        token::TokenKind::PercentEq => T![%=],
+        token::TokenKind::AmpEq => T![&=],
+        token::TokenKind::PipeEq => T![|=],
+        token::TokenKind::CaretEq => T![^=],
+        token::TokenKind::Lt2Eq => T![<<=],
+        token::TokenKind::Gt2Eq => T![>>=],
+        token::TokenKind::Dot2Eq => T![..=],
         token::TokenKind::Ident => T![ident],
         token::TokenKind::InvalidIdent => {
             error = Some("Identifiers contains invalid characters");
@@ -228,3 +261,10 @@ fn convert_kind(kind: token::TokenKind) -> (SyntaxKind, Option<&'static str>) {
     };
     (syntax_kind, error)
 }
+
+fn unterminated_string_message(quote_kind: token::QuoteKind) -> &'static str {
+    match quote_kind {
+        token::QuoteKind::Single => "Missing trailing `'` symbol to terminate the string literal",
+        token::QuoteKind::Double => "Missing trailing `\"` symbol to terminate the string literal",
+    }
+}
@@ -1,6 +1,6 @@
 use crate::token_set::TokenSet;
 use core::{mem, num::NonZeroU32};
-use foundation::syntax::SyntaxKind;
+use foundation::syntax::{SyntaxKind, T};
 use std::borrow::Cow;
 
 pub(crate) struct Parser {
@@ -8,6 +8,8 @@ pub(crate) struct Parser {
     pos: usize,
     events: Vec<EventRaw>,
     errors: Vec<Cow<'static, str>>,
+    bracket_depth: u32,
+    match_arm_depth: Option<u32>,
 }
 
 impl Parser {
@@ -17,6 +19,8 @@ impl Parser {
             pos: 0,
             events: Vec::new(),
             errors: Vec::new(),
+            bracket_depth: 0,
+            match_arm_depth: None,
         }
     }
 
@@ -43,6 +47,24 @@ impl Parser {
         self.input.get(self.pos + n) == Some(&kind)
     }
 
+    /// Returns `true` if the current token is `|` separating match arms, not the bit-or operator.
+    ///
+    /// In the body of a match arm, `|` is the arm separator unless it is enclosed in brackets.
+    pub(crate) fn at_match_arm_separator(&self) -> bool {
+        self.at(T![|]) && self.match_arm_depth == Some(self.bracket_depth)
+    }
+
+    /// Starts (`true`) or stops (`false`) treating `|` at the current bracket depth as the match
+    /// arm separator, and returns the previous state to be passed to `restore_match_arm`.
+    pub(crate) fn set_match_arm(&mut self, enabled: bool) -> Option<u32> {
+        let depth = enabled.then_some(self.bracket_depth);
+        mem::replace(&mut self.match_arm_depth, depth)
+    }
+
+    pub(crate) fn restore_match_arm(&mut self, state: Option<u32>) {
+        self.match_arm_depth = state;
+    }
+
     pub(crate) fn start(&mut self) -> Marker {
         let pos = self.events.len() as u32;
         self.events.push(EventRaw::Tombstone);
@@ -102,6 +124,13 @@ impl Parser {
     }
 
     fn _push_token(&mut self, kind: SyntaxKind) {
+        match kind {
+            T!['('] | T!['['] | T!['{'] => self.bracket_depth += 1,
+            T![')'] | T![']'] | T!['}'] => {
+                self.bracket_depth = self.bracket_depth.saturating_sub(1);
+            }
+            _ => {}
+        }
         self.events.push(EventRaw::Token { kind });
         self.pos += 1;
     }
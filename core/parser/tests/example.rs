@@ -1,8 +1,31 @@
 //! Generated by "../test_data/gen.ts". Do not edit by hand.
 #![cfg_attr(rustfmt, rustfmt_skip)]
 mod macros;
+test!(array_order, "../../../tests/cases/array_order/main.lico");
+test!(closing_brackets, "../../../tests/cases/closing_brackets/main.lico");
+test!(compound_assign, "../../../tests/cases/compound_assign/main.lico");
+test!(constant_folding, "../../../tests/cases/constant_folding/main.lico");
+test!(conversion, "../../../tests/cases/conversion/main.lico");
 test!(fibonacci_z_combinator, "../../../tests/cases/fibonacci_z_combinator/main.lico");
+test!(finalizer, "../../../tests/cases/finalizer/main.lico");
 test!(fizz_buzz_for, "../../../tests/cases/fizz_buzz_for/main.lico");
 test!(fizz_buzz_while, "../../../tests/cases/fizz_buzz_while/main.lico");
+test!(fs_io, "../../../tests/cases/fs_io/main.lico");
+test!(gc, "../../../tests/cases/gc/main.lico");
 test!(hello_with_variable, "../../../tests/cases/hello_with_variable/main.lico");
 test!(hello_world, "../../../tests/cases/hello_world/main.lico");
+test!(json, "../../../tests/cases/json/main.lico");
+test!(match_expr, "../../../tests/cases/match_expr/main.lico");
+test!(math_random, "../../../tests/cases/math_random/main.lico");
+test!(nil_safe_and_or, "../../../tests/cases/nil_safe_and_or/main.lico");
+test!(os_time, "../../../tests/cases/os_time/main.lico");
+test!(range_bounds, "../../../tests/cases/range_bounds/main.lico");
+test!(return_to_caller, "../../../tests/cases/return_to_caller/main.lico");
+test!(set_item_order, "../../../tests/cases/set_item_order/main.lico");
+test!(set_method_order, "../../../tests/cases/set_method_order/main.lico");
+test!(string_escape, "../../../tests/cases/string_escape/main.lico");
+test!(string_interpolation, "../../../tests/cases/string_interpolation/main.lico");
+test!(tail_call, "../../../tests/cases/tail_call/main.lico");
+test!(tiered_execution, "../../../tests/cases/tiered_execution/main.lico");
+test!(upvalues, "../../../tests/cases/upvalues/main.lico");
+test!(weak_ref, "../../../tests/cases/weak_ref/main.lico");
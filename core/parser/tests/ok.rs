@@ -14,3 +14,7 @@ test!(ok_009_break_stmt, "../test_data/ok/009_break_stmt.lico");
 test!(ok_010_assing_stmt, "../test_data/ok/010_assing_stmt.lico");
 test!(ok_011_call_stmt, "../test_data/ok/011_call_stmt.lico");
 test!(ok_012_method_call_stmt, "../test_data/ok/012_method_call_stmt.lico");
+test!(ok_013_interpolated_string, "../test_data/ok/013_interpolated_string.lico");
+test!(ok_014_compound_assign_stmt, "../test_data/ok/014_compound_assign_stmt.lico");
+test!(ok_015_nil_safe_expr, "../test_data/ok/015_nil_safe_expr.lico");
+test!(ok_016_match_expr, "../test_data/ok/016_match_expr.lico");
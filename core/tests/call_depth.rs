@@ -0,0 +1,60 @@
+//! Checks that the nested function calls do not consume the native stack, and that the call depth
+//! exceeding the limit raises an exception.
+
+use lico_core::{
+    compiler,
+    foundation::{
+        object::Object,
+        syntax::{
+            ast::{AstNode, Program},
+            SyntaxNode,
+        },
+    },
+    lexer, parser, vm,
+};
+
+const SOURCE: &str = r#"
+var o = {}
+o.f = func(n)
+  if n == 0 then return 0 end
+  return 1 + o.f(n - 1)
+end
+# Not a tail call, so that the call from here is counted in the depth.
+var result = o.f(depth)
+return result
+"#;
+
+fn run(depth: i64, max_call_depth: usize) -> Result<Object, String> {
+    let source = format!("var depth = {}\n{}", depth, SOURCE);
+    let (green, errors) = parser::parse(&source, lexer::tokenize(&source));
+    assert!(errors.is_empty(), "{:?}", errors);
+    let program = Program::cast(SyntaxNode::new_root(green)).unwrap();
+    let (module, errors, _) = compiler::lower_ast(program);
+    assert!(errors.is_empty(), "{:?}", errors);
+    let module = compiler::compile(&compiler::optimize(module));
+
+    match vm::execute_with(&module, &vm::Options { max_call_depth }) {
+        Ok(result) => Ok(result),
+        Err(vm::Interrupt::Exit(code)) => panic!("exit with code {}", code),
+        Err(vm::Interrupt::Exception) => {
+            let mut log = vm::EXCEPTION_LOG.lock().unwrap();
+            let message = log.read(1).next().unwrap().message().to_string();
+            Err(message)
+        }
+    }
+}
+
+// A single test runs all the checks, because `vm::EXCEPTION_LOG` is shared by the whole process.
+#[test]
+fn call_depth() {
+    // Deeper than the native stack allows for the recursive `loop_()`.
+    let depth = vm::DEFAULT_MAX_CALL_DEPTH;
+    assert_eq!(run(90_000, depth), Ok(Object::Int(90_000)));
+
+    let overflow = run(1_000_000, depth).unwrap_err();
+    assert!(overflow.starts_with("Stack overflow"), "{}", overflow);
+
+    assert_eq!(run(99, 100), Ok(Object::Int(99)));
+    let overflow = run(100, 100).unwrap_err();
+    assert!(overflow.contains("(100)"), "{}", overflow);
+}
@@ -0,0 +1,160 @@
+//! Runs the scripts in `../tests/cases` in-process and compares their output with the expected
+//! one, the same way as `tests/main.ts` does with the `lico` executable.
+
+use lico_core::{
+    compiler,
+    foundation::syntax::{
+        ast::{AstNode, Program},
+        SyntaxNode,
+    },
+    lexer, parser, vm,
+};
+use std::{
+    fs,
+    io::Cursor,
+    path::{Path, PathBuf},
+};
+
+struct TestCase {
+    name: String,
+    source: PathBuf,
+    input: Option<String>,
+    output: String,
+}
+
+fn collect_cases(group: &Path) -> Vec<TestCase> {
+    let name = group.file_name().unwrap().to_string_lossy().into_owned();
+    let source = group.join("main.lico");
+    let read = |path: PathBuf| fs::read_to_string(path).ok();
+
+    if let Some(output) = read(group.join("output.txt")) {
+        let input = read(group.join("input.txt"));
+        return vec![TestCase {
+            name,
+            source,
+            input,
+            output,
+        }];
+    }
+    let Ok(entries) = fs::read_dir(group.join("output")) else {
+        return Vec::new();
+    };
+    entries
+        .map(|entry| {
+            let path = entry.unwrap().path();
+            let case = path.file_stem().unwrap().to_string_lossy().into_owned();
+            TestCase {
+                name: format!("{}/{}", name, case),
+                source: source.clone(),
+                input: read(group.join("input").join(format!("{}.txt", case))),
+                output: read(path).unwrap(),
+            }
+        })
+        .collect()
+}
+
+fn run(source: &str, input: Option<&str>) -> Result<String, String> {
+    let (green, errors) = parser::parse(source, lexer::tokenize(source));
+    if !errors.is_empty() {
+        return Err(format!("syntax error: {:?}", errors));
+    }
+    let program = Program::cast(SyntaxNode::new_root(green)).unwrap();
+    let (module, errors, _) = compiler::lower_ast(program);
+    if !errors.is_empty() {
+        return Err(format!("syntax error: {:?}", errors));
+    }
+    let module = compiler::compile(&compiler::optimize(module));
+
+    let output = vm::OutputBuffer::new();
+    vm::STDIO.with_borrow_mut(|stdio| {
+        stdio.reset();
+        stdio.set_stdout(output.clone());
+        stdio.set_stdin(Cursor::new(input.unwrap_or_default().to_string()));
+    });
+    let result = vm::execute(&module);
+    vm::STDIO.with_borrow_mut(|stdio| stdio.reset());
+    match result {
+        Ok(_) | Err(vm::Interrupt::Exit(0)) => Ok(output.take()),
+        Err(vm::Interrupt::Exit(code)) => Err(format!("exit with code {}", code)),
+        Err(vm::Interrupt::Exception) => {
+            let mut log = vm::EXCEPTION_LOG.lock().unwrap();
+            let messages = log.read(1).map(|e| e.message().to_string());
+            Err(format!("exception: {:?}", messages.collect::<Vec<_>>()))
+        }
+    }
+}
+
+fn check(group: &str) {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/cases");
+    let mut failures = Vec::new();
+    for case in collect_cases(&root.join(group)) {
+        let source = fs::read_to_string(&case.source).unwrap();
+        match run(&source, case.input.as_deref()) {
+            Ok(actual) if actual == case.output => {}
+            Ok(actual) => failures.push(format!(
+                "[{}]\nexpected:\n{}\ngot:\n{}",
+                case.name, case.output, actual
+            )),
+            Err(err) => failures.push(format!("[{}]\n{}", case.name, err)),
+        }
+    }
+    assert!(failures.is_empty(), "\n{}", failures.join("\n\n"));
+}
+
+// Each case runs on its own test thread, which has its own `vm::STDIO`.
+macro_rules! cases {
+    ($($name:ident),* $(,)?) => {
+        $(
+            #[test]
+            fn $name() {
+                check(stringify!($name));
+            }
+        )*
+
+        const CASES: &[&str] = &[$(stringify!($name)),*];
+    };
+}
+
+cases! {
+    array_order,
+    closing_brackets,
+    compound_assign,
+    constant_folding,
+    conversion,
+    fibonacci_z_combinator,
+    finalizer,
+    fizz_buzz_for,
+    fizz_buzz_while,
+    fs_io,
+    gc,
+    hello_with_variable,
+    hello_world,
+    json,
+    match_expr,
+    math_random,
+    nil_safe_and_or,
+    os_time,
+    range_bounds,
+    return_to_caller,
+    set_item_order,
+    set_method_order,
+    string_escape,
+    string_interpolation,
+    tail_call,
+    tiered_execution,
+    upvalues,
+    weak_ref,
+}
+
+#[test]
+fn all_cases_listed() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/cases");
+    let mut groups = fs::read_dir(root)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.is_dir())
+        .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+    groups.sort();
+    assert_eq!(groups, CASES);
+}
@@ -0,0 +1,51 @@
+//! Checks the errors reported for the invalid programs, which must not panic the compiler.
+
+use lico_core::{
+    compiler,
+    foundation::syntax::{
+        ast::{AstNode, Program},
+        SyntaxNode,
+    },
+    lexer, parser,
+};
+
+/// Returns the messages of the syntax errors and the lowering errors of `source`.
+fn errors(source: &str) -> Vec<String> {
+    let (green, errors) = parser::parse(source, lexer::tokenize(source));
+    let mut messages = errors
+        .iter()
+        .map(|error| error.message().to_string())
+        .collect::<Vec<_>>();
+    let program = Program::cast(SyntaxNode::new_root(green)).unwrap();
+    let (_, errors, _) = compiler::lower_ast(program);
+    messages.extend(errors.iter().map(|error| error.message().to_string()));
+    messages
+}
+
+#[test]
+fn interpolated_string_assign_target() {
+    assert_eq!(
+        errors("var x = 0\n\"a{x}\" = 1"),
+        ["Invalid left-hand side expression"]
+    );
+}
+
+#[test]
+fn match_assign_target() {
+    assert_eq!(
+        errors("var x = 0\nmatch x with | _ then 1 end = 1"),
+        ["Invalid left-hand side expression"]
+    );
+}
+
+#[test]
+fn brace_in_string() {
+    // `{` always starts an interpolation, so a literal brace is escaped or in a raw string.
+    assert!(errors(r#"var x = "{{}}" .. r"{x}""#).is_empty());
+    let messages = errors(r#"var x = "a { b""#);
+    assert!(
+        messages.iter().any(|message| message.contains("Use `{{`")),
+        "{:?}",
+        messages
+    );
+}
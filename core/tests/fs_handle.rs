@@ -0,0 +1,94 @@
+//! Checks that a file handle of `fs.open` refers only to the file it opened, even after the file is
+//! closed and another file is opened, and that the finalizer of a handle closes its file.
+
+use lico_core::{
+    compiler,
+    foundation::syntax::{
+        ast::{AstNode, Program},
+        SyntaxNode,
+    },
+    lexer, parser, vm,
+};
+use std::{
+    env, fs,
+    sync::{Mutex, PoisonError},
+};
+
+// The tests run in parallel, but `vm::EXCEPTION_LOG` is shared by the whole process.
+static LOG_LOCK: Mutex<()> = Mutex::new(());
+
+fn run(source: &str) -> Result<(), String> {
+    let (green, errors) = parser::parse(source, lexer::tokenize(source));
+    assert!(errors.is_empty(), "{:?}", errors);
+    let program = Program::cast(SyntaxNode::new_root(green)).unwrap();
+    let (module, errors, _) = compiler::lower_ast(program);
+    assert!(errors.is_empty(), "{:?}", errors);
+    let module = compiler::compile(&compiler::optimize(module));
+
+    let _guard = LOG_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+    match vm::execute(&module) {
+        Ok(_) => Ok(()),
+        Err(vm::Interrupt::Exit(code)) => panic!("exit with code {}", code),
+        Err(vm::Interrupt::Exception) => {
+            let mut log = vm::EXCEPTION_LOG.lock().unwrap();
+            let message = log.read(1).next().unwrap().message().to_string();
+            Err(message)
+        }
+    }
+}
+
+#[test]
+fn closed_handle() {
+    let dir = env::temp_dir();
+    let a = dir.join("lico_fs_handle_a.txt");
+    let b = dir.join("lico_fs_handle_b.txt");
+    let source = format!(
+        r#"
+var a = fs.open("{a}", "w")
+a->close()
+var b = fs.open("{b}", "w")
+a.fd = 0
+a->write("oops")
+"#,
+        a = a.display(),
+        b = b.display(),
+    );
+
+    let result = run(&source);
+    let written = fs::read_to_string(&b).unwrap();
+    fs::remove_file(&a).unwrap();
+    fs::remove_file(&b).unwrap();
+    let error = result.unwrap_err();
+    assert!(
+        error.ends_with("write: the file is already closed"),
+        "{}",
+        error
+    );
+    assert_eq!(written, "");
+}
+
+#[test]
+fn finalized_handle() {
+    let path = env::temp_dir().join("lico_fs_handle_gc.txt");
+    let source = format!(
+        r#"
+var file = fs.open("{path}", "w")
+file->write("kept")
+file->__gc()
+file->__gc()
+file->write("oops")
+"#,
+        path = path.display(),
+    );
+
+    let result = run(&source);
+    let written = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    let error = result.unwrap_err();
+    assert!(
+        error.ends_with("write: the file is already closed"),
+        "{}",
+        error
+    );
+    assert_eq!(written, "kept");
+}
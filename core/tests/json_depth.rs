@@ -0,0 +1,90 @@
+//! Checks that `json.decode` and `json.encode` raise an exception for a deeply nested value,
+//! instead of overflowing the native stack.
+
+use lico_core::{
+    compiler,
+    foundation::{
+        object::Object,
+        syntax::{
+            ast::{AstNode, Program},
+            SyntaxNode,
+        },
+    },
+    lexer, parser, vm,
+};
+use std::sync::{Mutex, PoisonError};
+
+// The tests run in parallel, but `vm::EXCEPTION_LOG` is shared by the whole process.
+static LOG_LOCK: Mutex<()> = Mutex::new(());
+
+fn run(source: &str) -> Result<Object, String> {
+    let (green, errors) = parser::parse(source, lexer::tokenize(source));
+    assert!(errors.is_empty(), "{:?}", errors);
+    let program = Program::cast(SyntaxNode::new_root(green)).unwrap();
+    let (module, errors, _) = compiler::lower_ast(program);
+    assert!(errors.is_empty(), "{:?}", errors);
+    let module = compiler::compile(&compiler::optimize(module));
+
+    let _guard = LOG_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+    match vm::execute(&module) {
+        Ok(result) => Ok(result),
+        Err(vm::Interrupt::Exit(code)) => panic!("exit with code {}", code),
+        Err(vm::Interrupt::Exception) => {
+            let mut log = vm::EXCEPTION_LOG.lock().unwrap();
+            let message = log.read(1).next().unwrap().message().to_string();
+            Err(message)
+        }
+    }
+}
+
+fn decode(text: &str) -> Result<Object, String> {
+    run(&format!("return json.decode(\"{}\")", text))
+}
+
+fn encode(depth: usize) -> Result<Object, String> {
+    run(&format!(
+        "var a = []\nfor i in 1->upto({}) do\n  a = [a]\nend\nreturn json.encode(a)",
+        depth - 1
+    ))
+}
+
+#[test]
+fn decode_depth() {
+    let nested = |depth: usize| "[".repeat(depth) + &"]".repeat(depth);
+    assert!(decode(&nested(512)).is_ok());
+
+    let error = decode(&nested(513)).unwrap_err();
+    assert!(
+        error.ends_with("json.decode: nested deeper than 512 at line 1, column 513"),
+        "{}",
+        error
+    );
+
+    let error = decode(&"[".repeat(200_000)).unwrap_err();
+    assert!(error.ends_with("at line 1, column 513"), "{}", error);
+
+    let error = decode(&format!(
+        "{}\\n{}",
+        "[".repeat(256),
+        "{{\\\"a\\\":".repeat(300)
+    ))
+    .unwrap_err();
+    assert!(error.ends_with("at line 2, column 1281"), "{}", error);
+}
+
+#[test]
+fn encode_depth() {
+    assert_eq!(
+        encode(512),
+        Ok(Object::String(
+            ("[".repeat(512) + &"]".repeat(512)).as_str().into()
+        ))
+    );
+
+    let error = encode(513).unwrap_err();
+    assert!(
+        error.ends_with("json.encode: cannot encode a value nested deeper than 512"),
+        "{}",
+        error
+    );
+}
@@ -0,0 +1,57 @@
+//! Checks that `tostring` raises an exception for a precision which `format!` cannot handle,
+//! instead of panicking.
+
+use lico_core::{
+    compiler,
+    foundation::{
+        object::Object,
+        syntax::{
+            ast::{AstNode, Program},
+            SyntaxNode,
+        },
+    },
+    lexer, parser, vm,
+};
+
+fn run(source: &str) -> Result<Object, String> {
+    let (green, errors) = parser::parse(source, lexer::tokenize(source));
+    assert!(errors.is_empty(), "{:?}", errors);
+    let program = Program::cast(SyntaxNode::new_root(green)).unwrap();
+    let (module, errors, _) = compiler::lower_ast(program);
+    assert!(errors.is_empty(), "{:?}", errors);
+    let module = compiler::compile(&compiler::optimize(module));
+
+    match vm::execute(&module) {
+        Ok(result) => Ok(result),
+        Err(vm::Interrupt::Exit(code)) => panic!("exit with code {}", code),
+        Err(vm::Interrupt::Exception) => {
+            let mut log = vm::EXCEPTION_LOG.lock().unwrap();
+            let message = log.read(1).next().unwrap().message().to_string();
+            Err(message)
+        }
+    }
+}
+
+fn tostring(precision: i64) -> Result<Object, String> {
+    run(&format!(
+        "return tostring(1.5, {{precision = {}}})",
+        precision
+    ))
+}
+
+#[test]
+fn precision() {
+    let Ok(Object::String(max)) = tostring(65535) else {
+        panic!("expected a string");
+    };
+    assert_eq!(max.len(), 65537);
+
+    for precision in [65536, i64::MAX, -1] {
+        let error = tostring(precision).unwrap_err();
+        let expected = format!(
+            "tostring: precision must be in 0..=65535, got {}",
+            precision
+        );
+        assert!(error.ends_with(&expected), "{}", error);
+    }
+}
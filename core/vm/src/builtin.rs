@@ -15,6 +15,7 @@ pub(crate) mod nil;
 pub(crate) mod rust_function;
 pub(crate) mod string;
 pub(crate) mod table;
+pub(crate) mod weak_ref;
 
 pub(crate) enum RunMethodResult {
     Ok(Object),
@@ -34,7 +35,7 @@ pub(crate) enum RunMethodResult {
 }
 
 bitflags! {
-    pub(crate) struct TypeFlag: u8 {
+    pub(crate) struct TypeFlag: u16 {
         const INT      = 1 << 0;
         const FLOAT    = 1 << 1;
         const BOOL     = 1 << 2;
@@ -43,6 +44,7 @@ bitflags! {
         const ARRAY    = 1 << 5;
         const TABLE    = 1 << 6;
         const FUNCTION = 1 << 7;
+        const WEAK_REF = 1 << 8;
     }
 }
 
@@ -63,6 +65,7 @@ impl From<&Object> for TypeFlag {
             Object::Array(_) => TypeFlag::ARRAY,
             Object::Table(_) => TypeFlag::TABLE,
             Object::Function(_) | Object::RustFunction(_) => TypeFlag::FUNCTION,
+            Object::WeakRef(_) => TypeFlag::WEAK_REF,
         }
     }
 }
@@ -1,59 +1,68 @@
 use super::*;
+use std::vec;
+
+pub(crate) type Method = fn(i64, vec::IntoIter<Object>) -> RunMethodResult;
 
 pub(crate) fn run_method(
     name: &str,
     receiver: i64,
-    args: impl ExactSizeIterator<Item = Object>,
+    args: vec::IntoIter<Object>,
 ) -> RunMethodResult {
-    let args = args.into_iter();
+    match find_method(name) {
+        Some(method) => method(receiver, args),
+        None => RunMethodResult::NotFound {
+            receiver_type: TypeFlag::INT,
+        },
+    }
+}
 
+/// Returns the builtin method named `name`.
+pub(crate) fn find_method(name: &str) -> Option<Method> {
     #[rustfmt::skip]
-    let ret = match name {
+    let method: Method = match name {
         // common methods
-        "to_string" => method::to_string(receiver, args),
+        "to_string" => method::to_string,
 
         // number methods
-        "abs"   => method::abs(receiver, args),
-        "acos"  => method::acos(receiver,args),
-        "acosh" => method::acosh(receiver,args),
-        "asin"  => method::asin(receiver,args),
-        "asinh" => method::asinh(receiver,args),
-        "atan"  => method::atan(receiver,args),
-        "atan2" => method::atan2(receiver,args),
-        "atanh" => method::atanh(receiver,args),
-        "cbrt"  => method::cbrt(receiver,args),
-        "ceil"  => method::ceil(receiver,args),
-        "clamp" => method::clamp(receiver,args),
-        "cos"   => method::cos(receiver,args),
-        "cosh"  => method::cosh(receiver,args),
-        "exp"   => method::exp(receiver,args),
-        "exp2"  => method::exp2(receiver,args),
-        "floor" => method::floor(receiver,args),
-        "fract" => method::fract(receiver,args),
-        "ln"    => method::ln(receiver,args),
-        "log"   => method::log(receiver,args),
-        "log10" => method::log10(receiver,args),
-        "log2"  => method::log2(receiver,args),
-        "max"   => method::max(receiver,args),
-        "min"   => method::min(receiver,args),
-        "pow"   => method::pow(receiver,args),
-        "round" => method::round(receiver,args),
-        "sin"   => method::sin(receiver,args),
-        "sinh"  => method::sinh(receiver,args),
-        "sqrt"  => method::sqrt(receiver,args),
-        "tan"   => method::tan(receiver,args),
-        "tanh"  => method::tanh(receiver,args),
-        "trunc" => method::trunc(receiver,args),
+        "abs"   => method::abs,
+        "acos"  => method::acos,
+        "acosh" => method::acosh,
+        "asin"  => method::asin,
+        "asinh" => method::asinh,
+        "atan"  => method::atan,
+        "atan2" => method::atan2,
+        "atanh" => method::atanh,
+        "cbrt"  => method::cbrt,
+        "ceil"  => method::ceil,
+        "clamp" => method::clamp,
+        "cos"   => method::cos,
+        "cosh"  => method::cosh,
+        "exp"   => method::exp,
+        "exp2"  => method::exp2,
+        "floor" => method::floor,
+        "fract" => method::fract,
+        "ln"    => method::ln,
+        "log"   => method::log,
+        "log10" => method::log10,
+        "log2"  => method::log2,
+        "max"   => method::max,
+        "min"   => method::min,
+        "pow"   => method::pow,
+        "round" => method::round,
+        "sin"   => method::sin,
+        "sinh"  => method::sinh,
+        "sqrt"  => method::sqrt,
+        "tan"   => method::tan,
+        "tanh"  => method::tanh,
+        "trunc" => method::trunc,
 
         // int methods
-        "downto" => method::downto(receiver, args),
-        "upto" => method::upto(receiver, args),
+        "downto" => method::downto,
+        "upto" => method::upto,
 
-        _ => RunMethodResult::NotFound {
-            receiver_type: TypeFlag::INT,
-        }
+        _ => return None,
     };
-    ret
+    Some(method)
 }
 
 mod method {
@@ -308,13 +317,25 @@ mod method {
                 Result::Ok(args.next().unwrap())
             })),
         );
-        fn get_current(tbl: &object::Table) -> Option<anyhow::Result<i64>> {
-            match tbl.get("__current")? {
-                Int(current) => Some(Result::Ok(*current)),
-                invalid => Some(Err(anyhow!(
+        // `__current` is nil until the first `__move_next()`.
+        fn get_current(tbl: &object::Table) -> anyhow::Result<Option<i64>> {
+            match tbl.get("__current") {
+                Some(Int(current)) => Result::Ok(Some(*current)),
+                Some(Nil) | None => Result::Ok(None),
+                Some(invalid) => Err(anyhow!(
                     "The field '__current' is not an integer: got '{}'",
                     invalid.type_name()
-                ))),
+                )),
+            }
+        }
+        fn get_start(tbl: &object::Table) -> anyhow::Result<i64> {
+            match tbl.get("start") {
+                Some(Int(start)) => Result::Ok(*start),
+                Some(invalid) => Err(anyhow!(
+                    "The field 'start' is not an integer: got '{}'",
+                    invalid.type_name()
+                )),
+                None => Err(anyhow!("The field 'start' is not found")),
             }
         }
         fn get_end(tbl: &object::Table) -> anyhow::Result<i64> {
@@ -334,15 +355,14 @@ mod method {
                     let Table(mut this) = args.next().unwrap() else {
                         panic!("[BUG?] unexpected type of `self`")
                     };
-                    let current = match get_current(&this) {
-                        Some(Result::Ok(current)) => current,
-                        Some(Err(err)) => return Err(err),
-                        None => return Result::Ok(Bool(false)),
+                    let next = match get_current(&this)? {
+                        Some(current) => current - 1,
+                        None => get_start(&this)?,
                     };
                     let end = get_end(&this)?;
-                    if current > end {
+                    if next >= end {
                         // TODO: use `entry` after implementing `Table::entry`
-                        this.insert("__current".into(), Int(current - 1));
+                        this.insert("__current".into(), Int(next));
                         Result::Ok(Bool(true))
                     } else {
                         Result::Ok(Bool(false))
@@ -356,15 +376,14 @@ mod method {
                     let Table(mut this) = args.next().unwrap() else {
                         panic!("[BUG?] unexpected type of `self`")
                     };
-                    let current = match get_current(&this) {
-                        Some(Result::Ok(current)) => current,
-                        Some(Err(err)) => return Err(err),
-                        None => return Result::Ok(Bool(false)),
+                    let next = match get_current(&this)? {
+                        Some(current) => current + 1,
+                        None => get_start(&this)?,
                     };
                     let end = get_end(&this)?;
-                    if current < end {
+                    if next <= end {
                         // TODO: use `entry` after implementing `Table::entry`
-                        this.insert("__current".into(), Int(current + 1));
+                        this.insert("__current".into(), Int(next));
                         Result::Ok(Bool(true))
                     } else {
                         Result::Ok(Bool(false))
@@ -0,0 +1,43 @@
+use super::*;
+
+pub(crate) fn run_method(
+    name: &str,
+    receiver: WeakRef,
+    args: impl ExactSizeIterator<Item = Object>,
+) -> RunMethodResult {
+    let args = args.into_iter();
+    match name {
+        // common methods
+        "to_string" => method::to_string(receiver, args),
+
+        // weakref methods
+        "get" => method::get(receiver, args),
+        "is_alive" => method::is_alive(receiver, args),
+        _ => RunMethodResult::NotFound {
+            receiver_type: TypeFlag::WEAK_REF,
+        },
+    }
+}
+
+mod method {
+    use super::*;
+    use RunMethodResult::*;
+
+    util_macros::gen_method_macro!(WeakRef);
+
+    // to_string() -> string
+    method!(to_string, 0, |this, args| {
+        let string = UString::from(format!("{:?}", this).as_str());
+        Ok(Object::String(string))
+    });
+
+    // get() -> array | table | nil
+    //
+    // Returns the referent, or nil if it is already freed.
+    method!(get, 0, |this, args| Ok(this
+        .upgrade()
+        .unwrap_or(Object::Nil)));
+
+    // is_alive() -> bool
+    method!(is_alive, 0, |this, args| Ok(Object::Bool(this.is_alive())));
+}
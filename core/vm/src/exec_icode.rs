@@ -1,10 +1,9 @@
 use super::*;
 use core::cmp::Ordering;
-use foundation::object::{self, Object::*};
-use std::rc::Rc;
+use foundation::object::{self, FormatSpec, Object::*};
 
-/// `(pc, exe, runtime)`
-type LoopContextRef<'a> = (&'a mut usize, &'a Executable, &'a mut Runtime);
+/// `(pc, exe, runtime, dst)`, where `dst` is the register to store the result to.
+type LoopContextRef<'a> = (&'a mut usize, &'a Executable, &'a mut Runtime, Reg);
 
 pub(super) fn call<I>(callee: Object, args: I, context: LoopContextRef) -> Status
 where
@@ -20,7 +19,8 @@ where
         Object::Function(func) => util::exec_function(func, args, context),
         RustFunction(func) => util::exec_rust_function(func, args, context),
         Table(table) => {
-            if let Some(method) = table.get_method("__call") {
+            let cache = context.1.method_cache(*context.0);
+            if let Some(method) = util::get_method(&table, "__call", cache) {
                 util::exec_table_method(method.clone(), args, context)
             } else {
                 let pc = *context.0;
@@ -36,33 +36,28 @@ where
     }
 }
 
-pub(super) fn call_method<I>(
-    receiver: Object,
-    name: &UString,
-    args: I,
-    context: LoopContextRef,
-) -> Status
+/// Calls `callee` as `call()` does, but reuses the current frame if `callee` is a function of the
+/// running executable, so that `return f(x)` does not grow the frames.
+pub(super) fn tail_call<I>(callee: Object, args: I, context: LoopContextRef) -> Status
 where
     I: IntoIterator<Item = Object> + 'static,
     I::IntoIter: ExactSizeIterator,
 {
-    fn set_method_not_found_exception(pc: usize, name: &str, type_name: &str) {
-        let message = format!(
-            "The method '{}' is not found in the object of type '{}'.",
-            name, type_name
-        );
-        EXCEPTION_LOG.lock().unwrap().push_raw(message, pc, 0);
-    }
-
-    fn set_method_argument_length_exception(expected: u8, got: u8, pc: usize) {
-        let message = format!(
-            "Method call failed: expected {} arguments, got {}.",
-            expected, got
-        );
-        EXCEPTION_LOG.lock().unwrap().push_raw(message, pc, 1);
+    match callee {
+        Object::Function(func) if context.1.ptr_eq(func.executable()) => {
+            util::exec_function_in_frame(func, args.into_iter(), context)
+        }
+        callee => call(callee, args, context),
     }
+}
 
-    let name = name.as_str();
+pub(super) fn call_method(
+    receiver: Object,
+    method_name: &MethodName,
+    args: Vec<Object>,
+    context: LoopContextRef,
+) -> Status {
+    let name = method_name.as_str();
     let args = args.into_iter();
 
     use builtin::{
@@ -71,7 +66,7 @@ where
         int::run_method as run_int_method, nil::run_method as run_nil_method,
         rust_function::run_method as run_rust_function_method,
         string::run_method as run_string_method, table::run_method as run_table_method,
-        RunMethodResult,
+        weak_ref::run_method as run_weak_ref_method,
     };
 
     let result = match receiver {
@@ -82,7 +77,8 @@ where
         String(string) => run_string_method(name, string, args),
         Object::Array(array) => run_array_method(name, array, args),
         Object::Table(ref table) => {
-            if let Some(method) = table.get_method(name).cloned() {
+            let cache = context.1.method_cache(*context.0);
+            if let Some(method) = util::get_method_by_name(table, method_name, cache).cloned() {
                 // `iter::once(receiver).chain(args)` is not ExactSizeIterator. (overflow can occur)
                 // However, in this case, because the length of arguemnt is limited to u8::MAX by
                 // ICode::CallMethod, ExactSizeIterator can be implemented.
@@ -113,41 +109,28 @@ where
         }
         Object::Function(func) => run_function_method(name, func, args),
         RustFunction(func) => run_rust_function_method(name, func, args),
+        WeakRef(weak) => run_weak_ref_method(name, weak, args),
     };
-    let (pc, _, runtime) = context;
-    match result {
-        RunMethodResult::Ok(result) => {
-            runtime.stack.push(result);
-            *pc += 1;
-            CONTINUE
-        }
-        RunMethodResult::NotFound { receiver_type } => {
-            set_method_not_found_exception(*pc, name, &receiver_type.to_string());
-            EXCEPTION
-        }
-        RunMethodResult::InvalidArgCount { expected, got } => {
-            set_method_argument_length_exception(expected, got, *pc);
-            EXCEPTION
-        }
-        RunMethodResult::InvalidArgType {
-            index,
-            expected,
-            got,
-        } => {
-            let message = format!(
-                "Method call failed: expected argument of type '{}', got '{}'.",
-                expected, got
-            );
-            let extra = index as usize + 2;
-            EXCEPTION_LOG.lock().unwrap().push_raw(message, *pc, extra);
-            EXCEPTION
-        }
-        RunMethodResult::ExceptionOccurred => {
-            let message = format!("An exception occurred while calling the method '{}'.", name);
-            EXCEPTION_LOG.lock().unwrap().push_raw(message, *pc, 1);
-            EXCEPTION
+    util::finish_method_call(result, name, context)
+}
+
+pub(super) fn call_number_method(
+    receiver: Object,
+    index: u16,
+    args: Vec<Object>,
+    context: LoopContextRef,
+) -> Status {
+    let method = tier::number_method(index);
+    let result = match (receiver, method.int, method.float) {
+        (Int(int), Some(run), _) => run(int, args.into_iter()),
+        (Float(float), _, Some(run)) => run(float, args.into_iter()),
+        (receiver, _, _) => {
+            tier::deoptimize(context.1, *context.0);
+            let name = MethodName::new(method.name);
+            return call_method(receiver, &name, args, context);
         }
-    }
+    };
+    util::finish_method_call(result, method.name, context)
 }
 
 pub(super) fn set_item(
@@ -156,7 +139,7 @@ pub(super) fn set_item(
     value: Object,
     context: LoopContextRef,
 ) -> Status {
-    let (pc, _, _) = context;
+    let (pc, _, _, _) = context;
     match (container, key) {
         (Table(mut table), String(key)) => {
             table.insert(key, value);
@@ -188,9 +171,13 @@ pub(super) fn set_item(
 }
 
 pub(super) fn get_item(container: Object, key: Object, context: LoopContextRef) -> Status {
-    let (pc, _, runtime) = context;
+    let (pc, exe, runtime, dst) = context;
     let result = match (container, key) {
-        (Table(table), String(key)) => table.get(&key).cloned().unwrap_or(Nil),
+        (Table(table), String(key)) => match exe.field_cache(*pc) {
+            Some(cache) => table.get_cached(&key, cache),
+            None => table.get_upgraded(&key),
+        }
+        .unwrap_or(Nil),
         (Table(_), key) => {
             util::set_container_key_type_exception("table", key.type_name(), *pc);
             return EXCEPTION;
@@ -211,7 +198,7 @@ pub(super) fn get_item(container: Object, key: Object, context: LoopContextRef)
             return EXCEPTION;
         }
     };
-    runtime.stack.push(result);
+    runtime.local_table.set(dst, result);
     *pc += 1;
     CONTINUE
 }
@@ -223,17 +210,17 @@ pub(super) fn add(lhs: Object, rhs: Object, context: LoopContextRef) -> Status {
         (Float(lhs), Int(rhs)) => Float(lhs + rhs as f64),
         (Float(lhs), Float(rhs)) => Float(lhs + rhs),
         (lhs, rhs) => {
-            if let Some(method) = util::find_binary_method("__add", &lhs, &rhs) {
+            if let Some(method) = util::find_binary_method("__add", &lhs, &rhs, &context) {
                 return util::exec_table_method(method, [lhs, rhs], context);
             } else {
-                let (pc, _, _) = context;
+                let (pc, _, _, _) = context;
                 util::set_binary_type_exception("+", &lhs, &rhs, *pc);
                 return EXCEPTION;
             }
         }
     };
-    let (pc, _, runtime) = context;
-    runtime.stack.push(result);
+    let (pc, _, runtime, dst) = context;
+    runtime.local_table.set(dst, result);
     *pc += 1;
     CONTINUE
 }
@@ -245,17 +232,17 @@ pub(super) fn sub(lhs: Object, rhs: Object, context: LoopContextRef) -> Status {
         (Float(lhs), Int(rhs)) => Float(lhs - rhs as f64),
         (Float(lhs), Float(rhs)) => Float(lhs - rhs),
         (lhs, rhs) => {
-            if let Some(method) = util::find_binary_method("__sub", &lhs, &rhs) {
+            if let Some(method) = util::find_binary_method("__sub", &lhs, &rhs, &context) {
                 return util::exec_table_method(method, [lhs, rhs], context);
             } else {
-                let (pc, _, _) = context;
+                let (pc, _, _, _) = context;
                 util::set_binary_type_exception("-", &lhs, &rhs, *pc);
                 return EXCEPTION;
             }
         }
     };
-    let (pc, _, runtime) = context;
-    runtime.stack.push(result);
+    let (pc, _, runtime, dst) = context;
+    runtime.local_table.set(dst, result);
     *pc += 1;
     CONTINUE
 }
@@ -267,17 +254,17 @@ pub(super) fn mul(lhs: Object, rhs: Object, context: LoopContextRef) -> Status {
         (Float(lhs), Int(rhs)) => Float(lhs * rhs as f64),
         (Float(lhs), Float(rhs)) => Float(lhs * rhs),
         (lhs, rhs) => {
-            if let Some(method) = util::find_binary_method("__mul", &lhs, &rhs) {
+            if let Some(method) = util::find_binary_method("__mul", &lhs, &rhs, &context) {
                 return util::exec_table_method(method, [lhs, rhs], context);
             } else {
-                let (pc, _, _) = context;
+                let (pc, _, _, _) = context;
                 util::set_binary_type_exception("*", &lhs, &rhs, *pc);
                 return EXCEPTION;
             }
         }
     };
-    let (pc, _, runtime) = context;
-    runtime.stack.push(result);
+    let (pc, _, runtime, dst) = context;
+    runtime.local_table.set(dst, result);
     *pc += 1;
     CONTINUE
 }
@@ -298,17 +285,17 @@ pub(super) fn div(lhs: Object, rhs: Object, context: LoopContextRef) -> Status {
         (Float(lhs), Int(rhs)) => Float(lhs / rhs as f64),
         (Float(lhs), Float(rhs)) => Float(lhs / rhs),
         (lhs, rhs) => {
-            if let Some(method) = util::find_binary_method("__div", &lhs, &rhs) {
+            if let Some(method) = util::find_binary_method("__div", &lhs, &rhs, &context) {
                 return util::exec_table_method(method, [lhs, rhs], context);
             } else {
-                let (pc, _, _) = context;
+                let (pc, _, _, _) = context;
                 util::set_binary_type_exception("/", &lhs, &rhs, *pc);
                 return EXCEPTION;
             }
         }
     };
-    let (pc, _, runtime) = context;
-    runtime.stack.push(result);
+    let (pc, _, runtime, dst) = context;
+    runtime.local_table.set(dst, result);
     *pc += 1;
     CONTINUE
 }
@@ -329,17 +316,17 @@ pub(super) fn r#mod(lhs: Object, rhs: Object, context: LoopContextRef) -> Status
         (Float(lhs), Int(rhs)) => Float(lhs % rhs as f64),
         (Float(lhs), Float(rhs)) => Float(lhs % rhs),
         (lhs, rhs) => {
-            if let Some(method) = util::find_binary_method("__mod", &lhs, &rhs) {
+            if let Some(method) = util::find_binary_method("__mod", &lhs, &rhs, &context) {
                 return util::exec_table_method(method, [lhs, rhs], context);
             } else {
-                let (pc, _, _) = context;
+                let (pc, _, _, _) = context;
                 util::set_binary_type_exception("%", &lhs, &rhs, *pc);
                 return EXCEPTION;
             }
         }
     };
-    let (pc, _, runtime) = context;
-    runtime.stack.push(result);
+    let (pc, _, runtime, dst) = context;
+    runtime.local_table.set(dst, result);
     *pc += 1;
     CONTINUE
 }
@@ -352,14 +339,14 @@ pub(super) fn unm(value: Object, context: LoopContextRef) -> Status {
             if let Some(method) = util::find_unary_method("__unm", &value) {
                 return util::exec_table_method(method, [value], context);
             } else {
-                let (pc, _, _) = context;
+                let (pc, _, _, _) = context;
                 util::set_unary_type_exception("-", &value, *pc);
                 return EXCEPTION;
             }
         }
     };
-    let (pc, _, runtime) = context;
-    runtime.stack.push(result);
+    let (pc, _, runtime, dst) = context;
+    runtime.local_table.set(dst, result);
     *pc += 1;
     CONTINUE
 }
@@ -372,14 +359,14 @@ pub(super) fn unp(value: Object, context: LoopContextRef) -> Status {
             if let Some(method) = util::find_unary_method("__unp", &value) {
                 return util::exec_table_method(method, [value], context);
             } else {
-                let (pc, _, _) = context;
+                let (pc, _, _, _) = context;
                 util::set_unary_type_exception("+", &value, *pc);
                 return EXCEPTION;
             }
         }
     };
-    let (pc, _, runtime) = context;
-    runtime.stack.push(result);
+    let (pc, _, runtime, dst) = context;
+    runtime.local_table.set(dst, result);
     *pc += 1;
     CONTINUE
 }
@@ -388,37 +375,37 @@ pub(super) fn not(value: Object, context: LoopContextRef) -> Status {
     if let Some(method) = util::find_unary_method("__not", &value) {
         util::exec_table_method(method, [value], context)
     } else {
-        let (pc, _, runtime) = context;
+        let (pc, _, runtime, dst) = context;
         let result = Bool(value.is_falsey());
-        runtime.stack.push(result);
+        runtime.local_table.set(dst, result);
         *pc += 1;
         CONTINUE
     }
 }
 
 pub(super) fn eq(lhs: Object, rhs: Object, context: LoopContextRef) -> Status {
-    if let Some(method) = util::find_binary_method("__eq", &lhs, &rhs) {
+    if let Some(method) = util::find_binary_method("__eq", &lhs, &rhs, &context) {
         util::exec_table_method(method, [lhs, rhs], context)
     } else {
         let result = lhs == rhs;
-        let (pc, _, runtime) = context;
-        runtime.stack.push(Bool(result));
+        let (pc, _, runtime, dst) = context;
+        runtime.local_table.set(dst, Bool(result));
         *pc += 1;
         CONTINUE
     }
 }
 
 pub(super) fn not_eq(lhs: Object, rhs: Object, context: LoopContextRef) -> Status {
-    if let Some(method) = util::find_binary_method("__ne", &lhs, &rhs) {
+    if let Some(method) = util::find_binary_method("__ne", &lhs, &rhs, &context) {
         util::exec_table_method(method, [lhs, rhs], context)
-    } else if let Some(method) = util::find_binary_method("__eq", &lhs, &rhs) {
+    } else if let Some(method) = util::find_binary_method("__eq", &lhs, &rhs, &context) {
         util::exec_table_method_with_post_exec(method, [lhs, rhs], context, |obj| {
             Ok(Bool(obj.is_falsey()))
         })
     } else {
         let result = lhs != rhs;
-        let (pc, _, runtime) = context;
-        runtime.stack.push(Bool(result));
+        let (pc, _, runtime, dst) = context;
+        runtime.local_table.set(dst, Bool(result));
         *pc += 1;
         CONTINUE
     }
@@ -448,6 +435,85 @@ pub(super) fn greater_eq(lhs: Object, rhs: Object, context: LoopContextRef) -> S
     })
 }
 
+pub(super) fn jump_if_not_less(
+    lhs: Object,
+    rhs: Object,
+    offset: isize,
+    context: LoopContextRef,
+) -> Status {
+    jump_if_not_cmp(lhs, rhs, offset, context, Ordering::is_lt, less)
+}
+
+pub(super) fn jump_if_not_less_eq(
+    lhs: Object,
+    rhs: Object,
+    offset: isize,
+    context: LoopContextRef,
+) -> Status {
+    jump_if_not_cmp(lhs, rhs, offset, context, Ordering::is_le, less_eq)
+}
+
+pub(super) fn jump_if_not_greater(
+    lhs: Object,
+    rhs: Object,
+    offset: isize,
+    context: LoopContextRef,
+) -> Status {
+    jump_if_not_cmp(lhs, rhs, offset, context, Ordering::is_gt, greater)
+}
+
+pub(super) fn jump_if_not_greater_eq(
+    lhs: Object,
+    rhs: Object,
+    offset: isize,
+    context: LoopContextRef,
+) -> Status {
+    jump_if_not_cmp(lhs, rhs, offset, context, Ordering::is_ge, greater_eq)
+}
+
+pub(super) fn jump_if_not_eq(
+    lhs: Object,
+    rhs: Object,
+    offset: isize,
+    context: LoopContextRef,
+) -> Status {
+    if matches!(lhs, Table(_)) || matches!(rhs, Table(_)) {
+        return eq(lhs, rhs, context);
+    }
+    jump_if_not(lhs == rhs, offset, context)
+}
+
+/// Compares the numbers and jumps, or falls back to `fallback` (e.g. `less`) for the other types.
+/// Then the next `JumpIfFalse` jumps by its result.
+fn jump_if_not_cmp(
+    lhs: Object,
+    rhs: Object,
+    offset: isize,
+    context: LoopContextRef,
+    f: fn(Ordering) -> bool,
+    fallback: fn(Object, Object, LoopContextRef) -> Status,
+) -> Status {
+    let ordering = match (&lhs, &rhs) {
+        (Int(lhs), Int(rhs)) => lhs.partial_cmp(rhs),
+        (Int(lhs), Float(rhs)) => (*lhs as f64).partial_cmp(rhs),
+        (Float(lhs), Int(rhs)) => lhs.partial_cmp(&(*rhs as f64)),
+        (Float(lhs), Float(rhs)) => lhs.partial_cmp(rhs),
+        _ => return fallback(lhs, rhs, context),
+    };
+    jump_if_not(ordering.is_some_and(f), offset, context)
+}
+
+/// Jumps by `offset` if `condition` is false, otherwise skips the next `JumpIfFalse`.
+fn jump_if_not(condition: bool, offset: isize, context: LoopContextRef) -> Status {
+    let (pc, _, _, _) = context;
+    if condition {
+        *pc += 2;
+    } else {
+        *pc = (*pc as isize + offset) as usize;
+    }
+    CONTINUE
+}
+
 fn partial_cmp_with(
     op: &'static str,
     lhs: Object,
@@ -462,7 +528,7 @@ fn partial_cmp_with(
         (Float(lhs), Float(rhs)) => lhs.partial_cmp(&rhs),
         (lhs, rhs) => {
             let pc = *context.0;
-            if let Some(method) = util::find_binary_method("__cmp", &lhs, &rhs) {
+            if let Some(method) = util::find_binary_method("__cmp", &lhs, &rhs, &context) {
                 return util::exec_table_method_with_post_exec(
                     method,
                     [lhs, rhs],
@@ -487,9 +553,9 @@ fn partial_cmp_with(
             }
         }
     };
-    let (pc, _, runtime) = context;
+    let (pc, _, runtime, dst) = context;
     let result = f(result);
-    runtime.stack.push(Bool(result));
+    runtime.local_table.set(dst, Bool(result));
     *pc += 1;
     CONTINUE
 }
@@ -503,7 +569,7 @@ pub(super) fn concat(lhs: Object, rhs: Object, context: LoopContextRef) -> Statu
             String(lhs + rhs)
         }
         (lhs, rhs) => {
-            if let Some(method) = util::find_binary_method("__concat", &lhs, &rhs) {
+            if let Some(method) = util::find_binary_method("__concat", &lhs, &rhs, &context) {
                 return util::exec_table_method(method, [lhs, rhs], context);
             }
             let lhs = UString::from(lhs.to_string().as_str());
@@ -511,26 +577,46 @@ pub(super) fn concat(lhs: Object, rhs: Object, context: LoopContextRef) -> Statu
             String(lhs + rhs)
         }
     };
-    let (pc, _, runtime) = context;
-    runtime.stack.push(result);
+    let (pc, _, runtime, dst) = context;
+    runtime.local_table.set(dst, result);
     *pc += 1;
     CONTINUE
 }
 
+pub(super) fn format(value: Object, spec: &FormatSpec, context: LoopContextRef) -> Status {
+    let (pc, _, runtime, dst) = context;
+    match spec.format(&value) {
+        Ok(result) => {
+            runtime
+                .local_table
+                .set(dst, String(UString::from(result.as_str())));
+            *pc += 1;
+            CONTINUE
+        }
+        Err(err) => {
+            EXCEPTION_LOG
+                .lock()
+                .unwrap()
+                .push_raw(err.to_string(), *pc, 0);
+            EXCEPTION
+        }
+    }
+}
+
 pub(super) fn bit_and(lhs: Object, rhs: Object, context: LoopContextRef) -> Status {
     match (lhs, rhs) {
         (Int(lhs), Int(rhs)) => {
-            let (pc, _, runtime) = context;
+            let (pc, _, runtime, dst) = context;
             let result = Int(lhs & rhs);
-            runtime.stack.push(result);
+            runtime.local_table.set(dst, result);
             *pc += 1;
             CONTINUE
         }
         (lhs, rhs) => {
-            if let Some(method) = util::find_binary_method("__band", &lhs, &rhs) {
+            if let Some(method) = util::find_binary_method("__band", &lhs, &rhs, &context) {
                 util::exec_table_method(method, [lhs, rhs], context)
             } else {
-                let (pc, _, _) = context;
+                let (pc, _, _, _) = context;
                 util::set_binary_type_exception("&", &lhs, &rhs, *pc);
                 EXCEPTION
             }
@@ -541,17 +627,17 @@ pub(super) fn bit_and(lhs: Object, rhs: Object, context: LoopContextRef) -> Stat
 pub(super) fn bit_or(lhs: Object, rhs: Object, context: LoopContextRef) -> Status {
     match (lhs, rhs) {
         (Int(lhs), Int(rhs)) => {
-            let (pc, _, runtime) = context;
+            let (pc, _, runtime, dst) = context;
             let result = Int(lhs | rhs);
-            runtime.stack.push(result);
+            runtime.local_table.set(dst, result);
             *pc += 1;
             CONTINUE
         }
         (lhs, rhs) => {
-            if let Some(method) = util::find_binary_method("__bor", &lhs, &rhs) {
+            if let Some(method) = util::find_binary_method("__bor", &lhs, &rhs, &context) {
                 util::exec_table_method(method, [lhs, rhs], context)
             } else {
-                let (pc, _, _) = context;
+                let (pc, _, _, _) = context;
                 util::set_binary_type_exception("|", &lhs, &rhs, *pc);
                 EXCEPTION
             }
@@ -562,17 +648,17 @@ pub(super) fn bit_or(lhs: Object, rhs: Object, context: LoopContextRef) -> Statu
 pub(super) fn bit_xor(lhs: Object, rhs: Object, context: LoopContextRef) -> Status {
     match (lhs, rhs) {
         (Int(lhs), Int(rhs)) => {
-            let (pc, _, runtime) = context;
+            let (pc, _, runtime, dst) = context;
             let result = Int(lhs ^ rhs);
-            runtime.stack.push(result);
+            runtime.local_table.set(dst, result);
             *pc += 1;
             CONTINUE
         }
         (lhs, rhs) => {
-            if let Some(method) = util::find_binary_method("__bxor", &lhs, &rhs) {
+            if let Some(method) = util::find_binary_method("__bxor", &lhs, &rhs, &context) {
                 util::exec_table_method(method, [lhs, rhs], context)
             } else {
-                let (pc, _, _) = context;
+                let (pc, _, _, _) = context;
                 util::set_binary_type_exception("^", &lhs, &rhs, *pc);
                 EXCEPTION
             }
@@ -583,9 +669,9 @@ pub(super) fn bit_xor(lhs: Object, rhs: Object, context: LoopContextRef) -> Stat
 pub(super) fn bit_not(value: Object, context: LoopContextRef) -> Status {
     match value {
         Int(value) => {
-            let (pc, _, runtime) = context;
+            let (pc, _, runtime, dst) = context;
             let result = Int(!value);
-            runtime.stack.push(result);
+            runtime.local_table.set(dst, result);
             *pc += 1;
             CONTINUE
         }
@@ -593,7 +679,7 @@ pub(super) fn bit_not(value: Object, context: LoopContextRef) -> Status {
             if let Some(method) = util::find_unary_method("__bnot", &value) {
                 util::exec_table_method(method, [value], context)
             } else {
-                let (pc, _, _) = context;
+                let (pc, _, _, _) = context;
                 util::set_unary_type_exception("~", &value, *pc);
                 EXCEPTION
             }
@@ -606,17 +692,17 @@ pub(super) fn shift_l(lhs: Object, rhs: Object, context: LoopContextRef) -> Stat
         (Int(lhs), Int(rhs)) => Int(lhs << rhs),
         (Int(lhs), Float(rhs)) => Int(lhs << rhs as i64),
         (lhs, rhs) => {
-            if let Some(method) = util::find_binary_method("__shl", &lhs, &rhs) {
+            if let Some(method) = util::find_binary_method("__shl", &lhs, &rhs, &context) {
                 return util::exec_table_method(method, [lhs, rhs], context);
             } else {
-                let (pc, _, _) = context;
+                let (pc, _, _, _) = context;
                 util::set_binary_type_exception("<<", &lhs, &rhs, *pc);
                 return EXCEPTION;
             }
         }
     };
-    let (pc, _, runtime) = context;
-    runtime.stack.push(result);
+    let (pc, _, runtime, dst) = context;
+    runtime.local_table.set(dst, result);
     *pc += 1;
     CONTINUE
 }
@@ -626,23 +712,23 @@ pub(super) fn shift_r(lhs: Object, rhs: Object, context: LoopContextRef) -> Stat
         (Int(lhs), Int(rhs)) => Int(lhs >> rhs),
         (Int(lhs), Float(rhs)) => Int(lhs >> rhs as i64),
         (lhs, rhs) => {
-            if let Some(method) = util::find_binary_method("__shr", &lhs, &rhs) {
+            if let Some(method) = util::find_binary_method("__shr", &lhs, &rhs, &context) {
                 return util::exec_table_method(method, [lhs, rhs], context);
             } else {
-                let (pc, _, _) = context;
+                let (pc, _, _, _) = context;
                 util::set_binary_type_exception(">>", &lhs, &rhs, *pc);
                 return EXCEPTION;
             }
         }
     };
-    let (pc, _, runtime) = context;
-    runtime.stack.push(result);
+    let (pc, _, runtime, dst) = context;
+    runtime.local_table.set(dst, result);
     *pc += 1;
     CONTINUE
 }
 
 pub(super) fn get_iter(value: Object, context: LoopContextRef) -> Status {
-    if let Some(method) = util::find_unary_method("__iter", &value) {
+    if let Some(method) = util::find_unary_method("__get_iter", &value) {
         util::exec_table_method(method, [value], context)
     } else {
         todo!("define TextRange in GetIter (compiler/src/compile/icodesource.rs), set error to EXCEPTION_LOG");
@@ -670,25 +756,114 @@ pub(super) fn iter_current(iter: Object, context: LoopContextRef) -> Status {
 
 mod util {
     use super::*;
+    use builtin::RunMethodResult;
 
+    /// Finds the method for a binary operator in `lhs`, then in `rhs`.
+    /// The lookup in the first table is cached by the instruction. (See `get_method()`)
     pub(super) fn find_binary_method(
         name: &'static str,
         lhs: &Object,
         rhs: &Object,
+        context: &LoopContextRef,
     ) -> Option<TableMethod> {
+        let cache = context.1.method_cache(*context.0);
         if let Table(ref tbl) = lhs {
-            if let Some(method) = tbl.get_method(name) {
+            if let Some(method) = get_method(tbl, name, cache) {
                 return Some(method.clone());
             }
         }
         if let Table(ref tbl) = rhs {
-            if let Some(method) = tbl.get_method(name) {
+            // The cache is kept for `lhs`, which is looked up first.
+            let cache = if matches!(lhs, Table(_)) { None } else { cache };
+            if let Some(method) = get_method(tbl, name, cache) {
                 return Some(method.clone());
             }
         }
         None
     }
 
+    /// Returns the method of `table`, using the inline cache of the instruction if it has one.
+    pub(super) fn get_method<'a>(
+        table: &'a object::Table,
+        name: &str,
+        cache: Option<&object::MethodCache>,
+    ) -> Option<&'a TableMethod> {
+        match cache {
+            Some(cache) => table.get_method_cached(name, cache),
+            None => table.get_method(name),
+        }
+    }
+
+    /// Returns the method of `table` like `get_method()`, comparing the names by their identity.
+    pub(super) fn get_method_by_name<'a>(
+        table: &'a object::Table,
+        name: &MethodName,
+        cache: Option<&object::MethodCache>,
+    ) -> Option<&'a TableMethod> {
+        match cache {
+            Some(cache) => table.get_method_by_name_cached(name, cache),
+            None => table.get_method_by_name(name),
+        }
+    }
+
+    /// Stores the result of a builtin method, or sets the exception.
+    pub(super) fn finish_method_call(
+        result: RunMethodResult,
+        name: &str,
+        context: LoopContextRef,
+    ) -> Status {
+        fn set_method_not_found_exception(pc: usize, name: &str, type_name: &str) {
+            let message = format!(
+                "The method '{}' is not found in the object of type '{}'.",
+                name, type_name
+            );
+            EXCEPTION_LOG.lock().unwrap().push_raw(message, pc, 0);
+        }
+
+        fn set_method_argument_length_exception(expected: u8, got: u8, pc: usize) {
+            let message = format!(
+                "Method call failed: expected {} arguments, got {}.",
+                expected, got
+            );
+            EXCEPTION_LOG.lock().unwrap().push_raw(message, pc, 1);
+        }
+
+        let (pc, _, runtime, dst) = context;
+        match result {
+            RunMethodResult::Ok(result) => {
+                runtime.local_table.set(dst, result);
+                *pc += 1;
+                CONTINUE
+            }
+            RunMethodResult::NotFound { receiver_type } => {
+                set_method_not_found_exception(*pc, name, &receiver_type.to_string());
+                EXCEPTION
+            }
+            RunMethodResult::InvalidArgCount { expected, got } => {
+                set_method_argument_length_exception(expected, got, *pc);
+                EXCEPTION
+            }
+            RunMethodResult::InvalidArgType {
+                index,
+                expected,
+                got,
+            } => {
+                let message = format!(
+                    "Method call failed: expected argument of type '{}', got '{}'.",
+                    expected, got
+                );
+                let extra = index as usize + 2;
+                EXCEPTION_LOG.lock().unwrap().push_raw(message, *pc, extra);
+                EXCEPTION
+            }
+            RunMethodResult::ExceptionOccurred => {
+                let message = format!("An exception occurred while calling the method '{}'.", name);
+                EXCEPTION_LOG.lock().unwrap().push_raw(message, *pc, 1);
+                EXCEPTION
+            }
+        }
+    }
+
     pub(super) fn find_unary_method(name: &'static str, value: &Object) -> Option<TableMethod> {
         if let Table(ref tbl) = value {
             if let Some(method) = tbl.get_method(name) {
@@ -760,55 +935,84 @@ mod util {
 
     #[allow(clippy::type_complexity)]
     fn exec_function_with_core(
-        func: object::Function,
+        mut func: object::Function,
         args: impl ExactSizeIterator<Item = Object>,
         context: LoopContextRef,
         post_exec: Option<Box<dyn FnOnce(Object) -> Result<Object, ()>>>,
     ) -> Status {
-        let (pc, exe, runtime) = context;
+        let (pc, exe, runtime, dst) = context;
 
         if func.param_len() != args.len() as u8 {
             set_function_argument_length_exception(func.param_len(), args.len(), *pc);
             return EXCEPTION;
         }
 
+        if runtime.leave_hook.len() >= runtime.max_call_depth {
+            set_stack_overflow_exception(runtime.max_call_depth, *pc);
+            return EXCEPTION;
+        }
+
+        count_call(&mut func);
+
+        // A function of another executable is run in the same loop, instead of a nested `loop_()`
+        // which consumes the native stack. The caller's executable is restored by `Leave`.
         let next_exe = func.executable();
-        if exe.ptr_eq(next_exe) {
-            runtime.leave_hook.set(*pc, post_exec);
-            runtime.local_table.push_scope();
-            for env_obj in func.environment() {
-                runtime.local_table.add_ref(Rc::clone(env_obj));
-            }
-            for arg in args {
-                runtime.local_table.add(arg);
-            }
-            let next_pc = func.start_index();
-            *pc = next_pc;
+        let caller_exe = if exe.ptr_eq(next_exe) {
+            None
         } else {
-            let result = {
-                let mut runtime = Runtime::new();
-                for env_obj in func.environment() {
-                    runtime.local_table.add_ref(Rc::clone(env_obj));
-                }
-                for arg in args {
-                    runtime.local_table.add(arg);
-                }
-                loop_(Executable::clone(next_exe), &mut runtime).map_err(|_| {
-                    let message = "Error occurred while calling function.".to_string();
-                    EXCEPTION_LOG.lock().unwrap().push_raw(message, *pc, 0);
-                })?;
-                let mut result = runtime.stack.pop();
-                if let Some(post_exec) = post_exec {
-                    result = post_exec(result)?;
-                }
-                result
-            };
-            runtime.stack.push(result);
-            *pc += 1;
+            runtime.next_exe = Some(Executable::clone(next_exe));
+            Some(Executable::clone(exe))
+        };
+        runtime.leave_hook.set(*pc + 1, dst, post_exec, caller_exe);
+        runtime.local_table.push_frame();
+        for upvalue in func.environment() {
+            runtime.local_table.add_upvalue(upvalue.clone());
         }
+        for arg in args {
+            runtime.local_table.add(arg);
+        }
+        *pc = func.start_index();
         CONTINUE
     }
 
+    /// Runs `func` of the running executable in the current frame instead of a new one.
+    /// (See `ICode::TailCall`)
+    pub(super) fn exec_function_in_frame(
+        mut func: object::Function,
+        args: impl ExactSizeIterator<Item = Object>,
+        context: LoopContextRef,
+    ) -> Status {
+        let (pc, _, runtime, _) = context;
+
+        if func.param_len() != args.len() as u8 {
+            set_function_argument_length_exception(func.param_len(), args.len(), *pc);
+            return EXCEPTION;
+        }
+
+        count_call(&mut func);
+
+        // The caller's leave hook is kept, so the callee leaves to the caller of the frame.
+        runtime.local_table.truncate(Reg::new(0));
+        for upvalue in func.environment() {
+            runtime.local_table.add_upvalue(upvalue.clone());
+        }
+        for arg in args {
+            runtime.local_table.add(arg);
+        }
+        *pc = func.start_index();
+        CONTINUE
+    }
+
+    /// Counts the call of `func`, and specializes it if it gets hot.
+    /// (See `tier`)
+    fn count_call(func: &mut object::Function) {
+        func.inc_call_count();
+        if func.call_count() == tier::HOT_CALL_COUNT {
+            let range = func.start_index()..func.end_index();
+            tier::specialize(func.executable(), range);
+        }
+    }
+
     pub(super) fn exec_rust_function<I>(
         func: object::RustFunction,
         args: I,
@@ -846,21 +1050,36 @@ mod util {
         context: LoopContextRef,
         post_exec: Option<Box<dyn FnOnce(Object) -> Result<Object, ()>>>,
     ) -> Status {
-        let (pc, _, runtime) = context;
+        let (pc, _, runtime, dst) = context;
 
-        if func.param_len() != args.len() as u8 {
-            set_function_argument_length_exception(func.param_len(), args.len(), *pc);
+        if !func.accepts(args.len()) {
+            if func.optional_param_len() == 0 {
+                set_function_argument_length_exception(func.param_len(), args.len(), *pc);
+            } else {
+                let message = format!(
+                    "Function call failed: expected {} to {} arguments, got {}.",
+                    func.param_len(),
+                    func.param_len() + func.optional_param_len(),
+                    args.len()
+                );
+                EXCEPTION_LOG.lock().unwrap().push_raw(message, *pc, 0);
+            }
             return EXCEPTION;
         }
 
-        let mut result = func.call(args).map_err(|err| {
-            let message = format!("Rust function call failed:\n{:?}", err);
-            EXCEPTION_LOG.lock().unwrap().push_raw(message, *pc, 0);
-        })?;
+        let mut result = func
+            .call(args)
+            .map_err(|err| match err.downcast::<stdlib::Exit>() {
+                Ok(stdlib::Exit(code)) => runtime.exit_request = Some(code),
+                Err(err) => {
+                    let message = format!("Rust function call failed:\n{:#}", err);
+                    EXCEPTION_LOG.lock().unwrap().push_raw(message, *pc, 0);
+                }
+            })?;
         if let Some(post_exec) = post_exec {
             result = post_exec(result)?;
         }
-        runtime.stack.push(result);
+        runtime.local_table.set(dst, result);
         *pc += 1;
         CONTINUE
     }
@@ -912,6 +1131,15 @@ mod util {
         EXCEPTION_LOG.lock().unwrap().push_raw(message, pc, 0);
     }
 
+    #[cold]
+    fn set_stack_overflow_exception(max_call_depth: usize, pc: usize) {
+        let message = format!(
+            "Stack overflow: the call depth exceeds the limit ({}).",
+            max_call_depth
+        );
+        EXCEPTION_LOG.lock().unwrap().push_raw(message, pc, 0);
+    }
+
     #[cold]
     fn set_function_argument_length_exception(expected: u8, got: usize, pc: usize) {
         let message = format!(
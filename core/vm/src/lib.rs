@@ -2,7 +2,7 @@
 
 use core::iter;
 use foundation::{
-    il::{Executable, ICode, Module},
+    il::{Executable, ICode, Module, Reg},
     object::*,
 };
 
@@ -14,99 +14,251 @@ pub use exception::*;
 
 mod builtin;
 
+mod stdio;
+pub use stdio::*;
+
+mod stdlib;
+pub use stdlib::set_args;
+
+mod tier;
+
 type Status = Result<(), ()>;
 const EXCEPTION: Status = Err(());
 const CONTINUE: Status = Ok(());
 
 mod exec_icode;
 
-/// Execute the module.
-/// If the return value is `None`, it means that the execution was interrupted by an exception. You
-/// can get the exception information from `vm::EXCEPTION_LOG`.
-pub fn execute(module: &Module) -> Option<Object> {
-    let mut runtime = Runtime::new();
-    for (_, rfunc) in module.default_rfuncs() {
-        runtime.local_table.add((*rfunc).into());
+/// The reason why the execution of a module was interrupted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Interrupt {
+    /// An exception occurred. You can get the exception information from `vm::EXCEPTION_LOG`.
+    Exception,
+    /// The script called `os.exit(code)`.
+    Exit(i32),
+}
+
+/// The default maximum depth of the nested function calls. (See `Options::max_call_depth`)
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 100_000;
+
+/// The options of an execution. (See `execute_with()`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Options {
+    /// The maximum depth of the nested function calls. A call exceeding it raises a
+    /// "stack overflow" exception.
+    pub max_call_depth: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+        }
+    }
+}
+
+/// Execute the module with the default options.
+pub fn execute(module: &Module) -> Result<Object, Interrupt> {
+    execute_with(module, &Options::default())
+}
+
+/// Execute the module with `options`.
+pub fn execute_with(module: &Module, options: &Options) -> Result<Object, Interrupt> {
+    // Scripts run with the batched cycle collection, which is much cheaper than running mark and
+    // sweep on every drop. The host's mode is restored after the execution.
+    let gc_mode = gc::mode();
+    gc::set_mode(gc::Mode::Batched {
+        threshold: gc::Mode::DEFAULT_THRESHOLD,
+    });
+
+    let mut runtime = Runtime::new(options.max_call_depth);
+    for name in module.default_globals() {
+        runtime.local_table.add(stdlib::load(name));
     }
     let exe = Executable::clone(module.executable());
-    match loop_(exe, &mut runtime) {
-        Ok(_) => {
-            let result = runtime.stack.pop();
-            Some(result)
-        }
+    let status = loop_(exe, 0, &mut runtime);
+    runtime.clear();
+    // Finalize the tables freed by clearing the runtime, i.e. the globals and locals of the script,
+    // and the remaining garbage cycles. `os.exit` skips the finalizers.
+    let status = if runtime.is_exit_requested() {
+        discard_finalizable();
+        status
+    } else {
+        let finalized = finalize_rest(&mut runtime);
+        status.and_then(|result| finalized.map(|_| result))
+    };
+    gc::set_mode(gc_mode);
+    // The output written by the script may be lost if the host exits without flushing.
+    let _ = STDIO.with_borrow_mut(|stdio| stdio.flush());
+
+    match status {
+        Ok(result) => Ok(result),
         Err(_) => {
+            if let Some(code) = runtime.exit_request {
+                return Err(Interrupt::Exit(code));
+            }
             EXCEPTION_LOG.lock().unwrap().fixup(module.source_info());
-            None
+            Err(Interrupt::Exception)
         }
     }
 }
 
-fn loop_(exe: Executable, runtime: &mut Runtime) -> Status {
+/// Runs the finalizers (`__gc` methods) of the tables queued by the cycle collector.
+/// (See `gc::take_finalizable()`)
+///
+/// The finalizers run in `runtime` on top of the current frame, since the functions may refer to
+/// the locals of the running frames by the open upvalues.
+fn run_finalizers(pc: usize, runtime: &mut Runtime) -> Status {
+    fn set_finalizer_exception(message: String, pc: usize, runtime: &Runtime) {
+        if runtime.is_exit_requested() {
+            return;
+        }
+        EXCEPTION_LOG.lock().unwrap().push_raw(message, pc, 0);
+    }
+
+    while let Some(table) = gc::take_finalizable() {
+        let Some(method) = table.get_method("__gc").cloned() else {
+            continue;
+        };
+        match method {
+            TableMethod::Native(func) => {
+                if !func.accepts(1) {
+                    let message = "The finalizer '__gc' must take 1 argument.".to_string();
+                    set_finalizer_exception(message, pc, runtime);
+                    return EXCEPTION;
+                }
+                let args = Box::new(iter::once(Object::Table(table)));
+                if let Err(err) = func.call(args) {
+                    match err.downcast::<stdlib::Exit>() {
+                        Ok(stdlib::Exit(code)) => runtime.exit_request = Some(code),
+                        Err(err) => {
+                            let message = format!("Finalizer '__gc' failed:\n{:#}", err);
+                            set_finalizer_exception(message, pc, runtime);
+                        }
+                    }
+                    return EXCEPTION;
+                }
+            }
+            TableMethod::Custom(func) => {
+                if func.param_len() != 1 {
+                    let message = format!(
+                        "The finalizer '__gc' must take 1 argument, but takes {}.",
+                        func.param_len()
+                    );
+                    set_finalizer_exception(message, pc, runtime);
+                    return EXCEPTION;
+                }
+                runtime.local_table.push_frame();
+                for upvalue in func.environment() {
+                    runtime.local_table.add_upvalue(upvalue.clone());
+                }
+                runtime.local_table.add(Object::Table(table));
+                let exe = Executable::clone(func.executable());
+                loop_(exe, func.start_index(), runtime).map_err(|_| {
+                    let message = "Error occurred while running the finalizer '__gc'.".to_string();
+                    set_finalizer_exception(message, pc, runtime);
+                })?;
+                runtime.local_table.pop_frame();
+            }
+        }
+    }
+    CONTINUE
+}
+
+/// Runs the finalizers until nothing is left to finalize at the end of the execution.
+fn finalize_rest(runtime: &mut Runtime) -> Status {
+    let status = loop {
+        if run_finalizers(0, runtime).is_err() {
+            break EXCEPTION;
+        }
+        if gc::collect() == 0 && !gc::has_finalizable() {
+            break CONTINUE;
+        }
+    };
+    if status.is_err() {
+        discard_finalizable();
+    }
+    status
+}
+
+/// Drops the tables queued for finalization without running their finalizers.
+fn discard_finalizable() {
+    loop {
+        while gc::take_finalizable().is_some() {}
+        if gc::collect() == 0 && !gc::has_finalizable() {
+            break;
+        }
+    }
+}
+
+/// Runs `exe` from `pc` until the outermost `Leave`, and returns the left value.
+///
+/// The calls in progress when this is called, i.e. the ones of the caller of a finalizer, are not
+/// left by this.
+fn loop_(mut exe: Executable, mut pc: usize, runtime: &mut Runtime) -> Result<Object, ()> {
     use ICode::*;
 
-    let mut pc = 0;
-    let exe_len = exe.len();
+    let mut exe_len = exe.len();
+    let floor = runtime.leave_hook.len();
 
     loop {
+        // Finalizers run between instructions, right after the tables are freed.
+        if gc::has_finalizable() {
+            run_finalizers(pc, runtime)?;
+        }
+        // A function of another executable was called. (See `exec_function_with_core()`)
+        if let Some(next_exe) = runtime.next_exe.take() {
+            exe = next_exe;
+            exe_len = exe.len();
+        }
         let code = unsafe {
             assert!(pc < exe_len);
             exe.fetch(pc)
         };
-        match code {
-            LoadIntObject(x) => {
-                runtime.stack.push(Object::Int(*x));
-                pc += 1;
-            }
-            LoadFloatObject(x) => {
-                runtime.stack.push(Object::Float(*x));
+        let locals = &mut runtime.local_table;
+        // The operands are copied out of the instruction, since `tier` rewrites the instructions
+        // of a function while the function runs. Only the instructions which are never rewritten
+        // are borrowed by `ref`, except `CallMethod` which clones the name before the call.
+        match *code {
+            LoadIntObject(dst, x) => {
+                locals.set(dst, Object::Int(x));
                 pc += 1;
             }
-            LoadStringObject(x) => {
-                runtime.stack.push(Object::String(x.clone()));
+            LoadFloatObject(dst, x) => {
+                locals.set(dst, Object::Float(x));
                 pc += 1;
             }
-            LoadBoolObject(x) => {
-                runtime.stack.push(Object::Bool(*x));
+            LoadStringObject(dst, ref x) => {
+                locals.set(dst, Object::String(x.clone()));
                 pc += 1;
             }
-            LoadNilObject => {
-                runtime.stack.push(Object::Nil);
+            LoadBoolObject(dst, x) => {
+                locals.set(dst, Object::Bool(x));
                 pc += 1;
             }
-            LoadLocal(id) => {
-                let value = runtime.local_table.get(*id).clone();
-                runtime.stack.push(value);
+            LoadNilObject(dst) => {
+                locals.set(dst, Object::Nil);
                 pc += 1;
             }
 
-            Unload => {
-                runtime.stack.pop();
+            Move(dst, src) => {
+                let value = locals.read(src);
+                locals.set(dst, value);
                 pc += 1;
             }
 
-            StoreLocal(id) => {
-                let value = runtime.stack.pop();
-                runtime.local_table.set(*id, value);
-                pc += 1;
-            }
-            StoreNewLocal => {
-                let value = runtime.stack.pop();
-                runtime.local_table.add(value);
-                pc += 1;
-            }
-
-            MakeArray(len) => {
-                let mut array = Array::with_capacity(*len);
-                for _ in 0..*len {
-                    array.push(runtime.stack.pop());
+            MakeArray(base, len) => {
+                let mut elements = Vec::with_capacity(len);
+                for i in 0..len {
+                    elements.push(locals.read(base.offset(i).take()));
                 }
-                runtime.stack.push(array.into());
+                locals.set(base, Array::from(elements).into());
                 pc += 1;
             }
-            MakeTable(len) => {
-                let mut table = Table::with_capacity(*len);
-                for i in 0..*len {
-                    let (key, value) = runtime.stack.pop2();
+            MakeTable(base, len) => {
+                let mut table = Table::with_capacity(len);
+                for i in 0..len {
+                    let key = locals.read(base.offset(2 * i).take());
+                    let value = locals.read(base.offset(2 * i + 1).take());
                     if let Object::String(key) = key {
                         table.insert(key, value);
                     } else {
@@ -115,119 +267,224 @@ fn loop_(exe: Executable, runtime: &mut Runtime) -> Status {
                             key.type_name()
                         );
                         EXCEPTION_LOG.lock().unwrap().push_raw(message, pc, i);
-                        return EXCEPTION;
+                        return Err(());
                     }
                 }
-                runtime.stack.push(table.into());
+                locals.set(base, table.into());
                 pc += 1;
             }
 
-            DropLocal(count) => {
-                runtime.local_table.drop(*count);
+            DropLocal(reg) => {
+                locals.truncate(reg);
+                pc += 1;
+            }
+            CloseUpvalues(reg) => {
+                locals.close_upvalues(reg);
                 pc += 1;
             }
 
             Jump(offset) => {
-                pc = (pc as isize + *offset) as usize;
+                pc = (pc as isize + offset) as usize;
             }
-            JumpIfTrue(offset) => {
-                let value = runtime.stack.pop();
-                if value.is_truthy() {
-                    pc = (pc as isize + *offset) as usize;
+            JumpIfTrue(reg, offset) => {
+                if locals.read(reg).is_truthy() {
+                    pc = (pc as isize + offset) as usize;
                 } else {
                     pc += 1;
                 }
             }
-            JumpIfFalse(offset) => {
-                let value = runtime.stack.pop();
-                if value.is_falsey() {
-                    pc = (pc as isize + *offset) as usize;
+            JumpIfFalse(reg, offset) => {
+                if locals.read(reg).is_falsey() {
+                    pc = (pc as isize + offset) as usize;
                 } else {
                     pc += 1;
                 }
             }
-
-            Call(arg_len) => match arg_len {
-                0 => {
-                    let calee = runtime.stack.pop();
-                    exec_icode::call(calee, [], (&mut pc, &exe, runtime))?;
+            JumpIfNil(reg, offset) => {
+                if matches!(locals.read(reg), Object::Nil) {
+                    pc = (pc as isize + offset) as usize;
+                } else {
+                    pc += 1;
+                }
+            }
+            JumpIfNotNil(reg, offset) => {
+                if !matches!(locals.read(reg), Object::Nil) {
+                    pc = (pc as isize + offset) as usize;
+                } else {
+                    pc += 1;
+                }
+            }
+            JumpIfNotLess(dst, lhs, rhs, offset) => {
+                let (lhs, rhs) = (locals.read(lhs), locals.read(rhs));
+                let context = (&mut pc, &exe, &mut *runtime, dst);
+                exec_icode::jump_if_not_less(lhs, rhs, offset, context)?;
+            }
+            JumpIfNotLessEq(dst, lhs, rhs, offset) => {
+                let (lhs, rhs) = (locals.read(lhs), locals.read(rhs));
+                let context = (&mut pc, &exe, &mut *runtime, dst);
+                exec_icode::jump_if_not_less_eq(lhs, rhs, offset, context)?;
+            }
+            JumpIfNotGreater(dst, lhs, rhs, offset) => {
+                let (lhs, rhs) = (locals.read(lhs), locals.read(rhs));
+                let context = (&mut pc, &exe, &mut *runtime, dst);
+                exec_icode::jump_if_not_greater(lhs, rhs, offset, context)?;
+            }
+            JumpIfNotGreaterEq(dst, lhs, rhs, offset) => {
+                let (lhs, rhs) = (locals.read(lhs), locals.read(rhs));
+                let context = (&mut pc, &exe, &mut *runtime, dst);
+                exec_icode::jump_if_not_greater_eq(lhs, rhs, offset, context)?;
+            }
+            JumpIfNotEq(dst, lhs, rhs, offset) => {
+                let (lhs, rhs) = (locals.read(lhs), locals.read(rhs));
+                let context = (&mut pc, &exe, &mut *runtime, dst);
+                exec_icode::jump_if_not_eq(lhs, rhs, offset, context)?;
+            }
+            JumpIfNotIntLess(dst, lhs, rhs, offset) => match (locals.read(lhs), locals.read(rhs)) {
+                (Object::Int(lhs), Object::Int(rhs)) if lhs < rhs => pc += 2,
+                (Object::Int(_), Object::Int(_)) => pc = (pc as isize + offset) as usize,
+                (lhs, rhs) => {
+                    tier::deoptimize(&exe, pc);
+                    let context = (&mut pc, &exe, &mut *runtime, dst);
+                    exec_icode::jump_if_not_less(lhs, rhs, offset, context)?;
                 }
-                1 => {
-                    let arg = runtime.stack.pop();
-                    let calee = runtime.stack.pop();
-                    exec_icode::call(calee, [arg], (&mut pc, &exe, runtime))?;
+            },
+            JumpIfNotIntLessEq(dst, lhs, rhs, offset) => {
+                match (locals.read(lhs), locals.read(rhs)) {
+                    (Object::Int(lhs), Object::Int(rhs)) if lhs <= rhs => pc += 2,
+                    (Object::Int(_), Object::Int(_)) => pc = (pc as isize + offset) as usize,
+                    (lhs, rhs) => {
+                        tier::deoptimize(&exe, pc);
+                        let context = (&mut pc, &exe, &mut *runtime, dst);
+                        exec_icode::jump_if_not_less_eq(lhs, rhs, offset, context)?;
+                    }
                 }
-                2 => {
-                    let (arg1, arg2) = runtime.stack.pop2();
-                    let calee = runtime.stack.pop();
-                    exec_icode::call(calee, [arg1, arg2], (&mut pc, &exe, runtime))?;
+            }
+            JumpIfNotIntGreater(dst, lhs, rhs, offset) => {
+                match (locals.read(lhs), locals.read(rhs)) {
+                    (Object::Int(lhs), Object::Int(rhs)) if lhs > rhs => pc += 2,
+                    (Object::Int(_), Object::Int(_)) => pc = (pc as isize + offset) as usize,
+                    (lhs, rhs) => {
+                        tier::deoptimize(&exe, pc);
+                        let context = (&mut pc, &exe, &mut *runtime, dst);
+                        exec_icode::jump_if_not_greater(lhs, rhs, offset, context)?;
+                    }
                 }
-                3 => {
-                    let (arg1, arg2, arg3) = runtime.stack.pop3();
-                    let calee = runtime.stack.pop();
-                    exec_icode::call(calee, [arg1, arg2, arg3], (&mut pc, &exe, runtime))?;
+            }
+            JumpIfNotIntGreaterEq(dst, lhs, rhs, offset) => {
+                match (locals.read(lhs), locals.read(rhs)) {
+                    (Object::Int(lhs), Object::Int(rhs)) if lhs >= rhs => pc += 2,
+                    (Object::Int(_), Object::Int(_)) => pc = (pc as isize + offset) as usize,
+                    (lhs, rhs) => {
+                        tier::deoptimize(&exe, pc);
+                        let context = (&mut pc, &exe, &mut *runtime, dst);
+                        exec_icode::jump_if_not_greater_eq(lhs, rhs, offset, context)?;
+                    }
                 }
-                _ => {
-                    let mut args = Vec::with_capacity(*arg_len as usize);
-                    for _ in 0..*arg_len {
-                        args.push(runtime.stack.pop());
+            }
+
+            Call(base, arg_len) => {
+                let calee = locals.read(base.take());
+                let mut arg = |i: usize| locals.read(base.offset(i).take());
+                match arg_len {
+                    0 => {
+                        let context = (&mut pc, &exe, &mut *runtime, base);
+                        exec_icode::call(calee, [], context)?;
+                    }
+                    1 => {
+                        let args = [arg(1)];
+                        exec_icode::call(calee, args, (&mut pc, &exe, runtime, base))?;
+                    }
+                    2 => {
+                        let args = [arg(1), arg(2)];
+                        exec_icode::call(calee, args, (&mut pc, &exe, runtime, base))?;
+                    }
+                    3 => {
+                        let args = [arg(1), arg(2), arg(3)];
+                        exec_icode::call(calee, args, (&mut pc, &exe, runtime, base))?;
+                    }
+                    _ => {
+                        let args = (1..=arg_len as usize).map(&mut arg).collect::<Vec<_>>();
+                        exec_icode::call(calee, args, (&mut pc, &exe, runtime, base))?;
                     }
-                    let calee = runtime.stack.pop();
-                    args.reverse();
-                    exec_icode::call(calee, args, (&mut pc, &exe, runtime))?;
                 }
-            },
-            CallMethod(arg_len, name) => match arg_len {
-                0 => {
-                    let receiver = runtime.stack.pop();
-                    exec_icode::call_method(receiver, name, [], (&mut pc, &exe, runtime))?;
-                }
-                1 => {
-                    let arg = runtime.stack.pop();
-                    let receiver = runtime.stack.pop();
-                    exec_icode::call_method(receiver, name, [arg], (&mut pc, &exe, runtime))?;
-                }
-                2 => {
-                    let (arg1, arg2) = runtime.stack.pop2();
-                    let receiver = runtime.stack.pop();
-                    let args = [arg1, arg2];
-                    exec_icode::call_method(receiver, name, args, (&mut pc, &exe, runtime))?;
-                }
-                3 => {
-                    let (arg1, arg2, arg3) = runtime.stack.pop3();
-                    let receiver = runtime.stack.pop();
-                    let args = [arg1, arg2, arg3];
-                    exec_icode::call_method(receiver, name, args, (&mut pc, &exe, runtime))?;
-                }
-                _ => {
-                    let mut args = Vec::with_capacity(*arg_len as usize);
-                    for _ in 0..*arg_len {
-                        args.push(runtime.stack.pop());
+            }
+            TailCall(base, arg_len) => {
+                let calee = locals.read(base.take());
+                let mut arg = |i: usize| locals.read(base.offset(i).take());
+                match arg_len {
+                    0 => {
+                        let context = (&mut pc, &exe, &mut *runtime, base);
+                        exec_icode::tail_call(calee, [], context)?;
+                    }
+                    1 => {
+                        let args = [arg(1)];
+                        exec_icode::tail_call(calee, args, (&mut pc, &exe, runtime, base))?;
+                    }
+                    2 => {
+                        let args = [arg(1), arg(2)];
+                        exec_icode::tail_call(calee, args, (&mut pc, &exe, runtime, base))?;
+                    }
+                    _ => {
+                        let args = (1..=arg_len as usize).map(&mut arg).collect::<Vec<_>>();
+                        exec_icode::tail_call(calee, args, (&mut pc, &exe, runtime, base))?;
                     }
-                    let receiver = runtime.stack.pop();
-                    args.reverse();
-                    exec_icode::call_method(receiver, name, args, (&mut pc, &exe, runtime))?;
                 }
-            },
+            }
+            CallMethod(base, arg_len, ref name) => {
+                let receiver = locals.read(base.take());
+                let mut arg = |i: usize| locals.read(base.offset(i).take());
+                let args = match arg_len {
+                    0 => Vec::new(),
+                    _ => (1..=arg_len as usize).map(&mut arg).collect::<Vec<_>>(),
+                };
+                let name = name.clone();
+                let context = (&mut pc, &exe, &mut *runtime, base);
+                exec_icode::call_method(receiver, &name, args, context)?;
+            }
+            CallNumberMethod(base, arg_len, index) => {
+                let receiver = locals.read(base.take());
+                let args = (1..=arg_len as usize)
+                    .map(|i| locals.read(base.offset(i).take()))
+                    .collect::<Vec<_>>();
+                let context = (&mut pc, &exe, &mut *runtime, base);
+                exec_icode::call_number_method(receiver, index, args, context)?;
+            }
 
-            SetItem => {
-                let (container, key, value) = runtime.stack.pop3();
-                exec_icode::set_item(container, key, value, (&mut pc, &exe, runtime))?;
+            SetItem(container, key, value) => {
+                let container = locals.read(container);
+                let key = locals.read(key);
+                let value = locals.read(value);
+                // `SetItem` has no result, so `dst` is not used.
+                let context = (&mut pc, &exe, &mut *runtime, Reg::new(0));
+                exec_icode::set_item(container, key, value, context)?;
+            }
+            GetItem(dst, container, key) => {
+                let container = locals.read(container);
+                let key = locals.read(key);
+                exec_icode::get_item(container, key, (&mut pc, &exe, runtime, dst))?;
+            }
+            TestTable(dst, src) => {
+                let result = matches!(locals.read(src), Object::Table(_));
+                locals.set(dst, Object::Bool(result));
+                pc += 1;
             }
-            GetItem => {
-                let (container, key) = runtime.stack.pop2();
-                exec_icode::get_item(container, key, (&mut pc, &exe, runtime))?;
+            TestArray(dst, src, len) => {
+                let value = locals.read(src);
+                let result = matches!(value, Object::Array(array) if array.len() == len);
+                locals.set(dst, Object::Bool(result));
+                pc += 1;
             }
 
-            SetMethod(name) => {
-                let (mut table, func) = runtime.stack.pop2();
+            SetMethod(table, func, ref name) => {
+                let func = locals.read(func);
+                let mut table = locals.read(table);
                 let table: &mut Object = &mut table;
                 match (table, func) {
                     (Object::Table(table), Object::Function(func)) => {
-                        table.set_method(name.as_str().into(), func);
+                        table.set_method(name.clone(), func);
                     }
                     (Object::Table(table), Object::RustFunction(func)) => {
-                        table.set_method(name.as_str().into(), func);
+                        table.set_method(name.clone(), func);
                     }
                     (Object::Table(_), obj) => panic!(
                         "[BUG] `SetMethod` is called with the object of type '{}'",
@@ -239,151 +496,255 @@ fn loop_(exe: Executable, runtime: &mut Runtime) -> Status {
                             other.type_name()
                         );
                         EXCEPTION_LOG.lock().unwrap().push_raw(message, pc, 0);
-                        break EXCEPTION;
+                        break Err(());
                     }
                 }
                 pc += 1;
             }
 
-            Add => {
-                let (lhs, rhs) = runtime.stack.pop2();
-                exec_icode::add(lhs, rhs, (&mut pc, &exe, runtime))?;
+            Add(dst, lhs, rhs) => {
+                let (lhs, rhs) = (locals.read(lhs), locals.read(rhs));
+                exec_icode::add(lhs, rhs, (&mut pc, &exe, runtime, dst))?;
             }
-            Sub => {
-                let (lhs, rhs) = runtime.stack.pop2();
-                exec_icode::sub(lhs, rhs, (&mut pc, &exe, runtime))?;
+            Sub(dst, lhs, rhs) => {
+                let (lhs, rhs) = (locals.read(lhs), locals.read(rhs));
+                exec_icode::sub(lhs, rhs, (&mut pc, &exe, runtime, dst))?;
             }
-            Mul => {
-                let (lhs, rhs) = runtime.stack.pop2();
-                exec_icode::mul(lhs, rhs, (&mut pc, &exe, runtime))?;
+            AddInt(dst, lhs, rhs) => match locals.read(lhs) {
+                Object::Int(lhs) => {
+                    locals.set(dst, Object::Int(lhs + rhs));
+                    pc += 1;
+                }
+                lhs => exec_icode::add(lhs, Object::Int(rhs), (&mut pc, &exe, runtime, dst))?,
+            },
+            SubInt(dst, lhs, rhs) => match locals.read(lhs) {
+                Object::Int(lhs) => {
+                    locals.set(dst, Object::Int(lhs - rhs));
+                    pc += 1;
+                }
+                lhs => exec_icode::sub(lhs, Object::Int(rhs), (&mut pc, &exe, runtime, dst))?,
+            },
+            Mul(dst, lhs, rhs) => {
+                let (lhs, rhs) = (locals.read(lhs), locals.read(rhs));
+                exec_icode::mul(lhs, rhs, (&mut pc, &exe, runtime, dst))?;
             }
-            Div => {
-                let (lhs, rhs) = runtime.stack.pop2();
-                exec_icode::div(lhs, rhs, (&mut pc, &exe, runtime))?;
+            Div(dst, lhs, rhs) => {
+                let (lhs, rhs) = (locals.read(lhs), locals.read(rhs));
+                exec_icode::div(lhs, rhs, (&mut pc, &exe, runtime, dst))?;
             }
-            Mod => {
-                let (lhs, rhs) = runtime.stack.pop2();
-                exec_icode::r#mod(lhs, rhs, (&mut pc, &exe, runtime))?;
+            Mod(dst, lhs, rhs) => {
+                let (lhs, rhs) = (locals.read(lhs), locals.read(rhs));
+                exec_icode::r#mod(lhs, rhs, (&mut pc, &exe, runtime, dst))?;
             }
 
-            Unm => {
-                let value = runtime.stack.pop();
-                exec_icode::unm(value, (&mut pc, &exe, runtime))?;
+            Unm(dst, src) => {
+                let value = locals.read(src);
+                exec_icode::unm(value, (&mut pc, &exe, runtime, dst))?;
             }
-            Unp => {
-                let value = runtime.stack.pop();
-                exec_icode::unp(value, (&mut pc, &exe, runtime))?;
+            Unp(dst, src) => {
+                let value = locals.read(src);
+                exec_icode::unp(value, (&mut pc, &exe, runtime, dst))?;
             }
-            Not => {
-                let value = runtime.stack.pop();
-                exec_icode::not(value, (&mut pc, &exe, runtime))?;
+            Not(dst, src) => {
+                let value = locals.read(src);
+                exec_icode::not(value, (&mut pc, &exe, runtime, dst))?;
             }
 
-            Eq => {
-                let (lhs, rhs) = runtime.stack.pop2();
-                exec_icode::eq(lhs, rhs, (&mut pc, &exe, runtime))?;
+            Eq(dst, lhs, rhs) => {
+                let (lhs, rhs) = (locals.read(lhs), locals.read(rhs));
+                exec_icode::eq(lhs, rhs, (&mut pc, &exe, runtime, dst))?;
             }
-            NotEq => {
-                let (lhs, rhs) = runtime.stack.pop2();
-                exec_icode::not_eq(lhs, rhs, (&mut pc, &exe, runtime))?;
+            NotEq(dst, lhs, rhs) => {
+                let (lhs, rhs) = (locals.read(lhs), locals.read(rhs));
+                exec_icode::not_eq(lhs, rhs, (&mut pc, &exe, runtime, dst))?;
             }
 
-            Less => {
-                let (lhs, rhs) = runtime.stack.pop2();
-                exec_icode::less(lhs, rhs, (&mut pc, &exe, runtime))?;
+            Less(dst, lhs, rhs) => {
+                let (lhs, rhs) = (locals.read(lhs), locals.read(rhs));
+                exec_icode::less(lhs, rhs, (&mut pc, &exe, runtime, dst))?;
             }
-            LessEq => {
-                let (lhs, rhs) = runtime.stack.pop2();
-                exec_icode::less_eq(lhs, rhs, (&mut pc, &exe, runtime))?;
+            LessEq(dst, lhs, rhs) => {
+                let (lhs, rhs) = (locals.read(lhs), locals.read(rhs));
+                exec_icode::less_eq(lhs, rhs, (&mut pc, &exe, runtime, dst))?;
             }
-            Greater => {
-                let (lhs, rhs) = runtime.stack.pop2();
-                exec_icode::greater(lhs, rhs, (&mut pc, &exe, runtime))?;
+            Greater(dst, lhs, rhs) => {
+                let (lhs, rhs) = (locals.read(lhs), locals.read(rhs));
+                exec_icode::greater(lhs, rhs, (&mut pc, &exe, runtime, dst))?;
             }
-            GreaterEq => {
-                let (lhs, rhs) = runtime.stack.pop2();
-                exec_icode::greater_eq(lhs, rhs, (&mut pc, &exe, runtime))?;
+            GreaterEq(dst, lhs, rhs) => {
+                let (lhs, rhs) = (locals.read(lhs), locals.read(rhs));
+                exec_icode::greater_eq(lhs, rhs, (&mut pc, &exe, runtime, dst))?;
             }
 
-            Concat => {
-                let (lhs, rhs) = runtime.stack.pop2();
-                exec_icode::concat(lhs, rhs, (&mut pc, &exe, runtime))?;
+            // The specialized forms. (See `tier`)
+            IntAdd(dst, lhs, rhs) => match (locals.read(lhs), locals.read(rhs)) {
+                (Object::Int(lhs), Object::Int(rhs)) => {
+                    locals.set(dst, Object::Int(lhs + rhs));
+                    pc += 1;
+                }
+                (lhs, rhs) => {
+                    tier::deoptimize(&exe, pc);
+                    exec_icode::add(lhs, rhs, (&mut pc, &exe, runtime, dst))?;
+                }
+            },
+            IntSub(dst, lhs, rhs) => match (locals.read(lhs), locals.read(rhs)) {
+                (Object::Int(lhs), Object::Int(rhs)) => {
+                    locals.set(dst, Object::Int(lhs - rhs));
+                    pc += 1;
+                }
+                (lhs, rhs) => {
+                    tier::deoptimize(&exe, pc);
+                    exec_icode::sub(lhs, rhs, (&mut pc, &exe, runtime, dst))?;
+                }
+            },
+            IntMul(dst, lhs, rhs) => match (locals.read(lhs), locals.read(rhs)) {
+                (Object::Int(lhs), Object::Int(rhs)) => {
+                    locals.set(dst, Object::Int(lhs * rhs));
+                    pc += 1;
+                }
+                (lhs, rhs) => {
+                    tier::deoptimize(&exe, pc);
+                    exec_icode::mul(lhs, rhs, (&mut pc, &exe, runtime, dst))?;
+                }
+            },
+            IntLess(dst, lhs, rhs) => match (locals.read(lhs), locals.read(rhs)) {
+                (Object::Int(lhs), Object::Int(rhs)) => {
+                    locals.set(dst, Object::Bool(lhs < rhs));
+                    pc += 1;
+                }
+                (lhs, rhs) => {
+                    tier::deoptimize(&exe, pc);
+                    exec_icode::less(lhs, rhs, (&mut pc, &exe, runtime, dst))?;
+                }
+            },
+            IntLessEq(dst, lhs, rhs) => match (locals.read(lhs), locals.read(rhs)) {
+                (Object::Int(lhs), Object::Int(rhs)) => {
+                    locals.set(dst, Object::Bool(lhs <= rhs));
+                    pc += 1;
+                }
+                (lhs, rhs) => {
+                    tier::deoptimize(&exe, pc);
+                    exec_icode::less_eq(lhs, rhs, (&mut pc, &exe, runtime, dst))?;
+                }
+            },
+            IntGreater(dst, lhs, rhs) => match (locals.read(lhs), locals.read(rhs)) {
+                (Object::Int(lhs), Object::Int(rhs)) => {
+                    locals.set(dst, Object::Bool(lhs > rhs));
+                    pc += 1;
+                }
+                (lhs, rhs) => {
+                    tier::deoptimize(&exe, pc);
+                    exec_icode::greater(lhs, rhs, (&mut pc, &exe, runtime, dst))?;
+                }
+            },
+            IntGreaterEq(dst, lhs, rhs) => match (locals.read(lhs), locals.read(rhs)) {
+                (Object::Int(lhs), Object::Int(rhs)) => {
+                    locals.set(dst, Object::Bool(lhs >= rhs));
+                    pc += 1;
+                }
+                (lhs, rhs) => {
+                    tier::deoptimize(&exe, pc);
+                    exec_icode::greater_eq(lhs, rhs, (&mut pc, &exe, runtime, dst))?;
+                }
+            },
+
+            Concat(dst, lhs, rhs) => {
+                let (lhs, rhs) = (locals.read(lhs), locals.read(rhs));
+                exec_icode::concat(lhs, rhs, (&mut pc, &exe, runtime, dst))?;
+            }
+            Format(dst, src, ref spec) => {
+                let value = locals.read(src);
+                exec_icode::format(value, spec, (&mut pc, &exe, runtime, dst))?;
             }
 
-            BitAnd => {
-                let (lhs, rhs) = runtime.stack.pop2();
-                exec_icode::bit_and(lhs, rhs, (&mut pc, &exe, runtime))?;
+            BitAnd(dst, lhs, rhs) => {
+                let (lhs, rhs) = (locals.read(lhs), locals.read(rhs));
+                exec_icode::bit_and(lhs, rhs, (&mut pc, &exe, runtime, dst))?;
             }
-            BitOr => {
-                let (lhs, rhs) = runtime.stack.pop2();
-                exec_icode::bit_or(lhs, rhs, (&mut pc, &exe, runtime))?;
+            BitOr(dst, lhs, rhs) => {
+                let (lhs, rhs) = (locals.read(lhs), locals.read(rhs));
+                exec_icode::bit_or(lhs, rhs, (&mut pc, &exe, runtime, dst))?;
             }
-            BitXor => {
-                let (lhs, rhs) = runtime.stack.pop2();
-                exec_icode::bit_xor(lhs, rhs, (&mut pc, &exe, runtime))?;
+            BitXor(dst, lhs, rhs) => {
+                let (lhs, rhs) = (locals.read(lhs), locals.read(rhs));
+                exec_icode::bit_xor(lhs, rhs, (&mut pc, &exe, runtime, dst))?;
             }
-            BitNot => {
-                let value = runtime.stack.pop();
-                exec_icode::bit_not(value, (&mut pc, &exe, runtime))?;
+            BitNot(dst, src) => {
+                let value = locals.read(src);
+                exec_icode::bit_not(value, (&mut pc, &exe, runtime, dst))?;
             }
 
-            ShiftL => {
-                let (lhs, rhs) = runtime.stack.pop2();
-                exec_icode::shift_l(lhs, rhs, (&mut pc, &exe, runtime))?;
+            ShiftL(dst, lhs, rhs) => {
+                let (lhs, rhs) = (locals.read(lhs), locals.read(rhs));
+                exec_icode::shift_l(lhs, rhs, (&mut pc, &exe, runtime, dst))?;
             }
-            ShiftR => {
-                let (lhs, rhs) = runtime.stack.pop2();
-                exec_icode::shift_r(lhs, rhs, (&mut pc, &exe, runtime))?;
+            ShiftR(dst, lhs, rhs) => {
+                let (lhs, rhs) = (locals.read(lhs), locals.read(rhs));
+                exec_icode::shift_r(lhs, rhs, (&mut pc, &exe, runtime, dst))?;
             }
 
-            GetIter => {
-                let value = runtime.stack.pop();
-                exec_icode::get_iter(value, (&mut pc, &exe, runtime))?;
+            GetIter(dst, src) => {
+                let value = locals.read(src);
+                exec_icode::get_iter(value, (&mut pc, &exe, runtime, dst))?;
             }
-            IterMoveNext => {
-                let iter = runtime.stack.pop();
-                exec_icode::iter_move_next(iter, (&mut pc, &exe, runtime))?;
+            IterMoveNext(dst, src) => {
+                let iter = locals.read(src);
+                exec_icode::iter_move_next(iter, (&mut pc, &exe, runtime, dst))?;
             }
-            IterCurrent => {
-                let iter = runtime.stack.pop();
-                exec_icode::iter_current(iter, (&mut pc, &exe, runtime))?;
+            IterCurrent(dst, src) => {
+                let iter = locals.read(src);
+                exec_icode::iter_current(iter, (&mut pc, &exe, runtime, dst))?;
             }
 
-            BeginFuncSection => {
+            BeginFuncSection(dst) => {
                 pc += 1;
-                let (param_len, start_pc) = match unsafe { exe.fetch(pc) } {
-                    FuncSetProperty(param_len, start_pc) => (*param_len, *start_pc),
+                let (param_len, start_pc, end_pc) = match unsafe { exe.fetch(pc) } {
+                    FuncSetProperty(param_len, start_pc, end_pc) => {
+                        (*param_len, *start_pc as usize, *end_pc as usize)
+                    }
                     _ => panic!("[BUG] `FuncSetProperty` is expected"),
                 };
                 let env_iter = iter::from_fn(|| {
                     pc += 1;
                     match unsafe { exe.fetch(pc) } {
-                        FuncAddCapture(id) => Some(runtime.local_table.get_ref(*id)),
+                        FuncAddCapture(reg) => Some(locals.get_upvalue(*reg)),
                         EndFuncSection => None,
                         _ => panic!("[BUG] `FuncAddCapture` is expected"),
                     }
                 });
-                let func = Function::new(exe.clone(), param_len, start_pc, env_iter);
-                runtime.stack.push(Object::Function(func));
+                let func = Function::new(exe.clone(), param_len, start_pc, end_pc, env_iter);
+                locals.set(dst, Object::Function(func));
                 pc += 1;
             }
-            FuncSetProperty(_, _) => panic!("[BUG] `FuncSetProperty` is not allowed here"),
+            FuncSetProperty(..) => panic!("[BUG] `FuncSetProperty` is not allowed here"),
             FuncAddCapture(_) => panic!("[BUG] `FuncAddCapture` is not allowed here"),
             EndFuncSection => panic!("[BUG] `EndFuncSection` is not allowed here"),
 
             Nop => {
                 pc += 1;
             }
-            Leave => match runtime.leave_hook.pop() {
-                Some(hook) => {
-                    if let Some(post_exec) = hook.post_exec {
-                        let value = runtime.stack.pop();
-                        runtime.stack.push(post_exec(value)?);
+            Leave(reg) => {
+                let value = locals.read(reg);
+                if runtime.leave_hook.len() == floor {
+                    break Ok(value);
+                }
+                match runtime.leave_hook.pop() {
+                    Some(hook) => {
+                        runtime.local_table.pop_frame();
+                        let value = match hook.post_exec {
+                            Some(post_exec) => post_exec(value)?,
+                            None => value,
+                        };
+                        runtime.local_table.set(hook.dst, value);
+                        pc = hook.ra;
+                        if let Some(caller_exe) = hook.exe {
+                            exe = caller_exe;
+                            exe_len = exe.len();
+                        }
                     }
-                    pc = hook.ra;
+                    None => break Ok(value),
                 }
-                None => break Ok(()),
-            },
+            }
         }
     }
 }
@@ -1,21 +1,43 @@
-mod stack;
-use stack::*;
+mod leave_hook;
+use leave_hook::*;
 
 mod local_table;
 use local_table::*;
 
+use foundation::il::Executable;
+
 pub(crate) struct Runtime {
-    pub(crate) stack: Stack,
     pub(crate) local_table: LocalTable,
     pub(crate) leave_hook: LeaveHook,
+    /// The executable to switch to before the next instruction, set by a call to a function of
+    /// another executable.
+    pub(crate) next_exe: Option<Executable>,
+    /// The maximum number of the nested calls. (See `Options::max_call_depth`)
+    pub(crate) max_call_depth: usize,
+    /// The exit code requested by `os.exit`. While this is set, the execution is unwound as if an
+    /// exception occurred, but without logging anything to `EXCEPTION_LOG`.
+    pub(crate) exit_request: Option<i32>,
 }
 
 impl Runtime {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(max_call_depth: usize) -> Self {
         Self {
-            stack: Stack::new(),
             local_table: LocalTable::new(),
             leave_hook: LeaveHook::new(),
+            next_exe: None,
+            max_call_depth,
+            exit_request: None,
         }
     }
+
+    pub(crate) fn is_exit_requested(&self) -> bool {
+        self.exit_request.is_some()
+    }
+
+    /// Drops the locals and the calls in progress, keeping the options and the exit request.
+    pub(crate) fn clear(&mut self) {
+        self.local_table = LocalTable::new();
+        self.leave_hook = LeaveHook::new();
+        self.next_exe = None;
+    }
 }
@@ -0,0 +1,44 @@
+use foundation::{
+    il::{Executable, Reg},
+    object::Object,
+};
+
+pub(crate) struct LeaveHook(Vec<Hook>);
+pub(crate) struct Hook {
+    pub(crate) ra: usize,
+    /// The register of the caller to store the return value to.
+    pub(crate) dst: Reg,
+    pub(crate) post_exec: Option<Box<dyn FnOnce(Object) -> Result<Object, ()>>>,
+    /// The executable of the caller, if it is not the one of the callee.
+    pub(crate) exe: Option<Executable>,
+}
+
+impl LeaveHook {
+    pub(crate) const fn new() -> Self {
+        LeaveHook(Vec::new())
+    }
+
+    pub(crate) fn set(
+        &mut self,
+        value: usize,
+        dst: Reg,
+        post_exec: Option<Box<dyn FnOnce(Object) -> Result<Object, ()>>>,
+        exe: Option<Executable>,
+    ) {
+        self.0.push(Hook {
+            ra: value,
+            dst,
+            post_exec,
+            exe,
+        });
+    }
+
+    /// Returns the number of the calls in progress.
+    pub(crate) fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub(crate) fn pop(&mut self) -> Option<Hook> {
+        self.0.pop()
+    }
+}
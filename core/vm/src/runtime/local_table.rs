@@ -1,171 +1,196 @@
-use foundation::{il::LocalId, object::Object};
-use std::{cell::RefCell, rc::Rc};
-
-#[derive(Default, Clone, Debug, PartialEq)]
+use foundation::{
+    il::Reg,
+    object::{Object, Upvalue, UpvalueState},
+};
+use std::mem;
+
+/// The locals of the running frames.
+///
+/// The locals live in one contiguous stack. The register `r` of the current frame, i.e. the
+/// innermost call, is the slot `base + r`, and the current frame extends to the top of the stack.
+/// So a call and a return only move `base`, and the slots are reused without allocation.
+#[derive(Default, Debug)]
 pub struct LocalTable {
-    scopes: Vec<internal::Scope>,
+    stack: Vec<Entity>,
+    /// The first slot of the current frame.
+    base: usize,
+    /// The bases of the caller frames.
+    bases: Vec<usize>,
+    /// The upvalues referring to the slots in `stack`, sorted by the slot.
+    open_upvalues: Vec<Upvalue>,
+}
+
+#[derive(Debug)]
+enum Entity {
+    Value(Object),
+    Upvalue(Upvalue),
 }
 
 impl LocalTable {
     pub fn new() -> Self {
         Self {
-            scopes: vec![internal::Scope::new()],
+            stack: Vec::with_capacity(256),
+            base: 0,
+            bases: Vec::new(),
+            open_upvalues: Vec::new(),
         }
     }
 
-    pub fn push_scope(&mut self) {
-        self.scopes.push(internal::Scope::new());
+    /// Starts the frame of a call on the top of the stack.
+    pub fn push_frame(&mut self) {
+        self.bases.push(self.base);
+        self.base = self.stack.len();
     }
 
-    pub fn pop_scope(&mut self) {
-        self.scopes
+    /// Drops the current frame, and returns to the frame of the caller.
+    pub fn pop_frame(&mut self) {
+        debug_assert!(
+            !self.has_open_upvalue_from(Reg::new(0)),
+            "[BUG] The upvalues of the frame should be closed by `CloseUpvalues`."
+        );
+        self.stack.truncate(self.base);
+        self.base = self
+            .bases
             .pop()
-            .expect("[BUG] This should be called in at least one scope.");
+            .expect("[BUG] This should be called in a frame of a call.");
     }
 
     pub fn add(&mut self, object: Object) {
-        self.scopes
-            .last_mut()
-            .expect("[BUG] This should be called in at least one scope.")
-            .push(internal::Entity::Value(object));
-    }
-
-    pub fn add_ref(&mut self, ref_object: Rc<RefCell<Object>>) {
-        self.scopes
-            .last_mut()
-            .expect("[BUG] This should be called in at least one scope.")
-            .push(internal::Entity::Shared(ref_object));
+        self.stack.push(Entity::Value(object));
     }
 
-    pub fn drop(&mut self, count: usize) {
-        self.scopes
-            .last_mut()
-            .expect("[BUG] This should be called in at least one scope.")
-            .drop(count);
-    }
-
-    pub fn set(&mut self, id: LocalId, object: Object) {
-        self.scopes
-            .last_mut()
-            .expect("[BUG] This should be called in at least one scope.")
-            .edit(id, object)
-    }
-
-    pub fn get(&self, id: LocalId) -> Object {
-        self.scopes
-            .last()
-            .expect("[BUG] This should be called in at least one scope.")
-            .get(id)
+    /// Adds the local captured by the function being called.
+    pub fn add_upvalue(&mut self, upvalue: Upvalue) {
+        self.stack.push(Entity::Upvalue(upvalue));
     }
 
-    pub fn get_ref(&mut self, id: LocalId) -> Rc<RefCell<Object>> {
-        self.scopes
-            .last_mut()
-            .expect("[BUG] This should be called in at least one scope.")
-            .get_ref(id)
+    /// Drops the registers from `reg` to the end of the current frame.
+    pub fn truncate(&mut self, reg: Reg) {
+        debug_assert!(
+            !self.has_open_upvalue_from(reg),
+            "[BUG] The upvalues of the locals should be closed by `CloseUpvalues`."
+        );
+        self.stack.truncate(self.base + reg.as_usize());
     }
 
-    pub fn dump(&self, indent: usize) {
-        println!("{}[VariableTable]", " ".repeat(indent));
-        for scope in self.scopes.iter() {
-            scope.dump(indent + 2);
+    /// Writes `object` to `reg`, growing the current frame if needed.
+    pub fn set(&mut self, reg: Reg, object: Object) {
+        let slot = self.base + reg.as_usize();
+        if slot >= self.stack.len() {
+            self.stack
+                .resize_with(slot + 1, || Entity::Value(Object::Nil));
+        }
+        let (below, rest) = self.stack.split_at_mut(slot);
+        match &mut rest[0] {
+            Entity::Value(value) => *value = object,
+            Entity::Upvalue(upvalue) => match upvalue.open_slot() {
+                Some(slot) => *value_mut(below, slot) = object,
+                None => upvalue.set_closed(object),
+            },
         }
-    }
-}
-
-mod internal {
-    use super::*;
-
-    #[derive(Clone, Debug, PartialEq)]
-    pub enum Entity {
-        Value(Object),
-        Shared(Rc<RefCell<Object>>),
-    }
-
-    #[derive(Default, Clone, Debug, PartialEq)]
-    pub struct Scope {
-        entities: Vec<Entity>,
     }
 
-    impl Scope {
-        #[inline]
-        pub const fn new() -> Self {
-            Self {
-                entities: Vec::new(),
-            }
+    /// Reads `reg` as an operand: the value is moved out if `reg` is marked as take.
+    /// (See `Reg::take()`)
+    pub fn read(&mut self, reg: Reg) -> Object {
+        let slot = self.slot(reg.plain());
+        let (below, rest) = self.stack.split_at_mut(slot);
+        match &mut rest[0] {
+            Entity::Value(value) if reg.is_take() => mem::replace(value, Object::Nil),
+            Entity::Value(value) => value.clone(),
+            // The captured local is shared with other functions, so it is never moved out.
+            Entity::Upvalue(upvalue) => match &*upvalue.state() {
+                UpvalueState::Open { slot } => value_mut(below, *slot).clone(),
+                UpvalueState::Closed(value) => value.clone(),
+            },
         }
+    }
 
-        #[inline]
-        pub fn push(&mut self, entity: Entity) {
-            self.entities.push(entity);
+    /// Returns the upvalue of the local `reg` to be captured by a function.
+    ///
+    /// The same upvalue is returned while the local is alive, so that the functions share it.
+    pub fn get_upvalue(&mut self, reg: Reg) -> Upvalue {
+        let slot = self.slot(reg);
+        if let Entity::Upvalue(upvalue) = &self.stack[slot] {
+            return upvalue.clone();
         }
-
-        pub fn drop(&mut self, count: usize) {
-            if count > self.entities.len() {
-                panic!(
-                    "[BUG] Cannot drop {} variables because there are only {} variables in scope.",
-                    count,
-                    self.entities.len()
-                );
+        let mut index = self.open_upvalues.len();
+        while index > 0 {
+            let open = self.open_upvalues[index - 1].open_slot().unwrap();
+            if open == slot {
+                return self.open_upvalues[index - 1].clone();
             }
-            self.entities.truncate(self.entities.len() - count);
+            if open < slot {
+                break;
+            }
+            index -= 1;
         }
+        let upvalue = Upvalue::new_open(slot);
+        self.open_upvalues.insert(index, upvalue.clone());
+        upvalue
+    }
 
-        pub fn get(&self, id: LocalId) -> Object {
-            if let Some(entity) = self.entities.get(id.as_usize()) {
-                match entity {
-                    Entity::Value(object) => object.clone(),
-                    Entity::Shared(object) => object.borrow().clone(),
-                }
-            } else {
-                panic_id_out_of_range(self.entities.len(), id);
-            }
+    /// Closes the upvalues of the locals from `reg` in the current frame, moving their values into
+    /// the upvalues.
+    pub fn close_upvalues(&mut self, reg: Reg) {
+        while self.has_open_upvalue_from(reg) {
+            let upvalue = self.open_upvalues.pop().unwrap();
+            let slot = upvalue.open_slot().unwrap();
+            upvalue.set_closed(value_mut(&mut self.stack, slot).clone());
         }
+    }
 
-        pub fn get_ref(&mut self, id: LocalId) -> Rc<RefCell<Object>> {
-            if let Some(entity) = self.entities.get(id.as_usize()) {
-                match entity {
-                    Entity::Value(object) => {
-                        let res = Rc::new(RefCell::new(object.clone()));
-                        self.entities[id.as_usize()] = Entity::Shared(Rc::clone(&res));
-                        res
-                    }
-                    Entity::Shared(object) => Rc::clone(object),
-                }
-            } else {
-                panic_id_out_of_range(self.entities.len(), id);
+    pub fn dump(&self, indent: usize) {
+        println!("{}[VariableTable]", " ".repeat(indent));
+        let mut bases = self.bases.iter().copied().chain([self.base]).peekable();
+        for (slot, entity) in self.stack.iter().enumerate() {
+            while bases.next_if(|base| *base <= slot).is_some() {
+                println!("{}[Frame]", " ".repeat(indent + 2));
             }
+            println!("{}{slot}: {entity:?}", " ".repeat(indent + 4));
         }
+    }
 
-        pub fn edit(&mut self, id: LocalId, object: Object) {
-            if let Some(entity) = self.entities.get(id.as_usize()) {
-                match entity {
-                    Entity::Value(_) => {
-                        self.entities[id.as_usize()] = Entity::Value(object);
-                    }
-                    Entity::Shared(entity) => {
-                        *(entity.borrow_mut()) = object;
-                    }
-                }
-            } else {
-                panic_id_out_of_range(self.entities.len(), id);
-            }
+    fn slot(&self, reg: Reg) -> usize {
+        let slot = self.base + reg.as_usize();
+        if slot >= self.stack.len() {
+            panic_reg_out_of_range(self.stack.len() - self.base, reg.as_usize());
         }
+        slot
+    }
 
-        pub fn dump(&self, indent: usize) {
-            println!("{}[Scope]", " ".repeat(indent));
-            for (idx, entity) in self.entities.iter().enumerate() {
-                println!("{}{idx}: {entity:?}", " ".repeat(indent + 2));
-            }
+    fn has_open_upvalue_from(&self, reg: Reg) -> bool {
+        let slot = self.base + reg.as_usize();
+        self.open_upvalues
+            .last()
+            .is_some_and(|upvalue| upvalue.open_slot().is_some_and(|open| open >= slot))
+    }
+}
+
+impl Drop for LocalTable {
+    /// Closes the remaining upvalues, e.g. of the frames left by an exception, so that the
+    /// functions capturing them keep working.
+    fn drop(&mut self) {
+        for upvalue in mem::take(&mut self.open_upvalues) {
+            let slot = upvalue.open_slot().unwrap();
+            let value = mem::replace(value_mut(&mut self.stack, slot), Object::Nil);
+            upvalue.set_closed(value);
         }
     }
+}
 
-    #[cold]
-    fn panic_id_out_of_range(len: usize, got: LocalId) -> ! {
-        panic!(
-            "[BUG] LocalId out of range. Expected 0..{}, but got {}.",
-            len,
-            got.as_usize()
-        );
+/// Returns the value of the slot referred by an open upvalue.
+fn value_mut(stack: &mut [Entity], slot: usize) -> &mut Object {
+    match &mut stack[slot] {
+        Entity::Value(value) => value,
+        Entity::Upvalue(_) => panic!("[BUG] An open upvalue refers to an upvalue."),
     }
 }
+
+#[cold]
+fn panic_reg_out_of_range(len: usize, got: usize) -> ! {
+    panic!(
+        "[BUG] Register out of range. Expected 0..{}, but got {}.",
+        len, got
+    );
+}
@@ -0,0 +1,136 @@
+use std::cell::RefCell;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::sync::{Arc, Mutex};
+
+thread_local! {
+    /// The standard streams used by the builtins (`print`, `io.*`).
+    ///
+    /// A script runs on the thread which executes it, so each thread has its own streams, the same
+    /// as the state of the collector. Each stream falls back to the process's one until the host
+    /// replaces it, e.g. `vm::STDIO.with_borrow_mut(|stdio| stdio.set_stdout(buffer.clone()))`
+    /// with an [`OutputBuffer`] to capture the output of a script.
+    pub static STDIO: RefCell<Stdio> = const { RefCell::new(Stdio::new()) };
+}
+
+#[derive(Default)]
+pub struct Stdio {
+    stdin: Option<Box<dyn BufRead>>,
+    stdout: Option<Box<dyn OutputSink>>,
+    stderr: Option<Box<dyn OutputSink>>,
+}
+
+impl Stdio {
+    pub const fn new() -> Self {
+        Self {
+            stdin: None,
+            stdout: None,
+            stderr: None,
+        }
+    }
+
+    pub fn set_stdin(&mut self, stdin: impl BufRead + 'static) {
+        self.stdin = Some(Box::new(stdin));
+    }
+
+    pub fn set_stdout(&mut self, stdout: impl OutputSink + 'static) {
+        self.stdout = Some(Box::new(stdout));
+    }
+
+    pub fn set_stderr(&mut self, stderr: impl OutputSink + 'static) {
+        self.stderr = Some(Box::new(stderr));
+    }
+
+    /// Restores all the streams to the process's ones.
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    pub(crate) fn write(&mut self, str: impl AsRef<str>) -> io::Result<()> {
+        self.stdout().write_str(str.as_ref())
+    }
+
+    pub(crate) fn write_err(&mut self, str: impl AsRef<str>) -> io::Result<()> {
+        self.stderr().write_str(str.as_ref())
+    }
+
+    pub(crate) fn flush(&mut self) -> io::Result<()> {
+        self.stdout().flush()?;
+        self.stderr().flush()
+    }
+
+    /// Reads a line including the trailing newline. Returns an empty string at EOF.
+    pub(crate) fn read_line(&mut self) -> io::Result<String> {
+        let mut buf = String::new();
+        self.stdin().read_line(&mut buf)?;
+        Ok(buf)
+    }
+
+    pub(crate) fn read_all(&mut self) -> io::Result<String> {
+        let mut buf = String::new();
+        self.stdin().read_to_string(&mut buf)?;
+        Ok(buf)
+    }
+
+    fn stdin(&mut self) -> &mut Box<dyn BufRead> {
+        self.stdin
+            .get_or_insert_with(|| Box::new(BufReader::new(io::stdin())))
+    }
+
+    fn stdout(&mut self) -> &mut Box<dyn OutputSink> {
+        self.stdout.get_or_insert_with(|| Box::new(io::stdout()))
+    }
+
+    fn stderr(&mut self) -> &mut Box<dyn OutputSink> {
+        self.stderr.get_or_insert_with(|| Box::new(io::stderr()))
+    }
+}
+
+/// A destination of the text written by the builtins.
+///
+/// Any [`Write`] (e.g. [`io::Stdout`], [`std::fs::File`]) is an output sink. Use [`OutputBuffer`]
+/// to capture the output in memory.
+pub trait OutputSink {
+    fn write_str(&mut self, str: &str) -> io::Result<()>;
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<W: Write> OutputSink for W {
+    fn write_str(&mut self, str: &str) -> io::Result<()> {
+        self.write_all(str.as_bytes())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Write::flush(self)
+    }
+}
+
+/// An in-memory output sink. Clones share the same buffer, so the host can keep one to read
+/// what the script wrote after passing another to [`Stdio::set_stdout`].
+#[derive(Clone, Debug, Default)]
+pub struct OutputBuffer(Arc<Mutex<String>>);
+
+impl OutputBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a copy of the text written so far.
+    pub fn contents(&self) -> String {
+        self.0.lock().unwrap().clone()
+    }
+
+    /// Returns the text written so far and clears the buffer.
+    pub fn take(&self) -> String {
+        core::mem::take(&mut *self.0.lock().unwrap())
+    }
+}
+
+impl OutputSink for OutputBuffer {
+    fn write_str(&mut self, str: &str) -> io::Result<()> {
+        self.0.lock().unwrap().push_str(str);
+        Ok(())
+    }
+}
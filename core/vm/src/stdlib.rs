@@ -0,0 +1,131 @@
+use crate::STDIO;
+use anyhow::{anyhow, Context as _, Result};
+use foundation::object::*;
+
+mod convert;
+mod fs;
+mod gc;
+mod io;
+mod json;
+mod math;
+mod os;
+mod random;
+mod time;
+mod weak;
+
+pub use os::set_args;
+pub(crate) use os::Exit;
+
+type Args = Box<dyn ExactSizeIterator<Item = Object>>;
+
+/// Creates the value of the default global `name`.
+///
+/// The names must be kept in sync with `DEFAULT_GLOBALS` in `compiler/src/compile.rs`.
+pub(crate) fn load(name: &str) -> Object {
+    match name {
+        "print" => RustFunction::new(1, print).into(),
+        "println" => RustFunction::new(1, println).into(),
+        "type" => RustFunction::new(1, convert::type_).into(),
+        "int" => RustFunction::with_optional(1, 1, convert::int).into(),
+        "float" => RustFunction::new(1, convert::float).into(),
+        "str" => RustFunction::new(1, convert::str).into(),
+        "bool" => RustFunction::new(1, convert::bool).into(),
+        "tostring" => RustFunction::with_optional(1, 1, convert::tostring).into(),
+        "callable" => RustFunction::new(1, convert::callable).into(),
+        "weak" => RustFunction::new(1, weak::weak).into(),
+        "weak_table" => RustFunction::new(0, weak::weak_table).into(),
+        "io" => io::module().into(),
+        "fs" => fs::module().into(),
+        "json" => json::module().into(),
+        "math" => math::module().into(),
+        "random" => random::module().into(),
+        "os" => os::module().into(),
+        "time" => time::module().into(),
+        "gc" => gc::module().into(),
+        _ => panic!("[BUG] Unknown default global '{}'", name),
+    }
+}
+
+fn print(mut args: Args) -> Result<Object> {
+    let value = args.next().unwrap();
+    STDIO.with_borrow_mut(|stdio| stdio.write(value.to_string()))?;
+    Ok(Object::Nil)
+}
+
+fn println(mut args: Args) -> Result<Object> {
+    let value = args.next().unwrap();
+    STDIO.with_borrow_mut(|stdio| stdio.write(format!("{}\n", value)))?;
+    Ok(Object::Nil)
+}
+
+fn expect_string(value: Object, func: &str, index: usize) -> Result<UString> {
+    match value {
+        Object::String(string) => Ok(string),
+        other => Err(anyhow!(
+            "{}: expected argument {} of type 'string', got '{}'",
+            func,
+            index + 1,
+            other.type_name()
+        )),
+    }
+}
+
+fn expect_int(value: Object, func: &str, index: usize) -> Result<i64> {
+    match value {
+        Object::Int(int) => Ok(int),
+        other => Err(anyhow!(
+            "{}: expected argument {} of type 'int', got '{}'",
+            func,
+            index + 1,
+            other.type_name()
+        )),
+    }
+}
+
+fn expect_number(value: Object, func: &str, index: usize) -> Result<f64> {
+    match value {
+        Object::Int(int) => Ok(int as f64),
+        Object::Float(float) => Ok(float),
+        other => Err(anyhow!(
+            "{}: expected argument {} of type 'int | float', got '{}'",
+            func,
+            index + 1,
+            other.type_name()
+        )),
+    }
+}
+
+fn expect_array(value: Object, func: &str, index: usize) -> Result<Array> {
+    match value {
+        Object::Array(array) => Ok(array),
+        other => Err(anyhow!(
+            "{}: expected argument {} of type 'array', got '{}'",
+            func,
+            index + 1,
+            other.type_name()
+        )),
+    }
+}
+
+fn expect_table(value: Object, func: &str, index: usize) -> Result<Table> {
+    match value {
+        Object::Table(table) => Ok(table),
+        other => Err(anyhow!(
+            "{}: expected argument {} of type 'table', got '{}'",
+            func,
+            index + 1,
+            other.type_name()
+        )),
+    }
+}
+
+/// Removes the trailing `\n` or `\r\n` of a line read by [`std::io::BufRead::read_line`].
+fn trim_newline(mut line: String) -> String {
+    if line.ends_with('\n') {
+        line.pop();
+        if line.ends_with('\r') {
+            line.pop();
+        }
+    }
+    line
+}
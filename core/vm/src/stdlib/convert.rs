@@ -0,0 +1,177 @@
+use super::*;
+
+// type(value) -> string
+pub(super) fn type_(mut args: Args) -> Result<Object> {
+    let value = args.next().unwrap();
+    Ok(Object::String(value.type_name().into()))
+}
+
+// int(value: int | float | bool | string, base?: int) -> int
+//
+// Floats are truncated toward zero. `base` is only allowed for strings.
+pub(super) fn int(mut args: Args) -> Result<Object> {
+    let value = args.next().unwrap();
+    let base = args
+        .next()
+        .map(|base| expect_base(base, "int", 1))
+        .transpose()?;
+    if base.is_some() && !matches!(value, Object::String(_)) {
+        return Err(anyhow!(
+            "int: base is only allowed for a string, got '{}'",
+            value.type_name()
+        ));
+    }
+    let int = match value {
+        Object::Int(int) => int,
+        Object::Float(float) => {
+            // `i64::MAX as f64` is rounded up to 2^63, which is out of range.
+            if !float.is_finite() || float < i64::MIN as f64 || float >= i64::MAX as f64 {
+                return Err(anyhow!("int: cannot convert {:?} to int", float));
+            }
+            float.trunc() as i64
+        }
+        Object::Bool(bool) => bool as i64,
+        Object::String(string) => {
+            let text = string.as_str().trim();
+            i64::from_str_radix(text, base.unwrap_or(10))
+                .map_err(|_| anyhow!("int: cannot convert {:?} to int", string.as_str()))?
+        }
+        other => {
+            return Err(anyhow!(
+                "int: cannot convert a value of type '{}' to int",
+                other.type_name()
+            ))
+        }
+    };
+    Ok(Object::Int(int))
+}
+
+// float(value: int | float | bool | string) -> float
+pub(super) fn float(mut args: Args) -> Result<Object> {
+    let float = match args.next().unwrap() {
+        Object::Int(int) => int as f64,
+        Object::Float(float) => float,
+        Object::Bool(bool) => bool as i64 as f64,
+        Object::String(string) => string
+            .as_str()
+            .trim()
+            .parse()
+            .map_err(|_| anyhow!("float: cannot convert {:?} to float", string.as_str()))?,
+        other => {
+            return Err(anyhow!(
+                "float: cannot convert a value of type '{}' to float",
+                other.type_name()
+            ))
+        }
+    };
+    Ok(Object::Float(float))
+}
+
+// str(value) -> string
+pub(super) fn str(mut args: Args) -> Result<Object> {
+    let value = args.next().unwrap();
+    Ok(Object::String(value.to_string().as_str().into()))
+}
+
+// bool(value) -> bool
+pub(super) fn bool(mut args: Args) -> Result<Object> {
+    let value = args.next().unwrap();
+    Ok(Object::Bool(value.is_truthy()))
+}
+
+// tostring(value, options?: {base: int, precision: int}) -> string
+//
+// `base` (2 to 36) formats an int, `precision` (the number of digits after the decimal point)
+// formats an int or a float. Without options, it is the same as `str(value)`.
+pub(super) fn tostring(mut args: Args) -> Result<Object> {
+    let value = args.next().unwrap();
+    let (base, precision) = match args.next() {
+        Some(options) => {
+            let options = expect_table(options, "tostring", 1)?;
+            let base = options.get("base").cloned();
+            let precision = options.get("precision").cloned();
+            (
+                base.map(|base| expect_base(base, "tostring", 1))
+                    .transpose()?,
+                precision
+                    .map(|precision| expect_precision(precision, "tostring", 1))
+                    .transpose()?,
+            )
+        }
+        None => (None, None),
+    };
+    let string = match (value, base, precision) {
+        (_, Some(_), Some(_)) => {
+            return Err(anyhow!(
+                "tostring: 'base' and 'precision' cannot be combined"
+            ))
+        }
+        (Object::Int(int), Some(base), None) => int_to_string_radix(int, base),
+        (Object::Int(int), None, Some(precision)) => format!("{:.*}", precision, int as f64),
+        (Object::Float(float), None, Some(precision)) => format!("{:.*}", precision, float),
+        (value, None, None) => value.to_string(),
+        (value, Some(_), None) => {
+            return Err(anyhow!(
+                "tostring: 'base' is only allowed for an int, got '{}'",
+                value.type_name()
+            ))
+        }
+        (value, None, Some(_)) => {
+            return Err(anyhow!(
+                "tostring: 'precision' is only allowed for a number, got '{}'",
+                value.type_name()
+            ))
+        }
+    };
+    Ok(Object::String(string.as_str().into()))
+}
+
+// callable(value) -> bool
+pub(super) fn callable(mut args: Args) -> Result<Object> {
+    let callable = match args.next().unwrap() {
+        Object::Function(_) | Object::RustFunction(_) => true,
+        Object::Table(table) => table.get_method("__call").is_some(),
+        _ => false,
+    };
+    Ok(Object::Bool(callable))
+}
+
+fn expect_base(value: Object, func: &str, index: usize) -> Result<u32> {
+    let base = expect_int(value, func, index)?;
+    if !(2..=36).contains(&base) {
+        return Err(anyhow!("{}: base must be in 2..=36, got {}", func, base));
+    }
+    Ok(base as u32)
+}
+
+fn expect_precision(value: Object, func: &str, index: usize) -> Result<usize> {
+    // The same bound as the precision in a format spec. (See `FormatSpec::parse()`)
+    const MAX_PRECISION: i64 = u16::MAX as i64;
+
+    let precision = expect_int(value, func, index)?;
+    if !(0..=MAX_PRECISION).contains(&precision) {
+        return Err(anyhow!(
+            "{}: precision must be in 0..={}, got {}",
+            func,
+            MAX_PRECISION,
+            precision
+        ));
+    }
+    Ok(precision as usize)
+}
+
+fn int_to_string_radix(int: i64, base: u32) -> String {
+    let mut digits = Vec::new();
+    let mut rest = int.unsigned_abs();
+    loop {
+        digits.push(char::from_digit((rest % base as u64) as u32, base).unwrap());
+        rest /= base as u64;
+        if rest == 0 {
+            break;
+        }
+    }
+    if int < 0 {
+        digits.push('-');
+    }
+    digits.iter().rev().collect()
+}
@@ -0,0 +1,281 @@
+use super::*;
+use std::{
+    cell::RefCell,
+    fs::{self, File, OpenOptions},
+    io::{BufRead, BufReader, Read, Write},
+};
+
+pub(super) fn module() -> Table {
+    Table::from([
+        ("read_text".into(), RustFunction::new(1, read_text).into()),
+        ("write_text".into(), RustFunction::new(2, write_text).into()),
+        ("append".into(), RustFunction::new(2, append).into()),
+        ("exists".into(), RustFunction::new(1, exists).into()),
+        ("list_dir".into(), RustFunction::new(1, list_dir).into()),
+        ("remove".into(), RustFunction::new(1, remove).into()),
+        ("open".into(), RustFunction::new(2, open).into()),
+    ])
+}
+
+// fs.read_text(path: string) -> string
+fn read_text(mut args: Args) -> Result<Object> {
+    let path = expect_string(args.next().unwrap(), "fs.read_text", 0)?;
+    let text = fs::read_to_string(path.as_str())
+        .with_context(|| format!("fs.read_text: cannot read '{}'", path))?;
+    Ok(Object::String(text.as_str().into()))
+}
+
+// fs.write_text(path: string, text: string) -> nil
+fn write_text(mut args: Args) -> Result<Object> {
+    let path = expect_string(args.next().unwrap(), "fs.write_text", 0)?;
+    let text = expect_string(args.next().unwrap(), "fs.write_text", 1)?;
+    fs::write(path.as_str(), text.as_str())
+        .with_context(|| format!("fs.write_text: cannot write '{}'", path))?;
+    Ok(Object::Nil)
+}
+
+// fs.append(path: string, text: string) -> nil
+fn append(mut args: Args) -> Result<Object> {
+    let path = expect_string(args.next().unwrap(), "fs.append", 0)?;
+    let text = expect_string(args.next().unwrap(), "fs.append", 1)?;
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path.as_str())
+        .and_then(|mut file| file.write_all(text.as_str().as_bytes()))
+        .with_context(|| format!("fs.append: cannot write '{}'", path))?;
+    Ok(Object::Nil)
+}
+
+// fs.exists(path: string) -> bool
+fn exists(mut args: Args) -> Result<Object> {
+    let path = expect_string(args.next().unwrap(), "fs.exists", 0)?;
+    let exists = fs::exists(path.as_str())
+        .with_context(|| format!("fs.exists: cannot access '{}'", path))?;
+    Ok(Object::Bool(exists))
+}
+
+// fs.list_dir(path: string) -> array<string>
+fn list_dir(mut args: Args) -> Result<Object> {
+    let path = expect_string(args.next().unwrap(), "fs.list_dir", 0)?;
+    let mut names = fs::read_dir(path.as_str())
+        .and_then(|entries| {
+            entries
+                .map(|entry| Ok(entry?.file_name().to_string_lossy().into_owned()))
+                .collect::<std::io::Result<Vec<_>>>()
+        })
+        .with_context(|| format!("fs.list_dir: cannot read '{}'", path))?;
+    // `read_dir` does not guarantee any order.
+    names.sort();
+    let names = names
+        .iter()
+        .map(|name| Object::String(name.as_str().into()))
+        .collect::<Vec<_>>();
+    Ok(Object::Array(Array::from(names)))
+}
+
+// fs.remove(path: string) -> nil
+//
+// Removes a file or an empty directory.
+fn remove(mut args: Args) -> Result<Object> {
+    let path = expect_string(args.next().unwrap(), "fs.remove", 0)?;
+    let result = match fs::metadata(path.as_str()) {
+        Ok(meta) if meta.is_dir() => fs::remove_dir(path.as_str()),
+        _ => fs::remove_file(path.as_str()),
+    };
+    result.with_context(|| format!("fs.remove: cannot remove '{}'", path))?;
+    Ok(Object::Nil)
+}
+
+thread_local! {
+    /// Files opened by `fs.open`, with the handle that owns each.
+    ///
+    /// A handle finds its file by the identity of the handle table, not by a field, so that a
+    /// script cannot make a handle refer to another file, and a closed handle never refers to a
+    /// file opened later. The file is removed when its handle is closed or finalized.
+    static OPEN_FILES: RefCell<Vec<(WeakRef, OpenFile)>> = const { RefCell::new(Vec::new()) };
+}
+
+enum OpenFile {
+    Read(BufReader<File>),
+    Write(File),
+}
+
+impl OpenFile {
+    fn close(self, func: &str) -> Result<()> {
+        if let OpenFile::Write(mut file) = self {
+            file.flush()
+                .with_context(|| format!("{}: cannot flush the file", func))?;
+        }
+        Ok(())
+    }
+}
+
+// fs.open(path: string, mode: "r" | "w" | "a") -> handle
+fn open(mut args: Args) -> Result<Object> {
+    let path = expect_string(args.next().unwrap(), "fs.open", 0)?;
+    let mode = expect_string(args.next().unwrap(), "fs.open", 1)?;
+    let file = match mode.as_str() {
+        "r" => File::open(path.as_str()).map(|file| OpenFile::Read(BufReader::new(file))),
+        "w" => File::create(path.as_str()).map(OpenFile::Write),
+        "a" => OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path.as_str())
+            .map(OpenFile::Write),
+        other => {
+            return Err(anyhow!(
+                "fs.open: invalid mode '{}', expected 'r', 'w' or 'a'",
+                other
+            ))
+        }
+    }
+    .with_context(|| format!("fs.open: cannot open '{}'", path))?;
+
+    let mut handle = Table::from([
+        ("path".into(), Object::String(path)),
+        ("mode".into(), Object::String(mode)),
+    ]);
+    handle.set_method("read_line".into(), RustFunction::new(1, handle::read_line));
+    handle.set_method("read_all".into(), RustFunction::new(1, handle::read_all));
+    handle.set_method("lines".into(), RustFunction::new(1, handle::lines));
+    handle.set_method("write".into(), RustFunction::new(2, handle::write));
+    handle.set_method("close".into(), RustFunction::new(1, handle::close));
+    handle.set_method("__gc".into(), RustFunction::new(1, handle::finalize));
+    OPEN_FILES.with_borrow_mut(|files| {
+        // A handle whose `__gc` is replaced by the script leaves its file when it is freed.
+        files.retain(|(owner, _)| owner.is_alive());
+        files.push((WeakRef::from(&handle), file));
+    });
+    Ok(Object::Table(handle))
+}
+
+mod handle {
+    use super::*;
+
+    fn with_file<T>(
+        handle: &Table,
+        func: &str,
+        f: impl FnOnce(&mut OpenFile) -> Result<T>,
+    ) -> Result<T> {
+        OPEN_FILES.with_borrow_mut(|files| match find(files, handle) {
+            Some(index) => f(&mut files[index].1),
+            None => Err(anyhow!("{}: the file is already closed", func)),
+        })
+    }
+
+    /// Returns the index of the file owned by `handle` in `files`.
+    fn find(files: &[(WeakRef, OpenFile)], handle: &Table) -> Option<usize> {
+        let handle = WeakRef::from(handle);
+        files.iter().position(|(owner, _)| owner.ptr_eq(&handle))
+    }
+
+    fn read_line_core(handle: &Table, func: &str) -> Result<Option<String>> {
+        with_file(handle, func, |file| match file {
+            OpenFile::Read(reader) => {
+                let mut line = String::new();
+                if reader.read_line(&mut line)? == 0 {
+                    Ok(None)
+                } else {
+                    Ok(Some(trim_newline(line)))
+                }
+            }
+            OpenFile::Write(_) => Err(anyhow!("{}: the file is not opened for reading", func)),
+        })
+    }
+
+    // handle->read_line() -> string | nil
+    pub(super) fn read_line(mut args: Args) -> Result<Object> {
+        let this = expect_table(args.next().unwrap(), "read_line", 0)?;
+        Ok(match read_line_core(&this, "read_line")? {
+            Some(line) => Object::String(line.as_str().into()),
+            None => Object::Nil,
+        })
+    }
+
+    // handle->read_all() -> string
+    pub(super) fn read_all(mut args: Args) -> Result<Object> {
+        let this = expect_table(args.next().unwrap(), "read_all", 0)?;
+        let text = with_file(&this, "read_all", |file| match file {
+            OpenFile::Read(reader) => {
+                let mut text = String::new();
+                reader.read_to_string(&mut text)?;
+                Ok(text)
+            }
+            OpenFile::Write(_) => Err(anyhow!("read_all: the file is not opened for reading")),
+        })?;
+        Ok(Object::String(text.as_str().into()))
+    }
+
+    // handle->write(text: string) -> nil
+    pub(super) fn write(mut args: Args) -> Result<Object> {
+        let this = expect_table(args.next().unwrap(), "write", 0)?;
+        let text = expect_string(args.next().unwrap(), "write", 1)?;
+        with_file(&this, "write", |file| match file {
+            OpenFile::Write(file) => Ok(file.write_all(text.as_str().as_bytes())?),
+            OpenFile::Read(_) => Err(anyhow!("write: the file is not opened for writing")),
+        })?;
+        Ok(Object::Nil)
+    }
+
+    // handle->close() -> nil
+    pub(super) fn close(mut args: Args) -> Result<Object> {
+        let this = expect_table(args.next().unwrap(), "close", 0)?;
+        match take_file(&this) {
+            Some(file) => file.close("close")?,
+            None => return Err(anyhow!("close: the file is already closed")),
+        }
+        Ok(Object::Nil)
+    }
+
+    // handle->__gc() -> nil
+    //
+    // Closes the file left open when the handle is collected.
+    pub(super) fn finalize(mut args: Args) -> Result<Object> {
+        let this = expect_table(args.next().unwrap(), "__gc", 0)?;
+        if let Some(file) = take_file(&this) {
+            file.close("__gc")?;
+        }
+        Ok(Object::Nil)
+    }
+
+    fn take_file(handle: &Table) -> Option<OpenFile> {
+        OPEN_FILES
+            .with_borrow_mut(|files| find(files, handle).map(|index| files.swap_remove(index).1))
+    }
+
+    // handle->lines() -> iterator<string>
+    //
+    // The returned object reads the file line by line while it is iterated by `for ... in`.
+    pub(super) fn lines(mut args: Args) -> Result<Object> {
+        let this = expect_table(args.next().unwrap(), "lines", 0)?;
+        let mut iter = Table::from([("handle".into(), Object::Table(this))]);
+        iter.set_method("__get_iter".into(), RustFunction::new(1, lines_iter));
+        iter.set_method("__move_next".into(), RustFunction::new(1, lines_move_next));
+        iter.set_method("__current".into(), RustFunction::new(1, lines_current));
+        Ok(Object::Table(iter))
+    }
+
+    fn lines_iter(mut args: Args) -> Result<Object> {
+        Ok(args.next().unwrap())
+    }
+
+    fn lines_move_next(mut args: Args) -> Result<Object> {
+        let mut this = expect_table(args.next().unwrap(), "lines", 0)?;
+        let Some(Object::Table(handle)) = this.get("handle").cloned() else {
+            return Err(anyhow!("lines: the iterator has no file handle"));
+        };
+        let current = match read_line_core(&handle, "lines")? {
+            Some(line) => Object::String(line.as_str().into()),
+            None => Object::Nil,
+        };
+        let has_next = !matches!(current, Object::Nil);
+        this.insert("current".into(), current);
+        Ok(Object::Bool(has_next))
+    }
+
+    fn lines_current(mut args: Args) -> Result<Object> {
+        let this = expect_table(args.next().unwrap(), "lines", 0)?;
+        Ok(this.get("current").cloned().unwrap_or(Object::Nil))
+    }
+}
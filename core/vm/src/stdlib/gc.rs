@@ -0,0 +1,86 @@
+use super::*;
+use foundation::object::gc::{self, Mode};
+
+pub(super) fn module() -> Table {
+    Table::from([
+        ("collect".into(), RustFunction::new(0, collect).into()),
+        ("stats".into(), RustFunction::new(0, stats).into()),
+        ("mode".into(), RustFunction::new(0, mode).into()),
+        (
+            "set_mode".into(),
+            RustFunction::with_optional(1, 1, set_mode).into(),
+        ),
+    ])
+}
+
+// gc.collect() -> int
+//
+// Collects the objects queued by the batched mode, and returns the number of the freed objects.
+fn collect(_: Args) -> Result<Object> {
+    Ok(Object::Int(gc::collect() as i64))
+}
+
+// gc.stats() -> table
+fn stats(_: Args) -> Result<Object> {
+    let stats = gc::stats();
+    Ok(Table::from([
+        ("live_arrays".into(), Object::Int(stats.live_arrays as i64)),
+        ("live_tables".into(), Object::Int(stats.live_tables as i64)),
+        ("runs".into(), Object::Int(stats.runs as i64)),
+        ("collected".into(), Object::Int(stats.collected as i64)),
+        ("time".into(), Object::Float(stats.time.as_secs_f64())),
+        ("pending".into(), Object::Int(stats.pending as i64)),
+    ])
+    .into())
+}
+
+// gc.mode() -> string
+//
+// Returns "immediate" or "batched".
+fn mode(_: Args) -> Result<Object> {
+    let name = match gc::mode() {
+        Mode::Immediate => "immediate",
+        Mode::Batched { .. } => "batched",
+    };
+    Ok(Object::String(name.into()))
+}
+
+// gc.set_mode(mode: string, threshold?: int) -> nil
+//
+// The threshold is the number of the queued objects which triggers a collection in the batched
+// mode.
+fn set_mode(mut args: Args) -> Result<Object> {
+    let name = expect_string(args.next().unwrap(), "gc.set_mode", 0)?;
+    let threshold = match args.next() {
+        Some(threshold) => {
+            let threshold = expect_int(threshold, "gc.set_mode", 1)?;
+            if threshold <= 0 {
+                return Err(anyhow!(
+                    "gc.set_mode: threshold must be positive, got {}",
+                    threshold
+                ));
+            }
+            Some(threshold as usize)
+        }
+        None => None,
+    };
+    let mode = match (name.as_str(), threshold) {
+        ("immediate", None) => Mode::Immediate,
+        ("immediate", Some(_)) => {
+            return Err(anyhow!(
+                "gc.set_mode: the immediate mode does not take a threshold"
+            ))
+        }
+        ("batched", threshold) => Mode::Batched {
+            threshold: threshold.unwrap_or(Mode::DEFAULT_THRESHOLD),
+        },
+        (other, _) => {
+            return Err(anyhow!(
+                "gc.set_mode: unknown mode '{}', expected 'immediate' or 'batched'",
+                other
+            ))
+        }
+    };
+    gc::set_mode(mode);
+    Ok(Object::Nil)
+}
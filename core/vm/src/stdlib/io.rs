@@ -0,0 +1,43 @@
+use super::*;
+
+pub(super) fn module() -> Table {
+    Table::from([
+        ("read_line".into(), RustFunction::new(0, read_line).into()),
+        ("read_all".into(), RustFunction::new(0, read_all).into()),
+        ("eprint".into(), RustFunction::new(1, eprint).into()),
+        ("eprintln".into(), RustFunction::new(1, eprintln).into()),
+    ])
+}
+
+// io.read_line() -> string | nil
+fn read_line(_: Args) -> Result<Object> {
+    let line = STDIO
+        .with_borrow_mut(|stdio| stdio.read_line())
+        .context("io.read_line: cannot read from stdin")?;
+    if line.is_empty() {
+        return Ok(Object::Nil);
+    }
+    Ok(Object::String(trim_newline(line).as_str().into()))
+}
+
+// io.read_all() -> string
+fn read_all(_: Args) -> Result<Object> {
+    let text = STDIO
+        .with_borrow_mut(|stdio| stdio.read_all())
+        .context("io.read_all: cannot read from stdin")?;
+    Ok(Object::String(text.as_str().into()))
+}
+
+// io.eprint(value) -> nil
+fn eprint(mut args: Args) -> Result<Object> {
+    let value = args.next().unwrap();
+    STDIO.with_borrow_mut(|stdio| stdio.write_err(value.to_string()))?;
+    Ok(Object::Nil)
+}
+
+// io.eprintln(value) -> nil
+fn eprintln(mut args: Args) -> Result<Object> {
+    let value = args.next().unwrap();
+    STDIO.with_borrow_mut(|stdio| stdio.write_err(format!("{}\n", value)))?;
+    Ok(Object::Nil)
+}
@@ -0,0 +1,462 @@
+use super::*;
+use core::fmt::Write as _;
+
+pub(super) fn module() -> Table {
+    Table::from([
+        (
+            "encode".into(),
+            RustFunction::with_optional(1, 1, encode).into(),
+        ),
+        ("decode".into(), RustFunction::new(1, decode).into()),
+    ])
+}
+
+/// The maximum nesting of arrays and objects (tables), so that encoding and decoding, which recurse
+/// for each of them, do not overflow the native stack.
+const MAX_DEPTH: usize = 512;
+
+// json.encode(value, options?: {pretty: bool}) -> string
+fn encode(mut args: Args) -> Result<Object> {
+    let value = args.next().unwrap();
+    let pretty = match args.next() {
+        Some(options) => {
+            let options = expect_table(options, "json.encode", 1)?;
+            options.get("pretty").is_some_and(Object::is_truthy)
+        }
+        None => false,
+    };
+    let mut encoder = Encoder {
+        output: String::new(),
+        pretty,
+        ancestors: Vec::new(),
+    };
+    encoder
+        .value(&value)
+        .map_err(|err| anyhow!("json.encode: {}", err))?;
+    Ok(Object::String(encoder.output.as_str().into()))
+}
+
+// json.decode(text: string) -> any
+fn decode(mut args: Args) -> Result<Object> {
+    let text = expect_string(args.next().unwrap(), "json.decode", 0)?;
+    let mut decoder = Decoder {
+        chars: text.as_str().chars().peekable(),
+        line: 1,
+        column: 1,
+        depth: 0,
+    };
+    decoder.document().map_err(|err| {
+        anyhow!(
+            "json.decode: {} at line {}, column {}",
+            err.message,
+            err.line,
+            err.column
+        )
+    })
+}
+
+struct Encoder {
+    output: String,
+    pretty: bool,
+    /// The arrays and tables being encoded, from the outermost one. A value that is one of them
+    /// refers to its ancestor, that is, the value has a cycle.
+    ancestors: Vec<Object>,
+}
+
+impl Encoder {
+    fn value(&mut self, value: &Object) -> Result<(), String> {
+        match value {
+            Object::Nil => self.output.push_str("null"),
+            Object::Bool(bool) => write!(self.output, "{}", bool).unwrap(),
+            Object::Int(int) => write!(self.output, "{}", int).unwrap(),
+            Object::Float(float) => {
+                if !float.is_finite() {
+                    return Err(format!("cannot encode '{}' as a number", float));
+                }
+                // `{:?}` always prints the fractional part (e.g. `1.0`), so that the value is
+                // decoded as a float again.
+                write!(self.output, "{:?}", float).unwrap()
+            }
+            Object::String(string) => self.string(string.as_str()),
+            Object::Array(array) => {
+                self.enter(value)?;
+                // SAFETY: The array is not mutated while encoding.
+                let elements = unsafe { array.iter() }.cloned().collect::<Vec<_>>();
+                self.output.push('[');
+                for (i, element) in elements.iter().enumerate() {
+                    if i != 0 {
+                        self.output.push(',');
+                    }
+                    self.newline();
+                    self.value(element)?;
+                }
+                self.close(']', elements.is_empty());
+            }
+            Object::Table(table) => {
+                self.enter(value)?;
+                // SAFETY: The table is not mutated while encoding.
+                // The values of a table with weak values which are already freed are omitted.
+                let mut fields = unsafe { table.iter() }
+                    .filter_map(|(key, _)| Some((key.clone(), table.get_upgraded(key)?)))
+                    .collect::<Vec<_>>();
+                // The order of the fields in a table is unspecified, sort them to make the output
+                // stable.
+                fields.sort_by(|(a, _), (b, _)| a.cmp(b));
+                self.output.push('{');
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i != 0 {
+                        self.output.push(',');
+                    }
+                    self.newline();
+                    self.string(key.as_str());
+                    self.output.push_str(if self.pretty { ": " } else { ":" });
+                    self.value(value)?;
+                }
+                self.close('}', fields.is_empty());
+            }
+            Object::Function(_) | Object::RustFunction(_) => {
+                return Err("cannot encode a function".to_string());
+            }
+            Object::WeakRef(_) => {
+                return Err("cannot encode a weakref".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    fn enter(&mut self, value: &Object) -> Result<(), String> {
+        let is_cycle = self
+            .ancestors
+            .iter()
+            .any(|ancestor| match (ancestor, value) {
+                (Object::Array(a), Object::Array(b)) => a.ptr_eq(b),
+                (Object::Table(a), Object::Table(b)) => a.ptr_eq(b),
+                _ => false,
+            });
+        if is_cycle {
+            return Err(format!("cannot encode a cyclic {}", value.type_name()));
+        }
+        if self.ancestors.len() == MAX_DEPTH {
+            return Err(format!(
+                "cannot encode a value nested deeper than {}",
+                MAX_DEPTH
+            ));
+        }
+        self.ancestors.push(value.clone());
+        Ok(())
+    }
+
+    fn close(&mut self, bracket: char, is_empty: bool) {
+        self.ancestors.pop();
+        if !is_empty {
+            self.newline();
+        }
+        self.output.push(bracket);
+    }
+
+    fn newline(&mut self) {
+        if self.pretty {
+            self.output.push('\n');
+            for _ in 0..self.ancestors.len() {
+                self.output.push_str("  ");
+            }
+        }
+    }
+
+    fn string(&mut self, string: &str) {
+        self.output.push('"');
+        for char in string.chars() {
+            match char {
+                '"' => self.output.push_str("\\\""),
+                '\\' => self.output.push_str("\\\\"),
+                '\n' => self.output.push_str("\\n"),
+                '\r' => self.output.push_str("\\r"),
+                '\t' => self.output.push_str("\\t"),
+                '\u{08}' => self.output.push_str("\\b"),
+                '\u{0C}' => self.output.push_str("\\f"),
+                c if c.is_control() => write!(self.output, "\\u{:04x}", c as u32).unwrap(),
+                c => self.output.push(c),
+            }
+        }
+        self.output.push('"');
+    }
+}
+
+struct DecodeError {
+    message: String,
+    line: usize,
+    column: usize,
+}
+
+struct Decoder<'a> {
+    chars: core::iter::Peekable<core::str::Chars<'a>>,
+    line: usize,
+    column: usize,
+    /// The number of the arrays and objects being decoded.
+    depth: usize,
+}
+
+impl Decoder<'_> {
+    fn document(&mut self) -> Result<Object, DecodeError> {
+        let value = self.value()?;
+        self.skip_whitespace();
+        match self.chars.peek() {
+            None => Ok(value),
+            Some(&c) => Err(self.error(format!("unexpected '{}' after the value", c))),
+        }
+    }
+
+    fn value(&mut self) -> Result<Object, DecodeError> {
+        self.skip_whitespace();
+        match self.chars.peek() {
+            None => Err(self.error("unexpected end of input")),
+            Some('n') => self.keyword("null", Object::Nil),
+            Some('t') => self.keyword("true", Object::Bool(true)),
+            Some('f') => self.keyword("false", Object::Bool(false)),
+            Some('"') => Ok(Object::String(self.string()?.as_str().into())),
+            Some(&c @ ('[' | '{')) => {
+                if self.depth == MAX_DEPTH {
+                    return Err(self.error(format!("nested deeper than {}", MAX_DEPTH)));
+                }
+                self.depth += 1;
+                let value = if c == '[' {
+                    self.array()
+                } else {
+                    self.object()
+                };
+                self.depth -= 1;
+                value
+            }
+            Some('-' | '0'..='9') => self.number(),
+            Some(&c) => Err(self.error(format!("unexpected '{}'", c))),
+        }
+    }
+
+    fn keyword(&mut self, keyword: &str, value: Object) -> Result<Object, DecodeError> {
+        for expected in keyword.chars() {
+            if self.chars.peek() != Some(&expected) {
+                return Err(self.error(format!("invalid literal, expected '{}'", keyword)));
+            }
+            self.bump();
+        }
+        Ok(value)
+    }
+
+    fn array(&mut self) -> Result<Object, DecodeError> {
+        self.bump(); // '['
+        let mut elements = Vec::new();
+        self.skip_whitespace();
+        if self.eat(']') {
+            return Ok(Object::Array(Array::from(elements)));
+        }
+        loop {
+            elements.push(self.value()?);
+            self.skip_whitespace();
+            if self.eat(']') {
+                return Ok(Object::Array(Array::from(elements)));
+            }
+            self.expect(',')?;
+        }
+    }
+
+    fn object(&mut self) -> Result<Object, DecodeError> {
+        self.bump(); // '{'
+        let mut table = Table::new();
+        self.skip_whitespace();
+        if self.eat('}') {
+            return Ok(Object::Table(table));
+        }
+        loop {
+            self.skip_whitespace();
+            if self.chars.peek() != Some(&'"') {
+                return Err(self.error("expected a string as the key"));
+            }
+            let key = self.string()?;
+            self.skip_whitespace();
+            self.expect(':')?;
+            let value = self.value()?;
+            table.insert(key.as_str().into(), value);
+            self.skip_whitespace();
+            if self.eat('}') {
+                return Ok(Object::Table(table));
+            }
+            self.expect(',')?;
+        }
+    }
+
+    fn string(&mut self) -> Result<String, DecodeError> {
+        self.bump(); // '"'
+        let mut string = String::new();
+        loop {
+            let Some(c) = self.bump() else {
+                return Err(self.error("unterminated string"));
+            };
+            match c {
+                '"' => return Ok(string),
+                '\\' => {
+                    let Some(escape) = self.bump() else {
+                        return Err(self.error("unterminated string"));
+                    };
+                    match escape {
+                        '"' => string.push('"'),
+                        '\\' => string.push('\\'),
+                        '/' => string.push('/'),
+                        'b' => string.push('\u{08}'),
+                        'f' => string.push('\u{0C}'),
+                        'n' => string.push('\n'),
+                        'r' => string.push('\r'),
+                        't' => string.push('\t'),
+                        'u' => string.push(self.unicode_escape()?),
+                        c => return Err(self.error(format!("invalid escape '\\{}'", c))),
+                    }
+                }
+                c if c.is_control() => {
+                    return Err(self.error("control character in string"));
+                }
+                c => string.push(c),
+            }
+        }
+    }
+
+    // Reads the `XXXX` of `\uXXXX`, and the following low surrogate if it is a high surrogate.
+    fn unicode_escape(&mut self) -> Result<char, DecodeError> {
+        let high = self.hex4()?;
+        let code = if (0xD800..0xDC00).contains(&high) {
+            if !(self.eat('\\') && self.eat('u')) {
+                return Err(self.error("expected a low surrogate"));
+            }
+            let low = self.hex4()?;
+            if !(0xDC00..0xE000).contains(&low) {
+                return Err(self.error("invalid low surrogate"));
+            }
+            0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+        } else {
+            high
+        };
+        char::from_u32(code).ok_or_else(|| self.error("invalid unicode escape"))
+    }
+
+    fn hex4(&mut self) -> Result<u32, DecodeError> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let digit = self.chars.peek().and_then(|c| c.to_digit(16));
+            let Some(digit) = digit else {
+                return Err(self.error("invalid unicode escape"));
+            };
+            self.bump();
+            code = code * 16 + digit;
+        }
+        Ok(code)
+    }
+
+    fn number(&mut self) -> Result<Object, DecodeError> {
+        let (line, column) = (self.line, self.column);
+        let mut text = String::new();
+        let mut is_float = false;
+        while let Some(&c) = self.chars.peek() {
+            match c {
+                '0'..='9' | '-' | '+' => {}
+                '.' | 'e' | 'E' => is_float = true,
+                _ => break,
+            }
+            text.push(c);
+            self.bump();
+        }
+        let invalid = || DecodeError {
+            message: format!("invalid number '{}'", text),
+            line,
+            column,
+        };
+        if !is_valid_number(&text) {
+            return Err(invalid());
+        }
+        if !is_float {
+            if let Ok(int) = text.parse::<i64>() {
+                return Ok(Object::Int(int));
+            }
+            // Too large for an int, fall back to a float.
+        }
+        text.parse::<f64>()
+            .map(Object::Float)
+            .map_err(|_| invalid())
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.chars.peek(), Some(' ' | '\t' | '\n' | '\r')) {
+            self.bump();
+        }
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        if self.chars.peek() == Some(&expected) {
+            self.bump();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), DecodeError> {
+        if self.eat(expected) {
+            return Ok(());
+        }
+        match self.chars.peek() {
+            Some(&c) => Err(self.error(format!("expected '{}', found '{}'", expected, c))),
+            None => Err(self.error(format!("expected '{}', found end of input", expected))),
+        }
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn error(&self, message: impl Into<String>) -> DecodeError {
+        DecodeError {
+            message: message.into(),
+            line: self.line,
+            column: self.column,
+        }
+    }
+}
+
+// number = [ "-" ] ( "0" | [1-9] [0-9]* ) [ "." [0-9]+ ] [ ( "e" | "E" ) [ "+" | "-" ] [0-9]+ ]
+fn is_valid_number(text: &str) -> bool {
+    fn digits(bytes: &[u8]) -> usize {
+        bytes.iter().take_while(|b| b.is_ascii_digit()).count()
+    }
+    let mut bytes = text.as_bytes();
+    if let [b'-', rest @ ..] = bytes {
+        bytes = rest;
+    }
+    match bytes {
+        [b'0', rest @ ..] => bytes = rest,
+        [b'1'..=b'9', ..] => bytes = &bytes[digits(bytes)..],
+        _ => return false,
+    }
+    if let [b'.', rest @ ..] = bytes {
+        let n = digits(rest);
+        if n == 0 {
+            return false;
+        }
+        bytes = &rest[n..];
+    }
+    if let [b'e' | b'E', rest @ ..] = bytes {
+        let rest = match rest {
+            [b'+' | b'-', rest @ ..] => rest,
+            rest => rest,
+        };
+        let n = digits(rest);
+        if n == 0 {
+            return false;
+        }
+        bytes = &rest[n..];
+    }
+    bytes.is_empty()
+}
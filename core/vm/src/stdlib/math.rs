@@ -0,0 +1,86 @@
+use super::*;
+
+pub(super) fn module() -> Table {
+    Table::from([
+        ("pi".into(), Object::Float(core::f64::consts::PI)),
+        ("e".into(), Object::Float(core::f64::consts::E)),
+        ("inf".into(), Object::Float(f64::INFINITY)),
+        ("nan".into(), Object::Float(f64::NAN)),
+        ("hypot".into(), RustFunction::new(2, hypot).into()),
+        ("atan2".into(), RustFunction::new(2, atan2).into()),
+        ("gcd".into(), RustFunction::new(2, gcd).into()),
+        ("lcm".into(), RustFunction::new(2, lcm).into()),
+        ("isqrt".into(), RustFunction::new(1, isqrt).into()),
+        ("is_nan".into(), RustFunction::new(1, is_nan).into()),
+        ("is_finite".into(), RustFunction::new(1, is_finite).into()),
+    ])
+}
+
+// math.hypot(x: int | float, y: int | float) -> float
+fn hypot(mut args: Args) -> Result<Object> {
+    let x = expect_number(args.next().unwrap(), "math.hypot", 0)?;
+    let y = expect_number(args.next().unwrap(), "math.hypot", 1)?;
+    Ok(Object::Float(x.hypot(y)))
+}
+
+// math.atan2(y: int | float, x: int | float) -> float
+fn atan2(mut args: Args) -> Result<Object> {
+    let y = expect_number(args.next().unwrap(), "math.atan2", 0)?;
+    let x = expect_number(args.next().unwrap(), "math.atan2", 1)?;
+    Ok(Object::Float(y.atan2(x)))
+}
+
+fn gcd_core(a: i64, b: i64) -> u64 {
+    let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+// math.gcd(a: int, b: int) -> int
+fn gcd(mut args: Args) -> Result<Object> {
+    let a = expect_int(args.next().unwrap(), "math.gcd", 0)?;
+    let b = expect_int(args.next().unwrap(), "math.gcd", 1)?;
+    // Only `gcd(i64::MIN, i64::MIN)` and `gcd(i64::MIN, 0)` overflow.
+    let gcd = i64::try_from(gcd_core(a, b))
+        .map_err(|_| anyhow!("math.gcd: the result of gcd({}, {}) overflows", a, b))?;
+    Ok(Object::Int(gcd))
+}
+
+// math.lcm(a: int, b: int) -> int
+fn lcm(mut args: Args) -> Result<Object> {
+    let a = expect_int(args.next().unwrap(), "math.lcm", 0)?;
+    let b = expect_int(args.next().unwrap(), "math.lcm", 1)?;
+    if a == 0 || b == 0 {
+        return Ok(Object::Int(0));
+    }
+    let lcm = (a.unsigned_abs() / gcd_core(a, b))
+        .checked_mul(b.unsigned_abs())
+        .and_then(|lcm| i64::try_from(lcm).ok())
+        .ok_or_else(|| anyhow!("math.lcm: the result of lcm({}, {}) overflows", a, b))?;
+    Ok(Object::Int(lcm))
+}
+
+// math.isqrt(n: int) -> int
+//
+// The largest integer whose square does not exceed `n`.
+fn isqrt(mut args: Args) -> Result<Object> {
+    let n = expect_int(args.next().unwrap(), "math.isqrt", 0)?;
+    if n < 0 {
+        return Err(anyhow!("math.isqrt: negative argument {}", n));
+    }
+    Ok(Object::Int(n.isqrt()))
+}
+
+// math.is_nan(x: int | float) -> bool
+fn is_nan(mut args: Args) -> Result<Object> {
+    let x = expect_number(args.next().unwrap(), "math.is_nan", 0)?;
+    Ok(Object::Bool(x.is_nan()))
+}
+
+// math.is_finite(x: int | float) -> bool
+fn is_finite(mut args: Args) -> Result<Object> {
+    let x = expect_number(args.next().unwrap(), "math.is_finite", 0)?;
+    Ok(Object::Bool(x.is_finite()))
+}
@@ -0,0 +1,66 @@
+use super::*;
+use core::fmt;
+use std::{cell::RefCell, env};
+
+thread_local! {
+    /// The arguments passed to the script, which are exposed as `os.args`. They belong to the
+    /// thread which executes the script, the same as the state of the collector.
+    static ARGS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Sets the command-line arguments of the script (`os.args`) for the following executions on the
+/// current thread.
+pub fn set_args(args: impl IntoIterator<Item = String>) {
+    ARGS.set(args.into_iter().collect());
+}
+
+pub(super) fn module() -> Table {
+    let args = ARGS.with_borrow(|args| {
+        args.iter()
+            .map(|arg| Object::String(arg.as_str().into()))
+            .collect::<Vec<_>>()
+    });
+    Table::from([
+        ("args".into(), Object::Array(Array::from(args))),
+        ("getenv".into(), RustFunction::new(1, getenv).into()),
+        (
+            "exit".into(),
+            RustFunction::with_optional(0, 1, exit).into(),
+        ),
+    ])
+}
+
+// os.getenv(name: string) -> string | nil
+fn getenv(mut args: Args) -> Result<Object> {
+    let name = expect_string(args.next().unwrap(), "os.getenv", 0)?;
+    Ok(match env::var(name.as_str()) {
+        Ok(value) => Object::String(value.as_str().into()),
+        Err(_) => Object::Nil,
+    })
+}
+
+/// The error returned by `os.exit` to stop the execution. The VM turns it into
+/// [`crate::Interrupt::Exit`] instead of an exception.
+#[derive(Debug)]
+pub(crate) struct Exit(pub(crate) i32);
+
+impl fmt::Display for Exit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "exit with code {}", self.0)
+    }
+}
+
+impl std::error::Error for Exit {}
+
+// os.exit(code?: int) -> never
+fn exit(mut args: Args) -> Result<Object> {
+    let code = match args.next() {
+        Some(code) => {
+            let code = expect_int(code, "os.exit", 0)?;
+            i32::try_from(code)
+                .map_err(|_| anyhow!("os.exit: the exit code {} is out of range", code))?
+        }
+        None => 0,
+    };
+    Err(Exit(code).into())
+}
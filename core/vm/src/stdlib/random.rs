@@ -0,0 +1,98 @@
+use super::*;
+use std::{
+    cell::Cell,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+thread_local! {
+    /// The state of the generator. It is seeded from the clock at the first use unless
+    /// `random.seed` is called, so the sequence is reproducible only after `random.seed`. Each
+    /// thread has its own generator, the same as the state of the collector.
+    static STATE: Cell<Option<u64>> = const { Cell::new(None) };
+}
+
+pub(super) fn module() -> Table {
+    Table::from([
+        ("seed".into(), RustFunction::new(1, seed).into()),
+        ("int".into(), RustFunction::new(2, int).into()),
+        ("float".into(), RustFunction::new(0, float).into()),
+        ("shuffle".into(), RustFunction::new(1, shuffle).into()),
+    ])
+}
+
+/// Returns the next value of the generator (SplitMix64).
+fn next_u64() -> u64 {
+    let state = STATE.get().unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_nanos() as u64)
+    });
+    let state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    STATE.set(Some(state));
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Returns a uniformly distributed value in `0..=max`.
+fn next_below_or_eq(max: u64) -> u64 {
+    let Some(range) = max.checked_add(1) else {
+        return next_u64();
+    };
+    // Rejects the values in the last incomplete `range`-sized bucket to avoid the modulo bias.
+    let zone = u64::MAX - (u64::MAX - range + 1) % range;
+    loop {
+        let value = next_u64();
+        if value <= zone {
+            return value % range;
+        }
+    }
+}
+
+// random.seed(seed: int) -> nil
+fn seed(mut args: Args) -> Result<Object> {
+    let seed = expect_int(args.next().unwrap(), "random.seed", 0)?;
+    STATE.set(Some(seed as u64));
+    Ok(Object::Nil)
+}
+
+// random.int(min: int, max: int) -> int
+//
+// Returns an integer in `min..=max`.
+fn int(mut args: Args) -> Result<Object> {
+    let min = expect_int(args.next().unwrap(), "random.int", 0)?;
+    let max = expect_int(args.next().unwrap(), "random.int", 1)?;
+    if min > max {
+        return Err(anyhow!("random.int: empty range {}..={}", min, max));
+    }
+    let offset = next_below_or_eq(max.abs_diff(min));
+    Ok(Object::Int(min.wrapping_add(offset as i64)))
+}
+
+// random.float() -> float
+//
+// Returns a float in `0.0..1.0`.
+fn float(_: Args) -> Result<Object> {
+    // The upper 53 bits fill the mantissa of f64.
+    let value = (next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+    Ok(Object::Float(value))
+}
+
+// random.shuffle(array: array) -> nil
+//
+// Shuffles the array in place.
+fn shuffle(mut args: Args) -> Result<Object> {
+    let mut array = expect_array(args.next().unwrap(), "random.shuffle", 0)?;
+    // Fisher-Yates shuffle
+    for i in (1..array.len()).rev() {
+        let j = next_below_or_eq(i as u64) as usize;
+        if i != j {
+            let a = array.get(i).cloned().unwrap();
+            let b = array.get(j).cloned().unwrap();
+            array.set(i, b);
+            array.set(j, a);
+        }
+    }
+    Ok(Object::Nil)
+}
@@ -0,0 +1,168 @@
+use super::*;
+use core::fmt::Write as _;
+use std::{
+    sync::OnceLock,
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+pub(super) fn module() -> Table {
+    Table::from([
+        ("now".into(), RustFunction::new(0, now).into()),
+        ("monotonic".into(), RustFunction::new(0, monotonic).into()),
+        ("sleep".into(), RustFunction::new(1, sleep).into()),
+        (
+            "date".into(),
+            RustFunction::with_optional(0, 1, date).into(),
+        ),
+        (
+            "format".into(),
+            RustFunction::with_optional(1, 1, format).into(),
+        ),
+    ])
+}
+
+// time.now() -> float
+//
+// Seconds since the Unix epoch.
+fn now(_: Args) -> Result<Object> {
+    let elapsed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .context("time.now: the system clock is before the Unix epoch")?;
+    Ok(Object::Float(elapsed.as_secs_f64()))
+}
+
+// time.monotonic() -> float
+//
+// Seconds since an unspecified point, which never goes backward. Use it to measure durations.
+fn monotonic(_: Args) -> Result<Object> {
+    static START: OnceLock<Instant> = OnceLock::new();
+    let start = START.get_or_init(Instant::now);
+    Ok(Object::Float(start.elapsed().as_secs_f64()))
+}
+
+// time.sleep(ms: int) -> nil
+fn sleep(mut args: Args) -> Result<Object> {
+    let ms = expect_int(args.next().unwrap(), "time.sleep", 0)?;
+    let ms = u64::try_from(ms).map_err(|_| anyhow!("time.sleep: negative duration {}", ms))?;
+    thread::sleep(Duration::from_millis(ms));
+    Ok(Object::Nil)
+}
+
+// time.date(timestamp?: int | float) -> {year, month, day, hour, minute, second, weekday, yearday}
+//
+// Splits the timestamp (default: now) into the UTC calendar fields. `weekday` is 0 for Sunday.
+fn date(mut args: Args) -> Result<Object> {
+    let date = DateTime::from_timestamp(timestamp(args.next(), "time.date", 0)?);
+    Ok(Object::Table(Table::from([
+        ("year".into(), Object::Int(date.year)),
+        ("month".into(), Object::Int(date.month)),
+        ("day".into(), Object::Int(date.day)),
+        ("hour".into(), Object::Int(date.hour)),
+        ("minute".into(), Object::Int(date.minute)),
+        ("second".into(), Object::Int(date.second)),
+        ("weekday".into(), Object::Int(date.weekday)),
+        ("yearday".into(), Object::Int(date.yearday)),
+    ])))
+}
+
+// time.format(format: string, timestamp?: int | float) -> string
+//
+// Formats the timestamp (default: now) in UTC with the strftime-like specifiers:
+// `%Y` `%m` `%d` `%H` `%M` `%S` `%j` `%a` `%b` and `%%`.
+fn format(mut args: Args) -> Result<Object> {
+    let format = expect_string(args.next().unwrap(), "time.format", 0)?;
+    let date = DateTime::from_timestamp(timestamp(args.next(), "time.format", 1)?);
+
+    const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let mut output = String::new();
+    let mut chars = format.as_str().chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            output.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('Y') => write!(output, "{:04}", date.year).unwrap(),
+            Some('m') => write!(output, "{:02}", date.month).unwrap(),
+            Some('d') => write!(output, "{:02}", date.day).unwrap(),
+            Some('H') => write!(output, "{:02}", date.hour).unwrap(),
+            Some('M') => write!(output, "{:02}", date.minute).unwrap(),
+            Some('S') => write!(output, "{:02}", date.second).unwrap(),
+            Some('j') => write!(output, "{:03}", date.yearday).unwrap(),
+            Some('a') => output.push_str(WEEKDAYS[date.weekday as usize]),
+            Some('b') => output.push_str(MONTHS[date.month as usize - 1]),
+            Some('%') => output.push('%'),
+            Some(c) => return Err(anyhow!("time.format: unknown specifier '%{}'", c)),
+            None => return Err(anyhow!("time.format: incomplete specifier at the end")),
+        }
+    }
+    Ok(Object::String(output.as_str().into()))
+}
+
+/// Reads the optional timestamp argument as whole seconds since the Unix epoch.
+fn timestamp(value: Option<Object>, func: &str, index: usize) -> Result<i64> {
+    match value {
+        None => Ok(SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs() as i64)),
+        Some(Object::Int(int)) => Ok(int),
+        Some(Object::Float(float)) if float.is_finite() => Ok(float.floor() as i64),
+        Some(other) => Err(anyhow!(
+            "{}: expected argument {} of type 'int | float', got '{}'",
+            func,
+            index + 1,
+            other.type_name()
+        )),
+    }
+}
+
+struct DateTime {
+    year: i64,
+    month: i64,
+    day: i64,
+    hour: i64,
+    minute: i64,
+    second: i64,
+    weekday: i64,
+    yearday: i64,
+}
+
+impl DateTime {
+    fn from_timestamp(timestamp: i64) -> Self {
+        let days = timestamp.div_euclid(86400);
+        let secs = timestamp.rem_euclid(86400);
+
+        // Converts the days since 1970-01-01 to the proleptic Gregorian calendar date.
+        // See: http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+        let z = days + 719468;
+        let era = z.div_euclid(146097);
+        let doe = z.rem_euclid(146097);
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + i64::from(month <= 2);
+
+        let is_leap = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+        const DAYS_BEFORE_MONTH: [i64; 12] =
+            [0, 31, 59, 90, 120, 151, 181, 212, 243, 273, 304, 334];
+        let yearday = DAYS_BEFORE_MONTH[month as usize - 1] + day + i64::from(is_leap && month > 2);
+
+        DateTime {
+            year,
+            month,
+            day,
+            hour: secs / 3600,
+            minute: secs % 3600 / 60,
+            second: secs % 60,
+            // 1970-01-01 is Thursday.
+            weekday: (days + 4).rem_euclid(7),
+            yearday,
+        }
+    }
+}
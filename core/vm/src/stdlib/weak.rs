@@ -0,0 +1,21 @@
+use super::*;
+
+// weak(value: array | table) -> weakref
+pub(super) fn weak(mut args: Args) -> Result<Object> {
+    match args.next().unwrap() {
+        Object::Array(array) => Ok(Object::WeakRef((&array).into())),
+        Object::Table(table) => Ok(Object::WeakRef((&table).into())),
+        other => Err(anyhow!(
+            "weak: expected argument 0 of type 'array' or 'table', got '{}'",
+            other.type_name()
+        )),
+    }
+}
+
+// weak_table() -> table
+//
+// Returns an empty table with weak values. The arrays and tables stored in it are not kept alive
+// by the table, and read as nil once they are freed.
+pub(super) fn weak_table(_: Args) -> Result<Object> {
+    Ok(Object::Table(Table::with_weak_values()))
+}
@@ -0,0 +1,120 @@
+//! The tiered execution.
+//!
+//! A function called `HOT_CALL_COUNT` times is specialized: its generic instructions are rewritten
+//! in place to the specialized forms (`IntAdd`, `JumpIfNotIntLess`, `CallNumberMethod`, ...),
+//! which assume that the operands are ints, or that the receiver is a number. When the assumption
+//! fails at run time, the instruction is deoptimized, i.e. rewritten back to the generic form, and
+//! the generic form runs instead. A deoptimized instruction is never specialized again.
+
+use super::*;
+use builtin::{float, int};
+use std::{ops::Range, sync::LazyLock};
+
+/// The number of calls after which a function is specialized.
+pub(crate) const HOT_CALL_COUNT: u32 = 1000;
+
+/// The builtin methods of int and float. `CallNumberMethod` refers to a method by the index.
+#[rustfmt::skip]
+const NUMBER_METHOD_NAMES: [&str; 34] = [
+    "to_string",
+    "abs", "acos", "acosh", "asin", "asinh", "atan", "atan2", "atanh", "cbrt", "ceil", "clamp",
+    "cos", "cosh", "exp", "exp2", "floor", "fract", "ln", "log", "log10", "log2", "max", "min",
+    "pow", "round", "sin", "sinh", "sqrt", "tan", "tanh", "trunc",
+    "downto", "upto",
+];
+
+pub(crate) struct NumberMethod {
+    pub(crate) name: &'static str,
+    pub(crate) int: Option<int::Method>,
+    pub(crate) float: Option<float::Method>,
+}
+
+static NUMBER_METHODS: LazyLock<Box<[NumberMethod]>> = LazyLock::new(|| {
+    NUMBER_METHOD_NAMES
+        .iter()
+        .map(|name| NumberMethod {
+            name,
+            int: int::find_method(name),
+            float: float::find_method(name),
+        })
+        .collect()
+});
+
+/// Returns the method referred by `CallNumberMethod`.
+pub(crate) fn number_method(index: u16) -> &'static NumberMethod {
+    &NUMBER_METHODS[index as usize]
+}
+
+/// Specializes the code of the function `code` in `exe`.
+///
+/// The instructions may be being executed by the caller, which copies the operands out of them
+/// before calling the function. (See `loop_()`)
+pub(crate) fn specialize(exe: &Executable, code: Range<usize>) {
+    use ICode::*;
+
+    if !exe.mark_specialized(code.start) {
+        return;
+    }
+    for pc in code {
+        let specialized = match unsafe { exe.fetch(pc) } {
+            Add(dst, lhs, rhs) => IntAdd(*dst, *lhs, *rhs),
+            Sub(dst, lhs, rhs) => IntSub(*dst, *lhs, *rhs),
+            Mul(dst, lhs, rhs) => IntMul(*dst, *lhs, *rhs),
+            Less(dst, lhs, rhs) => IntLess(*dst, *lhs, *rhs),
+            LessEq(dst, lhs, rhs) => IntLessEq(*dst, *lhs, *rhs),
+            Greater(dst, lhs, rhs) => IntGreater(*dst, *lhs, *rhs),
+            GreaterEq(dst, lhs, rhs) => IntGreaterEq(*dst, *lhs, *rhs),
+            JumpIfNotLess(dst, lhs, rhs, offset) => JumpIfNotIntLess(*dst, *lhs, *rhs, *offset),
+            JumpIfNotLessEq(dst, lhs, rhs, offset) => JumpIfNotIntLessEq(*dst, *lhs, *rhs, *offset),
+            JumpIfNotGreater(dst, lhs, rhs, offset) => {
+                JumpIfNotIntGreater(*dst, *lhs, *rhs, *offset)
+            }
+            JumpIfNotGreaterEq(dst, lhs, rhs, offset) => {
+                JumpIfNotIntGreaterEq(*dst, *lhs, *rhs, *offset)
+            }
+            CallMethod(base, arg_len, name) => {
+                match NUMBER_METHOD_NAMES.iter().position(|n| *n == name.as_str()) {
+                    Some(index) => CallNumberMethod(*base, *arg_len, index as u16),
+                    None => continue,
+                }
+            }
+            _ => continue,
+        };
+        unsafe {
+            // SAFETY: `pc` is in bounds, and the old instruction is no longer referred.
+            exe.write(pc, specialized);
+        }
+    }
+}
+
+/// Rewrites the specialized instruction at `pc` back to the generic form.
+///
+/// The caller must not use the reference to the instruction at `pc` after this call.
+pub(crate) fn deoptimize(exe: &Executable, pc: usize) {
+    use ICode::*;
+
+    let generic = match unsafe { exe.fetch(pc) } {
+        IntAdd(dst, lhs, rhs) => Add(*dst, *lhs, *rhs),
+        IntSub(dst, lhs, rhs) => Sub(*dst, *lhs, *rhs),
+        IntMul(dst, lhs, rhs) => Mul(*dst, *lhs, *rhs),
+        IntLess(dst, lhs, rhs) => Less(*dst, *lhs, *rhs),
+        IntLessEq(dst, lhs, rhs) => LessEq(*dst, *lhs, *rhs),
+        IntGreater(dst, lhs, rhs) => Greater(*dst, *lhs, *rhs),
+        IntGreaterEq(dst, lhs, rhs) => GreaterEq(*dst, *lhs, *rhs),
+        JumpIfNotIntLess(dst, lhs, rhs, offset) => JumpIfNotLess(*dst, *lhs, *rhs, *offset),
+        JumpIfNotIntLessEq(dst, lhs, rhs, offset) => JumpIfNotLessEq(*dst, *lhs, *rhs, *offset),
+        JumpIfNotIntGreater(dst, lhs, rhs, offset) => JumpIfNotGreater(*dst, *lhs, *rhs, *offset),
+        JumpIfNotIntGreaterEq(dst, lhs, rhs, offset) => {
+            JumpIfNotGreaterEq(*dst, *lhs, *rhs, *offset)
+        }
+        CallNumberMethod(base, arg_len, index) => {
+            let name = MethodName::new(number_method(*index).name);
+            CallMethod(*base, *arg_len, name)
+        }
+        _ => return,
+    };
+    unsafe {
+        // SAFETY: `pc` is in bounds, and the caller does not use the old instruction.
+        exe.write(pc, generic);
+    }
+}